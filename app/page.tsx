@@ -435,6 +435,22 @@ export default function Home() {
       }
     });
 
+    // State changed by the backend (command-line remote control, rules)
+    const unlistenStateChanged = listen<OverlayStateResponse>(
+      "overlay-state-changed",
+      (event) => {
+        setState((prev) => ({
+          ...prev,
+          visible: event.payload.visible,
+          mode: event.payload.mode,
+          targetBound: event.payload.targetBound,
+          targetName: event.payload.targetName,
+          targetRect: event.payload.targetRect,
+          autoHidden: event.payload.autoHidden,
+        }));
+      }
+    );
+
     // Mode-changed event listener
     const unlistenModeChanged = listen<ModeChangePayload>("mode-changed", (event) => {
       console.log(
@@ -516,6 +532,7 @@ export default function Home() {
     return () => {
       unlistenVisibility.then((f) => f());
       unlistenMode.then((f) => f());
+      unlistenStateChanged.then((f) => f());
       unlistenModeChanged.then((f) => f());
       unlistenReady.then((f) => f());
       unlistenTargetChanged.then((f) => f());
@@ -0,0 +1,12 @@
+// Alarms module - reminders that fire at a set time, once or repeating
+// The schedule holds the alarms and decides when they fire; the service persists them,
+// fires due alarms (catching up on those missed while the app was closed) and pushes
+// changes to the frontend.
+
+pub mod schedule;
+pub mod service;
+pub mod types;
+
+// Re-exports for public API
+pub use service::*;
+pub use types::*;
@@ -0,0 +1,388 @@
+// Alarm schedule - the alarms and when they fire, independent of Tauri
+// Operations take the current time (epoch milliseconds) and the time zone used for
+// daily and weekly repeats, so the tests do not depend on the clock or the machine.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Days, TimeZone, Utc};
+
+use super::types::{Alarm, AlarmDue, AlarmFiredPayload, AlarmRepeat};
+
+/// Longest accepted alarm label
+const MAX_LABEL_LEN: usize = 100;
+
+/// Shortest accepted repeat interval
+const MIN_INTERVAL_SECS: u64 = 60;
+
+/// Longest accepted repeat interval (a year)
+const MAX_INTERVAL_SECS: u64 = 366 * 86_400;
+
+/// How far ahead an alarm can be set (ten years)
+const MAX_AHEAD_MS: u64 = 10 * 366 * 86_400_000;
+
+/// Alarms firing later than this after their due time are reported as late
+pub const LATE_AFTER_MS: u64 = 60_000;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AlarmSchedule {
+    alarms: BTreeMap<String, Alarm>,
+}
+
+fn to_datetime(ms: u64) -> Option<DateTime<Utc>> {
+    Utc.timestamp_millis_opt(i64::try_from(ms).ok()?).single()
+}
+
+/// Same local time of day, `days` days later
+fn add_local_days<Tz: TimeZone>(due_ms: u64, days: u64, tz: &Tz) -> Option<u64> {
+    let fallback = days
+        .checked_mul(86_400_000)
+        .and_then(|ms| due_ms.checked_add(ms));
+    let Some(local) = to_datetime(due_ms).and_then(|due| {
+        due.with_timezone(tz)
+            .naive_local()
+            .checked_add_days(Days::new(days))
+    }) else {
+        return fallback;
+    };
+    // A time skipped by a daylight saving change falls back to the same UTC offset
+    tz.from_local_datetime(&local)
+        .earliest()
+        .map(|next| next.timestamp_millis().max(0) as u64)
+        .or(fallback)
+}
+
+/// The occurrence after `due_ms`, or None if it cannot be represented (e.g. a corrupt
+/// alarms file with a huge interval)
+fn next_occurrence<Tz: TimeZone>(due_ms: u64, repeat: AlarmRepeat, tz: &Tz) -> Option<u64> {
+    match repeat {
+        AlarmRepeat::Interval { secs } => secs
+            .checked_mul(1000)
+            .and_then(|step| due_ms.checked_add(step)),
+        AlarmRepeat::Daily => add_local_days(due_ms, 1, tz),
+        AlarmRepeat::Weekly => add_local_days(due_ms, 7, tz),
+    }
+}
+
+impl AlarmSchedule {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let alarms: Vec<Alarm> =
+            serde_json::from_str(json).map_err(|e| format!("Invalid alarms file: {}", e))?;
+        Ok(Self {
+            alarms: alarms
+                .into_iter()
+                .map(|alarm| (alarm.id.clone(), alarm))
+                .collect(),
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        let alarms: Vec<&Alarm> = self.alarms.values().collect();
+        serde_json::to_string_pretty(&alarms)
+            .map_err(|e| format!("Failed to serialize alarms: {}", e))
+    }
+
+    /// Alarms ordered by due time, soonest first
+    pub fn upcoming(&self, limit: usize) -> Vec<Alarm> {
+        let mut alarms: Vec<Alarm> = self.alarms.values().cloned().collect();
+        alarms.sort_by(|a, b| a.due_at_ms.cmp(&b.due_at_ms).then(a.id.cmp(&b.id)));
+        alarms.truncate(limit);
+        alarms
+    }
+
+    pub fn add(
+        &mut self,
+        label: &str,
+        due: AlarmDue,
+        repeat: Option<AlarmRepeat>,
+        now_ms: u64,
+    ) -> Result<Alarm, String> {
+        let label = label.trim();
+        if label.is_empty() || label.chars().count() > MAX_LABEL_LEN {
+            return Err(format!(
+                "Alarm label must be 1 to {} characters long",
+                MAX_LABEL_LEN
+            ));
+        }
+        if let Some(AlarmRepeat::Interval { secs }) = repeat {
+            if !(MIN_INTERVAL_SECS..=MAX_INTERVAL_SECS).contains(&secs) {
+                return Err(format!(
+                    "Repeat interval must be between {} and {} seconds",
+                    MIN_INTERVAL_SECS, MAX_INTERVAL_SECS
+                ));
+            }
+        }
+        let due_at_ms = match due {
+            AlarmDue::At { at_ms } => at_ms,
+            AlarmDue::In { secs } => now_ms.saturating_add(secs.saturating_mul(1000)),
+        };
+        if due_at_ms <= now_ms {
+            return Err("Alarm due time is in the past".to_string());
+        }
+        if due_at_ms > now_ms.saturating_add(MAX_AHEAD_MS) {
+            return Err("Alarm due time is more than ten years ahead".to_string());
+        }
+
+        // Creation time as ID, with a suffix if several alarms are added in the same ms
+        let mut id = format!("alarm-{}", now_ms);
+        let mut suffix = 1;
+        while self.alarms.contains_key(&id) {
+            suffix += 1;
+            id = format!("alarm-{}-{}", now_ms, suffix);
+        }
+
+        let alarm = Alarm {
+            id: id.clone(),
+            label: label.to_string(),
+            due_at_ms,
+            repeat,
+            created_at_ms: now_ms,
+        };
+        self.alarms.insert(id, alarm.clone());
+        Ok(alarm)
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<(), String> {
+        self.alarms
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| format!("Unknown alarm '{}'", id))
+    }
+
+    /// Fire every alarm that is due. An alarm fires once even if several of its
+    /// occurrences passed (e.g. while the app was closed); repeating alarms move to their
+    /// next future occurrence and one-shot alarms are removed.
+    pub fn fire_due<Tz: TimeZone>(&mut self, now_ms: u64, tz: &Tz) -> Vec<AlarmFiredPayload> {
+        let due: Vec<String> = self
+            .alarms
+            .values()
+            .filter(|alarm| alarm.due_at_ms <= now_ms)
+            .map(|alarm| alarm.id.clone())
+            .collect();
+
+        let mut fired = Vec::new();
+        for id in due {
+            let Some(alarm) = self.alarms.get(&id).cloned() else {
+                continue;
+            };
+
+            let mut missed: u32 = 0;
+            // None for one-shot alarms, and for repeats whose next occurrence is out of range
+            let next_due_at_ms = alarm.repeat.and_then(|repeat| {
+                let mut next = next_occurrence(alarm.due_at_ms, repeat, tz)?;
+                while next <= now_ms {
+                    missed = missed.saturating_add(1);
+                    next = match repeat {
+                        // Skip straight past now instead of stepping through long gaps
+                        AlarmRepeat::Interval { secs } => {
+                            let step = secs.checked_mul(1000).filter(|&step| step > 0)?;
+                            let skipped = (now_ms - next) / step;
+                            missed = missed.saturating_add(skipped.try_into().unwrap_or(u32::MAX));
+                            (skipped + 1)
+                                .checked_mul(step)
+                                .and_then(|ms| next.checked_add(ms))?
+                        }
+                        _ => next_occurrence(next, repeat, tz)?,
+                    };
+                }
+                Some(next)
+            });
+            if alarm.repeat.is_some() && next_due_at_ms.is_none() {
+                log::warn!(
+                    "Alarm '{}' has no valid next occurrence, removing it",
+                    alarm.label
+                );
+            }
+
+            match next_due_at_ms {
+                Some(next) => {
+                    if let Some(stored) = self.alarms.get_mut(&id) {
+                        stored.due_at_ms = next;
+                    }
+                }
+                None => {
+                    self.alarms.remove(&id);
+                }
+            }
+
+            fired.push(AlarmFiredPayload {
+                id: alarm.id,
+                label: alarm.label,
+                due_at_ms: alarm.due_at_ms,
+                fired_at_ms: now_ms,
+                late: now_ms - alarm.due_at_ms > LATE_AFTER_MS,
+                missed,
+                next_due_at_ms,
+            });
+        }
+        fired.sort_by_key(|alarm| alarm.due_at_ms);
+        fired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    const HOUR_MS: u64 = 3_600_000;
+    const DAY_MS: u64 = 24 * HOUR_MS;
+
+    #[test]
+    fn test_add_validation() {
+        let mut schedule = AlarmSchedule::default();
+        let due = AlarmDue::In { secs: 60 };
+        assert!(schedule.add("  ", due, None, 0).is_err());
+        assert!(schedule
+            .add("x", AlarmDue::At { at_ms: 1_000 }, None, 5_000)
+            .is_err());
+        assert!(schedule
+            .add("x", due, Some(AlarmRepeat::Interval { secs: 5 }), 0)
+            .is_err());
+        assert!(schedule
+            .add("x", due, Some(AlarmRepeat::Interval { secs: u64::MAX }), 0)
+            .is_err());
+        assert!(schedule
+            .add("x", AlarmDue::At { at_ms: u64::MAX }, None, 0)
+            .is_err());
+        assert!(schedule
+            .add("x", AlarmDue::In { secs: u64::MAX }, None, 0)
+            .is_err());
+
+        let alarm = schedule
+            .add(" Refinery job ", AlarmDue::In { secs: 11_520 }, None, 1_000)
+            .unwrap();
+        assert_eq!(alarm.label, "Refinery job");
+        assert_eq!(alarm.due_at_ms, 1_000 + 11_520_000);
+
+        // Same creation time gets a distinct ID
+        let other = schedule.add("Hangar", due, None, 1_000).unwrap();
+        assert_ne!(other.id, alarm.id);
+        assert_eq!(
+            schedule
+                .upcoming(10)
+                .iter()
+                .map(|a| a.label.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Hangar", "Refinery job"]
+        );
+        assert_eq!(schedule.upcoming(1).len(), 1);
+
+        schedule.remove(&other.id).unwrap();
+        assert!(schedule.remove(&other.id).is_err());
+    }
+
+    #[test]
+    fn test_one_shot_fires_once() {
+        let mut schedule = AlarmSchedule::default();
+        let alarm = schedule
+            .add("Hangar", AlarmDue::In { secs: 10 }, None, 0)
+            .unwrap();
+
+        assert!(schedule.fire_due(9_999, &Utc).is_empty());
+        let fired = schedule.fire_due(10_500, &Utc);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].id, alarm.id);
+        assert!(!fired[0].late);
+        assert_eq!(fired[0].next_due_at_ms, None);
+
+        assert!(schedule.upcoming(10).is_empty());
+        assert!(schedule.fire_due(20_000, &Utc).is_empty());
+    }
+
+    #[test]
+    fn test_catch_up_after_downtime() {
+        let mut schedule = AlarmSchedule::default();
+        schedule
+            .add("Refinery", AlarmDue::At { at_ms: HOUR_MS }, None, 0)
+            .unwrap();
+        schedule
+            .add(
+                "Stretch",
+                AlarmDue::At { at_ms: HOUR_MS },
+                Some(AlarmRepeat::Interval { secs: 3600 }),
+                0,
+            )
+            .unwrap();
+
+        // The app was closed from before the first hour until 5.5 hours
+        let now = 5 * HOUR_MS + HOUR_MS / 2;
+        let fired = schedule.fire_due(now, &Utc);
+        assert_eq!(fired.len(), 2);
+        assert!(fired.iter().all(|f| f.late && f.due_at_ms == HOUR_MS));
+
+        let stretch = fired.iter().find(|f| f.label == "Stretch").unwrap();
+        // Hours 2 to 5 passed too; the next one is at 6
+        assert_eq!(stretch.missed, 4);
+        assert_eq!(stretch.next_due_at_ms, Some(6 * HOUR_MS));
+
+        let upcoming = schedule.upcoming(10);
+        assert_eq!(upcoming.len(), 1);
+        assert_eq!(upcoming[0].due_at_ms, 6 * HOUR_MS);
+    }
+
+    #[test]
+    fn test_daily_repeat_in_local_time() {
+        let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+        let mut schedule = AlarmSchedule::default();
+        // 08:00 local (06:00 UTC) on day 1
+        let due = DAY_MS + 6 * HOUR_MS;
+        schedule
+            .add(
+                "Daily reward",
+                AlarmDue::At { at_ms: due },
+                Some(AlarmRepeat::Daily),
+                0,
+            )
+            .unwrap();
+
+        let fired = schedule.fire_due(due, &tz);
+        assert_eq!(fired[0].missed, 0);
+        assert_eq!(fired[0].next_due_at_ms, Some(due + DAY_MS));
+
+        // Three days later: two occurrences missed
+        let fired = schedule.fire_due(due + 3 * DAY_MS + HOUR_MS, &tz);
+        assert_eq!(fired[0].missed, 2);
+        assert_eq!(fired[0].next_due_at_ms, Some(due + 4 * DAY_MS));
+    }
+
+    #[test]
+    fn test_out_of_range_alarms_from_file() {
+        // Values the add() checks reject, e.g. from a hand-edited alarms file
+        let json = r#"[
+            {"id": "a", "label": "Huge interval", "dueAtMs": 1000,
+             "repeat": {"every": "interval", "secs": 18446744073709551615}, "createdAtMs": 0},
+            {"id": "b", "label": "Zero interval", "dueAtMs": 1000,
+             "repeat": {"every": "interval", "secs": 0}, "createdAtMs": 0},
+            {"id": "c", "label": "Far future", "dueAtMs": 18446744073709551000,
+             "repeat": {"every": "daily"}, "createdAtMs": 0}
+        ]"#;
+        let mut schedule = AlarmSchedule::from_json(json).unwrap();
+
+        let fired = schedule.fire_due(DAY_MS, &Utc);
+        assert_eq!(fired.len(), 2);
+        assert!(fired.iter().all(|f| f.next_due_at_ms.is_none()));
+        assert_eq!(schedule.upcoming(10).len(), 1);
+
+        let fired = schedule.fire_due(u64::MAX, &Utc);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].next_due_at_ms, None);
+        assert!(schedule.upcoming(10).is_empty());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut schedule = AlarmSchedule::default();
+        schedule
+            .add(
+                "Weekly",
+                AlarmDue::In { secs: 60 },
+                Some(AlarmRepeat::Weekly),
+                0,
+            )
+            .unwrap();
+        let json = schedule.to_json().unwrap();
+        assert!(json.contains(r#""every": "weekly""#));
+        assert_eq!(AlarmSchedule::from_json(&json).unwrap(), schedule);
+        assert!(AlarmSchedule::from_json("{").is_err());
+    }
+}
@@ -0,0 +1,170 @@
+// Alarm service - schedules the alarms for the whole app
+// Alarms are stored in alarms.json in the app data directory after every change. A
+// scheduler thread fires due alarms as alarm-fired; alarms that came due while the app
+// was closed fire on the first check after startup. Recent fires are kept so a webview
+// that loads after them can still show them.
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use tauri::AppHandle;
+
+use super::schedule::AlarmSchedule;
+use super::types::*;
+use crate::persistence::app_data::{emit_event, AppDataFile};
+
+const ALARMS_FILE: &str = "alarms.json";
+
+/// How often the schedule is checked for due alarms
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of recent fires kept for list_fired_alarms
+const RECENT_FIRES: usize = 20;
+
+/// Alarms returned by list_alarms when no limit is given
+const DEFAULT_ALARM_LIMIT: u32 = 50;
+
+struct AlarmService {
+    app: AppHandle,
+    schedule: AlarmSchedule,
+    file: AppDataFile,
+    /// Most recent fires, newest last
+    recent: Vec<AlarmFiredPayload>,
+}
+
+/// The alarm service (None until start_alarm_service runs)
+static SERVICE: Mutex<Option<AlarmService>> = Mutex::new(None);
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+impl AlarmService {
+    fn save(&self) {
+        self.file.save(self.schedule.to_json());
+    }
+}
+
+/// Load the alarms and start the scheduler (call once at startup)
+pub fn start_alarm_service(app: &AppHandle) {
+    let file = AppDataFile::open(app, ALARMS_FILE);
+    let schedule = file.load(AlarmSchedule::from_json);
+    log::info!(
+        "Alarm service started with {} alarm(s)",
+        schedule.upcoming(usize::MAX).len()
+    );
+    *SERVICE.lock().unwrap_or_else(|p| p.into_inner()) = Some(AlarmService {
+        app: app.clone(),
+        schedule,
+        file,
+        recent: Vec::new(),
+    });
+
+    thread::spawn(|| loop {
+        fire_due_alarms();
+        thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+fn fire_due_alarms() {
+    let (app, fired, alarms) = {
+        let mut guard = SERVICE.lock().unwrap_or_else(|p| p.into_inner());
+        let Some(service) = guard.as_mut() else {
+            return;
+        };
+        let fired = service.schedule.fire_due(now_ms(), &chrono::Local);
+        if fired.is_empty() {
+            return;
+        }
+        service.save();
+        service.recent.extend(fired.iter().cloned());
+        let excess = service.recent.len().saturating_sub(RECENT_FIRES);
+        service.recent.drain(..excess);
+        (
+            service.app.clone(),
+            fired,
+            service.schedule.upcoming(usize::MAX),
+        )
+    };
+
+    for alarm in fired {
+        if alarm.late {
+            log::info!(
+                "Alarm '{}' fired late (due at {}, {} more missed)",
+                alarm.label,
+                alarm.due_at_ms,
+                alarm.missed
+            );
+        } else {
+            log::info!("Alarm '{}' fired", alarm.label);
+        }
+        emit_event(&app, ALARM_FIRED_EVENT, alarm);
+    }
+    emit_event(&app, ALARMS_UPDATED_EVENT, alarms);
+}
+
+/// Apply a change to the schedule, save, and push the new alarm list to the frontend
+fn update_schedule<T>(
+    apply: impl FnOnce(&mut AlarmSchedule, u64) -> Result<T, String>,
+) -> Result<T, String> {
+    let (app, result, alarms) = {
+        let mut guard = SERVICE.lock().unwrap_or_else(|p| p.into_inner());
+        let service = guard
+            .as_mut()
+            .ok_or_else(|| "Alarm service is not running".to_string())?;
+        let result = apply(&mut service.schedule, now_ms())?;
+        service.save();
+        (
+            service.app.clone(),
+            result,
+            service.schedule.upcoming(usize::MAX),
+        )
+    };
+
+    emit_event(&app, ALARMS_UPDATED_EVENT, alarms);
+    Ok(result)
+}
+
+// ============================================================================
+// Tauri commands
+// ============================================================================
+
+/// List the upcoming alarms, soonest first
+#[tauri::command]
+pub fn list_alarms(limit: Option<u32>) -> Vec<Alarm> {
+    let limit = limit.unwrap_or(DEFAULT_ALARM_LIMIT) as usize;
+    let guard = SERVICE.lock().unwrap_or_else(|p| p.into_inner());
+    guard
+        .as_ref()
+        .map(|service| service.schedule.upcoming(limit))
+        .unwrap_or_default()
+}
+
+/// Alarms fired since startup (at most the last 20), newest first
+#[tauri::command]
+pub fn list_fired_alarms() -> Vec<AlarmFiredPayload> {
+    let guard = SERVICE.lock().unwrap_or_else(|p| p.into_inner());
+    guard
+        .as_ref()
+        .map(|service| service.recent.iter().rev().cloned().collect())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn create_alarm(
+    label: String,
+    due: AlarmDue,
+    repeat: Option<AlarmRepeat>,
+) -> Result<Alarm, String> {
+    let alarm = update_schedule(|schedule, now| schedule.add(&label, due, repeat, now))?;
+    log::info!("Alarm '{}' created ({})", alarm.label, alarm.id);
+    Ok(alarm)
+}
+
+#[tauri::command]
+pub fn delete_alarm(id: String) -> Result<(), String> {
+    update_schedule(|schedule, _| schedule.remove(&id))?;
+    log::info!("Alarm {} deleted", id);
+    Ok(())
+}
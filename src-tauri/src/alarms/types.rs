@@ -0,0 +1,62 @@
+// Alarm types - stored alarms, creation requests and the events sent to the frontend
+
+use serde::{Deserialize, Serialize};
+
+// Events emitted by the alarm service
+pub const ALARM_FIRED_EVENT: &str = "alarm-fired";
+pub const ALARMS_UPDATED_EVENT: &str = "alarms-updated";
+
+/// Events emitted by the alarm service (forwarded by the control API)
+pub const ALARM_EVENT_NAMES: &[&str] = &[ALARM_FIRED_EVENT, ALARMS_UPDATED_EVENT];
+
+/// How an alarm repeats after it fires. Daily and weekly alarms keep their local time
+/// of day across daylight saving changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "every", rename_all = "camelCase")]
+pub enum AlarmRepeat {
+    Interval { secs: u64 },
+    Daily,
+    Weekly,
+}
+
+/// When a new alarm is due: at a point in time or after a delay from now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AlarmDue {
+    #[serde(rename_all = "camelCase")]
+    At { at_ms: u64 },
+    #[serde(rename_all = "camelCase")]
+    In { secs: u64 },
+}
+
+/// An alarm as stored in alarms.json (times are Unix epoch milliseconds)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Alarm {
+    pub id: String,
+    pub label: String,
+    /// Next time the alarm fires
+    pub due_at_ms: u64,
+    /// None for one-shot alarms, which are removed once they fire
+    #[serde(default)]
+    pub repeat: Option<AlarmRepeat>,
+    pub created_at_ms: u64,
+}
+
+/// Payload of alarm-fired
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlarmFiredPayload {
+    pub id: String,
+    pub label: String,
+    /// The occurrence that fired
+    pub due_at_ms: u64,
+    pub fired_at_ms: u64,
+    /// Fired after its due time, e.g. when catching up after the app was closed
+    pub late: bool,
+    /// Further occurrences of a repeating alarm that passed while the app was closed
+    /// (they are not fired separately)
+    pub missed: u32,
+    /// Next occurrence of a repeating alarm (None once a one-shot alarm has fired)
+    pub next_due_at_ms: Option<u64>,
+}
@@ -2,18 +2,21 @@
 // Extracted from lib.rs as part of modular refactor (Feature 055)
 
 use crate::browser;
-use crate::core::types::{ModeChangePayload, OverlayMode, OverlayStateResponse, TargetWindowInfo};
+use crate::core::types::{
+    DetectionDiagnostics, ModeChangePayload, OverlayMode, OverlayStateResponse, TargetWindowInfo,
+};
 use crate::core::OverlayState;
-#[cfg(windows)]
+use crate::platform::detection_diagnostics;
+#[cfg(any(windows, target_os = "linux"))]
 use crate::core::types::ShowErrorModalPayload;
-#[cfg(windows)]
+#[cfg(any(windows, target_os = "linux"))]
 use crate::core::types::TargetWindowChangedPayload;
 use tauri::Emitter;
 
-#[cfg(windows)]
+#[cfg(any(windows, target_os = "linux"))]
 use crate::core::window;
-#[cfg(windows)]
-use crate::platform::{process_monitor, target_window};
+#[cfg(any(windows, target_os = "linux"))]
+use crate::platform::{process_monitor, window_match, window_system};
 
 /// Set overlay visibility
 #[tauri::command]
@@ -54,22 +57,31 @@ pub fn get_overlay_state(state: tauri::State<'_, OverlayState>) -> OverlayStateR
 /// @feature 044-session-timer-widget
 #[tauri::command]
 pub fn is_target_process_running() -> bool {
-    #[cfg(windows)]
+    #[cfg(any(windows, target_os = "linux"))]
     {
         process_monitor::PROCESS_MONITOR_STATE.is_target_found()
     }
-    #[cfg(not(windows))]
+    #[cfg(not(any(windows, target_os = "linux")))]
     {
         false
     }
 }
 
-// T019: F3: Toggle visibility with target window validation (Windows)
-#[cfg(windows)]
+// F3: Toggle visibility (show/hide)
 #[tauri::command]
 pub async fn toggle_visibility(
     window: tauri::WebviewWindow,
     state: tauri::State<'_, OverlayState>,
+) -> Result<OverlayStateResponse, String> {
+    toggle_visibility_internal(&window, &state)
+}
+
+// T019: F3: Toggle visibility with target window validation (Windows and Linux/X11)
+// Shared by the command and the backend (remote commands, rules)
+#[cfg(any(windows, target_os = "linux"))]
+pub fn toggle_visibility_internal(
+    window: &tauri::WebviewWindow,
+    state: &OverlayState,
 ) -> Result<OverlayStateResponse, String> {
     use tauri::Manager;
 
@@ -113,12 +125,16 @@ pub async fn toggle_visibility(
         log::info!("F3 outcome: hidden overlay (user manually hidden)");
     } else {
         // T019, T020 (028): Check target window using three-point verification
-        let detection_result = target_window::find_target_window_verified();
+        let system = window_system::window_system();
+        let detection_result = window_system::detect_target(
+            &*system,
+            &window_match::get_target_criteria(),
+        );
 
         let target_hwnd = if detection_result.success {
-            // Extract the HWND from the matched window
+            // Extract the window handle from the matched window
             if let Some(ref matched) = detection_result.matched_window {
-                target_window::u64_to_hwnd(matched.hwnd)
+                matched.hwnd
             } else {
                 // Should not happen if success is true, but handle defensively
                 let _ = window::set_window_screen_center(&window, 420.0, 280.0);
@@ -192,10 +208,10 @@ pub async fn toggle_visibility(
         };
 
         // Check if target is focused
-        if !target_window::is_target_focused(target_hwnd) {
+        if !window_system::is_window_focused(&*system, target_hwnd) {
             // Target exists but not focused - don't show overlay
             // T036 (028): Log focus issue with foreground window info
-            let (fg_process, fg_title) = target_window::get_foreground_window_info();
+            let (fg_process, fg_title) = window_system::foreground_window_info(&*system);
             log::warn!(
                 "Target detected but not focused - overlay not shown. Foreground: process={}, title={}",
                 fg_process, fg_title
@@ -207,7 +223,10 @@ pub async fn toggle_visibility(
         log::info!("Target focused - showing overlay");
 
         // Get target window rect
-        let rect = match target_window::get_window_rect(target_hwnd) {
+        let rect = match system
+            .window_rect(target_hwnd)
+            .ok_or_else(|| "Target window no longer exists".to_string())
+        {
             Ok(r) => r,
             Err(e) => {
                 // Show window temporarily for error modal - centered on screen
@@ -221,7 +240,7 @@ pub async fn toggle_visibility(
                 let _ = window.emit(
                     "show-error-modal",
                     ShowErrorModalPayload {
-                        target_name: target_window::get_target_window_name().to_string(),
+                        target_name: window_match::get_target_window_name(),
                         message: format!("Failed to get target window position: {}", e),
                         auto_dismiss_ms: 5000,
                     },
@@ -231,9 +250,7 @@ pub async fn toggle_visibility(
         };
 
         // Store target binding
-        state
-            .target_binding
-            .set_hwnd(target_window::hwnd_to_u64(target_hwnd));
+        state.target_binding.set_hwnd(target_hwnd);
         state.target_binding.set_focused(true);
         state.target_binding.set_rect(Some(rect));
         state.target_binding.update_last_check();
@@ -285,12 +302,11 @@ pub async fn toggle_visibility(
     Ok(state.to_response())
 }
 
-// F3: Toggle visibility - non-Windows fallback
-#[cfg(not(windows))]
-#[tauri::command]
-pub async fn toggle_visibility(
-    window: tauri::WebviewWindow,
-    state: tauri::State<'_, OverlayState>,
+// F3: Toggle visibility - fallback for platforms without target detection
+#[cfg(not(any(windows, target_os = "linux")))]
+pub fn toggle_visibility_internal(
+    window: &tauri::WebviewWindow,
+    state: &OverlayState,
 ) -> Result<OverlayStateResponse, String> {
     let is_visible = state.is_visible();
 
@@ -320,6 +336,14 @@ pub async fn toggle_visibility(
 pub async fn toggle_mode(
     window: tauri::WebviewWindow,
     state: tauri::State<'_, OverlayState>,
+) -> Result<OverlayStateResponse, String> {
+    toggle_mode_internal(&window, &state)
+}
+
+// F5 logic shared by the command and the backend (remote commands, rules)
+pub fn toggle_mode_internal(
+    window: &tauri::WebviewWindow,
+    state: &OverlayState,
 ) -> Result<OverlayStateResponse, String> {
     let current_mode = state.get_mode();
 
@@ -384,26 +408,27 @@ pub async fn toggle_mode(
 
 // T054: Get target window info command
 // T015: Updated to use runtime settings for target window name
-#[cfg(windows)]
+#[cfg(any(windows, target_os = "linux"))]
 #[tauri::command]
 pub fn get_target_window_info(_state: tauri::State<'_, OverlayState>) -> TargetWindowInfo {
-    let target_name = target_window::get_target_window_name();
+    let target_name = window_match::get_target_window_name();
 
     // Try to find target window
-    match target_window::find_target_window() {
-        Ok(hwnd) => {
-            let focused = target_window::is_target_focused(hwnd);
-            let rect = target_window::get_window_rect(hwnd).ok();
+    match window_system::find_target_window_verified().matched_window {
+        Some(matched) => {
+            let system = window_system::window_system();
+            let focused = window_system::is_window_focused(&*system, matched.hwnd);
+            let rect = system.window_rect(matched.hwnd);
 
             TargetWindowInfo {
-                pattern: target_name.to_string(),
+                pattern: target_name,
                 found: true,
                 focused,
                 rect,
             }
         }
-        Err(_) => TargetWindowInfo {
-            pattern: target_name.to_string(),
+        None => TargetWindowInfo {
+            pattern: target_name,
             found: false,
             focused: false,
             rect: None,
@@ -411,7 +436,7 @@ pub fn get_target_window_info(_state: tauri::State<'_, OverlayState>) -> TargetW
     }
 }
 
-#[cfg(not(windows))]
+#[cfg(not(any(windows, target_os = "linux")))]
 #[tauri::command]
 pub fn get_target_window_info(_state: tauri::State<'_, OverlayState>) -> TargetWindowInfo {
     TargetWindowInfo {
@@ -422,6 +447,16 @@ pub fn get_target_window_info(_state: tauri::State<'_, OverlayState>) -> TargetW
     }
 }
 
+// Run target detection once and explain the outcome ("why didn't it attach")
+// Reports every evaluated window with per-criterion pass/fail for the settings window
+#[tauri::command]
+pub fn diagnose_target_detection() -> DetectionDiagnostics {
+    let result = crate::platform::window_system::find_target_window_verified();
+    let report = detection_diagnostics::diagnose_detection(&result);
+    log::info!("Detection diagnostics: {}", report.verdict);
+    report
+}
+
 // T043: Dismiss error modal command
 #[tauri::command]
 pub fn dismiss_error_modal(window: tauri::WebviewWindow) -> Result<(), String> {
@@ -1,7 +1,7 @@
 use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use std::sync::Mutex;
 
-#[cfg(windows)]
+#[cfg(any(windows, target_os = "linux"))]
 use super::types::TargetWindowState;
 use super::types::{OverlayMode, OverlayStateResponse, WindowState};
 
@@ -11,8 +11,8 @@ pub struct OverlayState {
     pub initialized: AtomicBool,
     mode: AtomicU8, // 0 = Windowed, 1 = Fullscreen
     saved_window_state: Mutex<Option<WindowState>>,
-    // T007: Target window binding state (Windows only)
-    #[cfg(windows)]
+    // T007: Target window binding state (Windows and Linux/X11)
+    #[cfg(any(windows, target_os = "linux"))]
     pub target_binding: TargetWindowState,
     // T007: Auto-hidden due to focus loss (not user toggle)
     pub auto_hidden: AtomicBool,
@@ -25,7 +25,7 @@ impl Default for OverlayState {
             initialized: AtomicBool::new(false),
             mode: AtomicU8::new(0), // Default: Windowed
             saved_window_state: Mutex::new(None),
-            #[cfg(windows)]
+            #[cfg(any(windows, target_os = "linux"))]
             target_binding: TargetWindowState::default(),
             auto_hidden: AtomicBool::new(false),
         }
@@ -95,9 +95,9 @@ impl OverlayState {
 
     // T012: Convert state to response
     // T015: Updated to use runtime settings for target window name
-    #[cfg(windows)]
+    #[cfg(any(windows, target_os = "linux"))]
     pub fn to_response(&self) -> OverlayStateResponse {
-        use crate::platform::target_window::get_target_window_name;
+        use crate::platform::window_match::get_target_window_name;
 
         let mode_str = match self.get_mode() {
             OverlayMode::Windowed => "windowed",
@@ -114,7 +114,7 @@ impl OverlayState {
         }
     }
 
-    #[cfg(not(windows))]
+    #[cfg(not(any(windows, target_os = "linux")))]
     pub fn to_response(&self) -> OverlayStateResponse {
         let mode_str = match self.get_mode() {
             OverlayMode::Windowed => "windowed",
@@ -44,7 +44,7 @@ pub struct SetVisibilityParams {
 }
 
 // T004: Updated OverlayStateResponse to include mode field and target binding
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OverlayStateResponse {
     pub visible: bool,
@@ -110,7 +110,7 @@ impl std::fmt::Display for TargetWindowError {
 impl std::error::Error for TargetWindowError {}
 
 // T006: TargetWindowState struct for tracking target window binding
-#[cfg(windows)]
+#[cfg(any(windows, target_os = "linux"))]
 pub struct TargetWindowState {
     pub hwnd: std::sync::atomic::AtomicU64,
     pub is_focused: std::sync::atomic::AtomicBool,
@@ -118,7 +118,7 @@ pub struct TargetWindowState {
     pub last_check: std::sync::Mutex<std::time::Instant>,
 }
 
-#[cfg(windows)]
+#[cfg(any(windows, target_os = "linux"))]
 impl Default for TargetWindowState {
     fn default() -> Self {
         Self {
@@ -130,7 +130,7 @@ impl Default for TargetWindowState {
     }
 }
 
-#[cfg(windows)]
+#[cfg(any(windows, target_os = "linux"))]
 impl TargetWindowState {
     pub fn get_hwnd(&self) -> u64 {
         self.hwnd.load(std::sync::atomic::Ordering::SeqCst)
@@ -199,6 +199,14 @@ pub struct AutoHideChangedPayload {
     pub reason: String,
 }
 
+/// Payload of target-focus-changed, emitted whenever the bound target window (or the
+/// overlay) gains or loses focus, whether or not the overlay is shown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetFocusChangedPayload {
+    pub focused: bool,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct TargetWindowInfo {
     pub pattern: String,
@@ -229,6 +237,8 @@ pub struct WindowCandidate {
 // T005 (028): SearchCriteria for window detection configuration
 #[derive(Debug, Clone, Serialize)]
 pub struct SearchCriteria {
+    /// Target profile these criteria belong to (e.g., "LIVE", "PTU")
+    pub profile_name: String,
     /// Target process name (e.g., "StarCitizen.exe")
     pub process_name: String,
     /// Target window class (e.g., "CryENGINE")
@@ -237,6 +247,25 @@ pub struct SearchCriteria {
     pub window_title: String,
 }
 
+// CandidateScore - per-field match results and weighted score of a candidate for one profile
+#[derive(Debug, Clone, Serialize)]
+pub struct CandidateScore {
+    /// Window handle of the candidate
+    pub hwnd: u64,
+    /// Profile the candidate was scored against
+    pub profile_name: String,
+    pub process_match: bool,
+    pub class_match: bool,
+    pub title_match: bool,
+    /// Title equals a plain-text title rule exactly
+    pub title_exact: bool,
+    pub is_top_level: bool,
+    /// Weighted sum of the matched fields
+    pub score: u32,
+    /// All fields matched and the window is top-level
+    pub valid: bool,
+}
+
 // T006 (028): DetectionResult - outcome of a window detection attempt
 #[derive(Debug, Clone, Serialize)]
 pub struct DetectionResult {
@@ -246,8 +275,57 @@ pub struct DetectionResult {
     pub matched_window: Option<WindowCandidate>,
     /// All candidates that were checked
     pub candidates_evaluated: Vec<WindowCandidate>,
-    /// The criteria used for detection
+    /// The criteria used for detection (of the matched profile, or the first profile)
     pub search_criteria: SearchCriteria,
+    /// Name of the target profile that matched (if success)
+    pub matched_profile: Option<String>,
+    /// Criteria of every profile tried, in priority order
+    pub profiles_evaluated: Vec<SearchCriteria>,
+    /// Per-field match results of candidates that matched at least one field
+    pub candidate_scores: Vec<CandidateScore>,
+    /// Time taken for detection in milliseconds
+    pub detection_time_ms: u64,
+}
+
+// CriterionCheck - pass/fail of one search criterion for a candidate window
+#[derive(Debug, Clone, Serialize)]
+pub struct CriterionCheck {
+    /// Criterion name: "process", "class" or "title"
+    pub criterion: String,
+    /// Rule from the target profile (e.g., "CryENGINE", "glob:StarCitizen*.exe")
+    pub expected: String,
+    /// Value of the candidate window
+    pub actual: String,
+    pub passed: bool,
+}
+
+// CandidateDiagnosis - a candidate checked against the profile it comes closest to
+#[derive(Debug, Clone, Serialize)]
+pub struct CandidateDiagnosis {
+    pub window: WindowCandidate,
+    /// Profile the checks were made against
+    pub profile_name: String,
+    pub checks: Vec<CriterionCheck>,
+    /// Weighted score against that profile
+    pub score: u32,
+    /// All criteria passed and the window is top-level
+    pub valid: bool,
+    /// The overlay attaches to this window
+    pub selected: bool,
+}
+
+// DetectionDiagnostics - "why didn't it attach" report for one detection run
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectionDiagnostics {
+    pub success: bool,
+    /// Human-readable summary (e.g., "process found, class mismatch: ...")
+    pub verdict: String,
+    pub matched_profile: Option<String>,
+    pub matched_window: Option<WindowCandidate>,
+    /// Criteria of every profile tried, in priority order
+    pub criteria: Vec<SearchCriteria>,
+    /// Every evaluated window, selected window and closest matches first
+    pub candidates: Vec<CandidateDiagnosis>,
     /// Time taken for detection in milliseconds
     pub detection_time_ms: u64,
 }
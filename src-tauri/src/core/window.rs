@@ -153,11 +153,11 @@ pub fn get_default_windowed_state(window: &WebviewWindow) -> Result<WindowState,
 }
 
 // T018: Sync overlay position and size to target window
-#[cfg(windows)]
+#[cfg(any(windows, target_os = "linux"))]
 use super::types::WindowRect;
 
 // T046: Sync overlay position and size to target window with timing instrumentation
-#[cfg(windows)]
+#[cfg(any(windows, target_os = "linux"))]
 pub fn sync_overlay_to_target(window: &WebviewWindow, rect: &WindowRect) -> Result<(), String> {
     use std::time::Instant;
     use tauri::PhysicalPosition;
@@ -165,7 +165,7 @@ pub fn sync_overlay_to_target(window: &WebviewWindow, rect: &WindowRect) -> Resu
 
     let start = Instant::now();
 
-    // Use physical pixels since WindowRect comes from Windows API in physical coords
+    // Use physical pixels since WindowRect comes from Windows API / X11 in physical coords
     window
         .set_position(tauri::Position::Physical(PhysicalPosition::new(
             rect.x, rect.y,
@@ -0,0 +1,78 @@
+// Locating Game.log
+// The game writes Game.log into its channel folder (LIVE, PTU, ...), one level above
+// Bin64\StarCitizen.exe. game_log_path in settings.toml overrides the lookup.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::core::types::SearchCriteria;
+use crate::platform::window_system::{detect_target, WindowSystem};
+use crate::settings;
+
+/// File name of the game log
+pub const GAME_LOG_FILE: &str = "Game.log";
+
+/// Possible Game.log paths for the game executable at `exe`, most likely first
+pub fn candidates_for_executable(exe: &Path) -> Vec<PathBuf> {
+    let Some(dir) = exe.parent() else {
+        return Vec::new();
+    };
+    let mut candidates = Vec::new();
+    let in_bin64 = dir
+        .file_name()
+        .is_some_and(|name| name.eq_ignore_ascii_case("Bin64"));
+    if let (true, Some(channel_dir)) = (in_bin64, dir.parent()) {
+        candidates.push(channel_dir.join(GAME_LOG_FILE));
+    }
+    candidates.push(dir.join(GAME_LOG_FILE));
+    candidates
+}
+
+/// Find Game.log: the configured path, or the one next to the running game
+pub fn locate_game_log(system: &dyn WindowSystem, profiles: &[SearchCriteria]) -> Option<PathBuf> {
+    if let Some(path) = settings::get_game_log_path() {
+        return Some(path);
+    }
+
+    let window = detect_target(system, profiles).matched_window?;
+    let Some(exe) = system.process_path(window.hwnd) else {
+        log::debug!(
+            "Executable path of {} is unknown; set game_log_path in settings.toml",
+            window.process_name
+        );
+        return None;
+    };
+    candidates_for_executable(&exe)
+        .into_iter()
+        .find(|path| path.is_file())
+}
+
+/// When the running game started, if the window system can tell
+pub fn game_start_time(
+    system: &dyn WindowSystem,
+    profiles: &[SearchCriteria],
+) -> Option<SystemTime> {
+    let window = detect_target(system, profiles).matched_window?;
+    system.process_start_time(window.hwnd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates_for_executable() {
+        let exe = Path::new("/games/StarCitizen/LIVE/Bin64/StarCitizen.exe");
+        assert_eq!(
+            candidates_for_executable(exe),
+            vec![
+                PathBuf::from("/games/StarCitizen/LIVE/Game.log"),
+                PathBuf::from("/games/StarCitizen/LIVE/Bin64/Game.log"),
+            ]
+        );
+        assert_eq!(
+            candidates_for_executable(Path::new("/opt/game/Game.exe")),
+            vec![PathBuf::from("/opt/game/Game.log")]
+        );
+    }
+}
@@ -0,0 +1,14 @@
+// Game.log module - follows the game's log file and turns known lines into game events
+// The parser is pure (lines in, events out); the watcher finds and tails Game.log while
+// the target process runs and emits the events to the frontend.
+
+pub mod locate;
+pub mod parser;
+pub mod tailer;
+pub mod types;
+pub mod watcher;
+
+// Re-exports for public API
+pub use parser::GameLogParser;
+pub use types::*;
+pub use watcher::*;
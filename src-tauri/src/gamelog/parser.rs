@@ -0,0 +1,341 @@
+// Game.log line parser
+// Pure and stateful: lines go in, GameEvents come out. The state only carries what a
+// single line does not say (player handle, current location, selected quantum destination).
+//
+// Recognized lines (the bracketed tags are what the game prints; wording changes between
+// game patches are handled here and covered by the fixture in fixtures/):
+//   <Legacy login response> ... Handle[..]                            local player handle
+//   <Join PU> address[..] port[..] shard[..]                         session start
+//   <RequestLocationInventory> Player[..] ... Location[..]            location change
+//   <Actor Death> CActor::Kill: 'victim' [id] in zone '..' killed by 'killer' [id]
+//       using 'weapon' [..] with damage type '..'                     death (of the player)
+//   <Player Selected Quantum Target ...> ... selected point X as their destination
+//   <Jump Drive State Changed> Now Traveling                          quantum jump start
+//   <Quantum Drive Arrived>                                           quantum jump end
+//   <Channel Disconnected> ... reason="..."                           disconnect
+
+use regex::Regex;
+
+use super::types::{
+    DisconnectedPayload, GameEvent, LocationChangedPayload, PlayerDeathPayload, QuantumPhase,
+    QuantumTravelPayload, SessionStartedPayload,
+};
+
+/// First line of every Game.log
+const LOG_START: &str = "Log started on";
+
+lazy_static::lazy_static! {
+    static ref ACTOR_DEATH: Regex = Regex::new(
+        r"CActor::Kill: '([^']*)' \[\d+\] in zone '([^']*)' killed by '([^']*)' \[\d+\] using '([^']*)' \[[^\]]*\] with damage type '([^']*)'"
+    )
+    .expect("valid actor death pattern");
+    static ref QUANTUM_TARGET: Regex =
+        Regex::new(r"selected point (\S+) as their destination").expect("valid quantum pattern");
+    static ref DISCONNECT_REASON: Regex =
+        Regex::new(r#"reason="([^"]*)""#).expect("valid disconnect pattern");
+}
+
+/// Turns Game.log lines into GameEvents
+#[derive(Debug, Default)]
+pub struct GameLogParser {
+    /// Handle of the local player, from the login (or the first inventory request)
+    player: Option<String>,
+    location: Option<String>,
+    quantum_destination: Option<String>,
+}
+
+impl GameLogParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the session state (a new Game.log was started)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Parse every line, in order
+    pub fn parse_lines<'a>(&mut self, lines: impl IntoIterator<Item = &'a str>) -> Vec<GameEvent> {
+        lines
+            .into_iter()
+            .filter_map(|line| self.parse_line(line))
+            .collect()
+    }
+
+    /// Parse one line. Returns None for lines that are not a known event.
+    pub fn parse_line(&mut self, line: &str) -> Option<GameEvent> {
+        let line = line.trim_end();
+        if line.starts_with(LOG_START) {
+            self.reset();
+            return None;
+        }
+        let timestamp = timestamp(line);
+
+        if line.contains("<Legacy login response>") {
+            if let Some(handle) = bracket_field(line, "Handle") {
+                self.player = Some(handle.to_string());
+            }
+            return None;
+        }
+
+        if line.contains("<Join PU>") {
+            // A new server session; the player stays logged in
+            *self = Self {
+                player: self.player.take(),
+                ..Self::default()
+            };
+            let server = match (bracket_field(line, "address"), bracket_field(line, "port")) {
+                (Some(address), Some(port)) => Some(format!("{}:{}", address, port)),
+                (address, _) => address.map(String::from),
+            };
+            return Some(GameEvent::SessionStarted(SessionStartedPayload {
+                timestamp,
+                shard: bracket_field(line, "shard").map(String::from),
+                server,
+            }));
+        }
+
+        if line.contains("<RequestLocationInventory>") {
+            if self.player.is_none() {
+                self.player = bracket_field(line, "Player").map(String::from);
+            }
+            let location = bracket_field(line, "Location")?.to_string();
+            if self.location.as_ref() == Some(&location) {
+                return None;
+            }
+            let previous_location = self.location.replace(location.clone());
+            return Some(GameEvent::LocationChanged(LocationChangedPayload {
+                timestamp,
+                location,
+                previous_location,
+            }));
+        }
+
+        if line.contains("<Actor Death>") {
+            let captures = ACTOR_DEATH.captures(line)?;
+            // Only the local player's deaths (not NPCs or other players)
+            if self.player.as_deref() != Some(&captures[1]) {
+                return None;
+            }
+            let field = |index: usize| captures[index].to_string();
+            return Some(GameEvent::PlayerDeath(PlayerDeathPayload {
+                timestamp,
+                victim: field(1),
+                zone: field(2),
+                killer: field(3),
+                weapon: field(4),
+                damage_type: field(5),
+            }));
+        }
+
+        let phase = if line.contains("<Player Selected Quantum Target") {
+            let destination = QUANTUM_TARGET.captures(line)?[1].to_string();
+            self.quantum_destination = Some(destination);
+            QuantumPhase::Selected
+        } else if line.contains("<Jump Drive State Changed>") && line.contains("Now Traveling") {
+            QuantumPhase::Started
+        } else if line.contains("<Quantum Drive Arrived") {
+            QuantumPhase::Arrived
+        } else if line.contains("<Channel Disconnected>") {
+            return Some(GameEvent::Disconnected(DisconnectedPayload {
+                timestamp,
+                reason: DISCONNECT_REASON
+                    .captures(line)
+                    .map(|captures| captures[1].to_string()),
+            }));
+        } else {
+            return None;
+        };
+
+        let destination = match phase {
+            QuantumPhase::Arrived => self.quantum_destination.take(),
+            _ => self.quantum_destination.clone(),
+        };
+        Some(GameEvent::QuantumTravel(QuantumTravelPayload {
+            timestamp,
+            phase,
+            destination,
+        }))
+    }
+}
+
+/// Timestamp at the start of a line ("<2024-05-11T18:21:06.512Z> ...")
+fn timestamp(line: &str) -> Option<String> {
+    let rest = line.strip_prefix('<')?;
+    let (timestamp, _) = rest.split_once('>')?;
+    let is_timestamp = timestamp.len() >= 10
+        && timestamp.as_bytes()[..4].iter().all(u8::is_ascii_digit)
+        && timestamp.contains('T');
+    is_timestamp.then(|| timestamp.to_string())
+}
+
+/// Value of a `name[value]` field (None if missing or empty)
+fn bracket_field<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let start = line
+        .match_indices(&format!("{}[", name))
+        // Only whole field names, not the end of a longer word
+        .find(|(index, _)| {
+            line[..*index]
+                .chars()
+                .next_back()
+                .is_none_or(|c| !c.is_alphanumeric())
+        })?
+        .0
+        + name.len()
+        + 1;
+    let value = &line[start..start + line[start..].find(']')?];
+    (!value.is_empty()).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSION_LOG: &str = include_str!("fixtures/session.log");
+
+    fn events(log: &str) -> Vec<GameEvent> {
+        GameLogParser::new().parse_lines(log.lines())
+    }
+
+    #[test]
+    fn test_parse_session_fixture() {
+        let events = events(SESSION_LOG);
+        let names: Vec<_> = events.iter().map(GameEvent::event_name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "game-session-started",
+                "game-location-changed",
+                "game-quantum-travel",
+                "game-quantum-travel",
+                "game-quantum-travel",
+                "game-location-changed",
+                "game-player-death",
+                "game-disconnected",
+            ]
+        );
+
+        assert_eq!(
+            events[0],
+            GameEvent::SessionStarted(SessionStartedPayload {
+                timestamp: Some("2024-05-11T18:21:06.512Z".to_string()),
+                shard: Some("pub_use1b_8932401_110".to_string()),
+                server: Some("34.150.0.1:64314".to_string()),
+            })
+        );
+        let GameEvent::LocationChanged(location) = &events[5] else {
+            panic!("expected a location change");
+        };
+        assert_eq!(location.location, "Stanton1b_Aberdeen_Klescher");
+        assert_eq!(
+            location.previous_location.as_deref(),
+            Some("Stanton1_Lorville")
+        );
+
+        let GameEvent::QuantumTravel(arrived) = &events[4] else {
+            panic!("expected quantum travel");
+        };
+        assert_eq!(arrived.phase, QuantumPhase::Arrived);
+        assert_eq!(
+            arrived.destination.as_deref(),
+            Some("OOC_Stanton_1b_Aberdeen")
+        );
+
+        assert_eq!(
+            events[6],
+            GameEvent::PlayerDeath(PlayerDeathPayload {
+                timestamp: Some("2024-05-11T18:40:12.001Z".to_string()),
+                victim: "PilotOne".to_string(),
+                killer: "PirateTwo".to_string(),
+                zone: "ANVL_Arrow_1234567".to_string(),
+                weapon: "KLWE_LaserRepeater_S3_1234".to_string(),
+                damage_type: "VehicleDestruction".to_string(),
+            })
+        );
+        assert_eq!(
+            events[7],
+            GameEvent::Disconnected(DisconnectedPayload {
+                timestamp: Some("2024-05-11T19:02:55.420Z".to_string()),
+                reason: Some("Connection timed out".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_repeated_location_and_new_log() {
+        let line = "<2024-05-11T18:25:44.310Z> [Notice] <RequestLocationInventory> Player[PilotOne] requested inventory for Location[Stanton1_Lorville] [Team_InvArch][Inventory]";
+        let mut parser = GameLogParser::new();
+        assert!(parser.parse_line(line).is_some());
+        assert_eq!(parser.parse_line(line), None);
+
+        // A new log starts a new session state
+        assert_eq!(
+            parser.parse_line("Log started on Sat May 11 18:20:01 2024"),
+            None
+        );
+        assert!(parser.parse_line(line).is_some());
+    }
+
+    #[test]
+    fn test_only_player_deaths() {
+        let death = |victim: &str| {
+            format!("<2024-05-11T18:40:12.001Z> [Notice] <Actor Death> CActor::Kill: '{}' [201978614731] in zone 'Stanton1' killed by 'PirateTwo' [200146297631] using 'Gun' [Class unknown] with damage type 'Bullet'", victim)
+        };
+        let mut parser = GameLogParser::new();
+        // Unknown player: no death is attributed
+        assert_eq!(parser.parse_line(&death("PilotOne")), None);
+
+        parser.parse_line("<2024-05-11T18:21:00.000Z> [Notice] <Legacy login response> [CIG-net] User Login Success - Handle[PilotOne] [Team_GameServices][Login]");
+        // Joining a server keeps the handle
+        parser.parse_line("<2024-05-11T18:21:06.512Z> [Notice] <Join PU> address[34.150.0.1] port[64314] shard[pub]");
+        assert_eq!(parser.parse_line(&death("PilotTwo")), None);
+        assert!(matches!(
+            parser.parse_line(&death("PilotOne")),
+            Some(GameEvent::PlayerDeath(_))
+        ));
+
+        // Without a login line the inventory requests name the player
+        let mut parser = GameLogParser::new();
+        parser.parse_line("<2024-05-11T18:25:44.310Z> [Notice] <RequestLocationInventory> Player[PilotOne] requested inventory for Location[Stanton1_Lorville]");
+        assert!(parser.parse_line(&death("PilotOne")).is_some());
+    }
+
+    #[test]
+    fn test_unknown_and_malformed_lines() {
+        let mut parser = GameLogParser::new();
+        assert_eq!(parser.parse_line(""), None);
+        assert_eq!(
+            parser.parse_line("<2024-05-11T18:21:00.000Z> [Notice] <Legacy login response> [CIG-net] User Login Success"),
+            None
+        );
+        // Known tag but missing fields
+        assert_eq!(
+            parser.parse_line(
+                "<2024-05-11T18:40:12.001Z> [Notice] <Actor Death> CActor::Kill: truncated"
+            ),
+            None
+        );
+        assert_eq!(
+            parser.parse_line(
+                "<2024-05-11T18:25:44.310Z> [Notice] <RequestLocationInventory> Location[]"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(
+            timestamp("<2024-05-11T18:21:06.512Z> [Notice]").as_deref(),
+            Some("2024-05-11T18:21:06.512Z")
+        );
+        assert_eq!(timestamp("<Join PU> address[1]"), None);
+        let line = "<Join PU> address[34.150.0.1] port[64314] shard[pub_use1b]";
+        assert_eq!(bracket_field(line, "port"), Some("64314"));
+        assert_eq!(bracket_field(line, "dress"), None);
+        assert_eq!(
+            bracket_field("Player[PilotOne] Location[Area18]", "Location"),
+            Some("Area18")
+        );
+    }
+}
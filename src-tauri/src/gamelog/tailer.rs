@@ -0,0 +1,201 @@
+// Follows a growing log file, returning complete new lines on each poll
+// Handles the file being truncated or replaced (the game moves Game.log to logbackups/
+// and starts a new one on every launch) by reading the new file from the start.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// New content of the log since the last poll
+#[derive(Debug, Default, PartialEq)]
+pub struct TailChunk {
+    /// The file was truncated or replaced; lines start at the top of a new log
+    pub restarted: bool,
+    /// Complete lines, without line endings
+    pub lines: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct LogTailer {
+    path: PathBuf,
+    /// Bytes of the current file already read
+    offset: u64,
+    /// Creation time of the current file, to notice replacements that are not shorter
+    created: Option<SystemTime>,
+    /// Trailing text of the last read that did not end with a newline yet
+    partial: Vec<u8>,
+}
+
+impl LogTailer {
+    /// Follow `path` from its current end (only lines written from now on)
+    pub fn from_end(path: &Path) -> Self {
+        let metadata = std::fs::metadata(path).ok();
+        Self {
+            path: path.to_path_buf(),
+            offset: metadata.as_ref().map_or(0, |m| m.len()),
+            created: metadata.and_then(|m| m.created().ok()),
+            partial: Vec::new(),
+        }
+    }
+
+    /// Follow `path` from its first line
+    pub fn from_start(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            offset: 0,
+            created: std::fs::metadata(path).and_then(|m| m.created()).ok(),
+            partial: Vec::new(),
+        }
+    }
+
+    /// Follow the log of a process that started at `process_started`. A log written since
+    /// then is read from its first line (the game writes "Log started" and the login before
+    /// its window appears); an older one, left by a previous run, from its end. Without a
+    /// start time only new lines are read.
+    pub fn for_process(path: &Path, process_started: Option<SystemTime>) -> Self {
+        let written = std::fs::metadata(path)
+            .ok()
+            .and_then(|m| m.created().or_else(|_| m.modified()).ok());
+        match (written, process_started) {
+            (Some(written), Some(started)) if written >= started => Self::from_start(path),
+            _ => Self::from_end(path),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read what was appended since the last poll. A missing file (e.g. while the game
+    /// rotates it) yields no lines.
+    pub fn poll(&mut self) -> Result<TailChunk, String> {
+        let mut chunk = TailChunk::default();
+        let Ok(metadata) = std::fs::metadata(&self.path) else {
+            return Ok(chunk);
+        };
+
+        let created = metadata.created().ok();
+        let replaced = created.is_some() && self.created.is_some() && created != self.created;
+        if metadata.len() < self.offset || replaced {
+            log::debug!(
+                "{:?} was truncated or replaced, reading from the start",
+                self.path
+            );
+            self.offset = 0;
+            self.partial.clear();
+            chunk.restarted = true;
+        }
+        self.created = created;
+
+        if metadata.len() == self.offset {
+            return Ok(chunk);
+        }
+
+        let mut file =
+            File::open(&self.path).map_err(|e| format!("Failed to open {:?}: {}", self.path, e))?;
+        file.seek(SeekFrom::Start(self.offset))
+            .map_err(|e| format!("Failed to seek in {:?}: {}", self.path, e))?;
+        let mut buffer = Vec::new();
+        let read = file
+            .read_to_end(&mut buffer)
+            .map_err(|e| format!("Failed to read {:?}: {}", self.path, e))?;
+        self.offset += read as u64;
+
+        self.partial.extend_from_slice(&buffer);
+        let Some(end) = self.partial.iter().rposition(|&b| b == b'\n') else {
+            return Ok(chunk);
+        };
+        let complete: Vec<u8> = self.partial.drain(..=end).collect();
+        chunk.lines = String::from_utf8_lossy(&complete)
+            .lines()
+            .map(|line| line.trim_end_matches('\r').to_string())
+            .collect();
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::test_support::TempDir;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+    use std::time::Duration;
+
+    // Path of a Game.log in a new directory, removed with the returned TempDir
+    fn temp_log(name: &str) -> (TempDir, PathBuf) {
+        let dir = TempDir::new(&format!("gamelog-{}", name));
+        let path = dir.join("Game.log");
+        (dir, path)
+    }
+
+    fn append(path: &Path, text: &str) {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_follows_appended_lines() {
+        let (_dir, path) = temp_log("append");
+        append(&path, "old line\n");
+
+        let mut tailer = LogTailer::from_end(&path);
+        assert_eq!(tailer.poll().unwrap(), TailChunk::default());
+
+        append(&path, "first\r\nsecond\nthi");
+        assert_eq!(tailer.poll().unwrap().lines, vec!["first", "second"]);
+        // The partial line is returned once it is complete
+        append(&path, "rd\n");
+        assert_eq!(tailer.poll().unwrap().lines, vec!["third"]);
+    }
+
+    #[test]
+    fn test_log_of_running_process_is_read_from_start() {
+        let (_dir, path) = temp_log("process");
+        append(&path, "Log started\n");
+        let written = fs::metadata(&path).unwrap().modified().unwrap();
+
+        // Created after the game started: its earlier lines are part of this session
+        let started = written - Duration::from_secs(5);
+        let mut tailer = LogTailer::for_process(&path, Some(started));
+        assert_eq!(tailer.poll().unwrap().lines, vec!["Log started"]);
+
+        // Older than the game: left by the previous run
+        let started = written + Duration::from_secs(5);
+        let mut tailer = LogTailer::for_process(&path, Some(started));
+        assert!(tailer.poll().unwrap().lines.is_empty());
+
+        let mut tailer = LogTailer::for_process(&path, None);
+        assert!(tailer.poll().unwrap().lines.is_empty());
+    }
+
+    #[test]
+    fn test_truncation_restarts() {
+        let (_dir, path) = temp_log("truncate");
+        append(&path, "a long line from the previous session\n");
+        let mut tailer = LogTailer::from_end(&path);
+
+        fs::write(&path, "new log\n").unwrap();
+        let chunk = tailer.poll().unwrap();
+        assert!(chunk.restarted);
+        assert_eq!(chunk.lines, vec!["new log"]);
+    }
+
+    #[test]
+    fn test_missing_file_then_created() {
+        let (_dir, path) = temp_log("missing");
+        let mut tailer = LogTailer::from_end(&path);
+        assert_eq!(tailer.poll().unwrap(), TailChunk::default());
+
+        append(&path, "Log started on Sat May 11 18:20:01 2024\n");
+        assert_eq!(
+            tailer.poll().unwrap().lines,
+            vec!["Log started on Sat May 11 18:20:01 2024"]
+        );
+    }
+}
@@ -0,0 +1,102 @@
+// Game.log event types
+// Each event is emitted under its own name (see GameEvent::event_name) with one of the
+// payloads below. Timestamps are the game's own (UTC, ISO 8601) when the line has one.
+
+use serde::Serialize;
+
+/// Joined a game server (persistent universe shard)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStartedPayload {
+    pub timestamp: Option<String>,
+    /// Shard name (e.g., "pub_use1b_8932401_110")
+    pub shard: Option<String>,
+    /// Server address and port (e.g., "34.150.0.1:64314")
+    pub server: Option<String>,
+}
+
+/// The player's location changed (e.g., "Stanton1_Lorville")
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationChangedPayload {
+    pub timestamp: Option<String>,
+    pub location: String,
+    /// Location before the change (None for the first one of a session)
+    pub previous_location: Option<String>,
+}
+
+/// The local player died
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerDeathPayload {
+    pub timestamp: Option<String>,
+    pub victim: String,
+    pub killer: String,
+    /// Zone the victim was in (a location or vehicle ID)
+    pub zone: String,
+    pub weapon: String,
+    /// Damage type as logged (e.g., "Bullet", "VehicleDestruction", "Crash")
+    pub damage_type: String,
+}
+
+/// Stage of a quantum jump
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QuantumPhase {
+    /// A destination was selected
+    Selected,
+    /// The drive spooled up and the jump began
+    Started,
+    /// The ship arrived at the destination
+    Arrived,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuantumTravelPayload {
+    pub timestamp: Option<String>,
+    pub phase: QuantumPhase,
+    /// Last selected destination, if known
+    pub destination: Option<String>,
+}
+
+/// The client lost its connection to the server
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisconnectedPayload {
+    pub timestamp: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// A structured event parsed from Game.log (serializes as its payload)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum GameEvent {
+    SessionStarted(SessionStartedPayload),
+    LocationChanged(LocationChangedPayload),
+    PlayerDeath(PlayerDeathPayload),
+    QuantumTravel(QuantumTravelPayload),
+    Disconnected(DisconnectedPayload),
+}
+
+impl GameEvent {
+    /// Name of the Tauri event this is emitted as
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            GameEvent::SessionStarted(_) => "game-session-started",
+            GameEvent::LocationChanged(_) => "game-location-changed",
+            GameEvent::PlayerDeath(_) => "game-player-death",
+            GameEvent::QuantumTravel(_) => "game-quantum-travel",
+            GameEvent::Disconnected(_) => "game-disconnected",
+        }
+    }
+}
+
+/// Every event name GameEvent is emitted as
+pub const GAME_EVENT_NAMES: &[&str] = &[
+    "game-session-started",
+    "game-location-changed",
+    "game-player-death",
+    "game-quantum-travel",
+    "game-disconnected",
+];
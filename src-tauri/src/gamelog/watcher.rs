@@ -0,0 +1,80 @@
+// Game.log watcher - tails Game.log while the target process runs
+// Starts on target-process-detected, stops on target-process-terminated, and emits every
+// parsed GameEvent under its own event name (game-session-started, game-player-death, ...).
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Listener};
+
+use super::locate::{game_start_time, locate_game_log};
+use super::parser::GameLogParser;
+use super::tailer::LogTailer;
+use crate::platform::window_match::get_target_criteria;
+use crate::platform::window_system::window_system;
+
+/// How often Game.log is checked for new lines
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often to look for Game.log again while it has not been found
+const LOCATE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Incremented on every game start and stop; a tail thread runs while it matches
+static SESSION: AtomicU64 = AtomicU64::new(0);
+
+/// Tail Game.log whenever the target process runs
+pub fn start_game_log_watcher(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen_any("target-process-detected", move |_| {
+        start_tailing(handle.clone());
+    });
+    app.listen_any("target-process-terminated", move |_| {
+        SESSION.fetch_add(1, Ordering::SeqCst);
+    });
+}
+
+fn start_tailing(app: AppHandle) {
+    let session = SESSION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    thread::spawn(move || {
+        let mut tailer: Option<LogTailer> = None;
+        let mut parser = GameLogParser::new();
+        let mut last_locate: Option<Instant> = None;
+
+        while SESSION.load(Ordering::SeqCst) == session {
+            if tailer.is_none() && last_locate.is_none_or(|at| at.elapsed() >= LOCATE_INTERVAL) {
+                last_locate = Some(Instant::now());
+                let system = window_system();
+                let criteria = get_target_criteria();
+                if let Some(path) = locate_game_log(&*system, &criteria) {
+                    log::info!("Following game log {:?}", path);
+                    // The current game's log is read from its start, so the session start and
+                    // the location before the overlay noticed the game are not lost
+                    let started = game_start_time(&*system, &criteria);
+                    tailer = Some(LogTailer::for_process(&path, started));
+                }
+            }
+
+            if let Some(tailer) = tailer.as_mut() {
+                match tailer.poll() {
+                    Ok(chunk) => {
+                        if chunk.restarted {
+                            parser.reset();
+                        }
+                        for event in parser.parse_lines(chunk.lines.iter().map(String::as_str)) {
+                            log::debug!("Game log: {:?}", event);
+                            if let Err(e) = app.emit(event.event_name(), &event) {
+                                log::error!("Failed to emit {} event: {}", event.event_name(), e);
+                            }
+                        }
+                    }
+                    Err(e) => log::warn!("{}", e),
+                }
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+        log::debug!("Stopped following the game log");
+    });
+}
@@ -0,0 +1,139 @@
+// Key names and Windows VK codes
+// Shared by binding validation and the global-shortcut fallback. Accepts the names the
+// settings panel stores ("F3", "T", "SPACE", "Arrow LEFT") as well as the W3C key codes
+// used by the global shortcut plugin ("KeyT", "Digit1", "ArrowLeft", "Numpad0"), and
+// maps VK codes back to the latter.
+
+pub const VK_TAB: u32 = 0x09;
+pub const VK_ESCAPE: u32 = 0x1B;
+pub const VK_DELETE: u32 = 0x2E;
+pub const VK_F4: u32 = 0x73;
+
+// VK_SHIFT, VK_CONTROL, VK_MENU, VK_LWIN, VK_RWIN, VK_LSHIFT..VK_RMENU
+const MODIFIER_KEY_CODES: &[u32] = &[
+    0x10, 0x11, 0x12, 0x5B, 0x5C, 0xA0, 0xA1, 0xA2, 0xA3, 0xA4, 0xA5,
+];
+
+// Keys other than letters, digits, F-keys and numpad digits, by W3C code name
+const NAMED_KEYS: &[(&str, u32)] = &[
+    ("Space", 0x20),
+    ("Enter", 0x0D),
+    ("Escape", VK_ESCAPE),
+    ("Tab", VK_TAB),
+    ("Backspace", 0x08),
+    ("Delete", VK_DELETE),
+    ("Insert", 0x2D),
+    ("Home", 0x24),
+    ("End", 0x23),
+    ("PageUp", 0x21),
+    ("PageDown", 0x22),
+    ("ArrowLeft", 0x25),
+    ("ArrowUp", 0x26),
+    ("ArrowRight", 0x27),
+    ("ArrowDown", 0x28),
+    ("Semicolon", 0xBA),
+    ("Equal", 0xBB),
+    ("Comma", 0xBC),
+    ("Minus", 0xBD),
+    ("Period", 0xBE),
+    ("Slash", 0xBF),
+    ("Backquote", 0xC0),
+    ("BracketLeft", 0xDB),
+    ("Backslash", 0xDC),
+    ("BracketRight", 0xDD),
+    ("Quote", 0xDE),
+];
+
+/// Whether a VK code is a modifier (Shift, Ctrl, Alt or Windows key)
+pub fn is_modifier_key_code(key_code: u32) -> bool {
+    MODIFIER_KEY_CODES.contains(&key_code)
+}
+
+/// VK code a key name stands for (None if the name is not recognised)
+pub fn key_code_for_name(key: &str) -> Option<u32> {
+    let name: String = key
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase();
+    let name = match (name.strip_prefix("KEY"), name.strip_prefix("DIGIT")) {
+        (Some(rest), _) | (_, Some(rest)) if rest.len() == 1 => rest,
+        _ => name.as_str(),
+    };
+
+    if let [c] = name.as_bytes() {
+        if c.is_ascii_uppercase() || c.is_ascii_digit() {
+            return Some(*c as u32);
+        }
+    }
+    if let Some(n) = name.strip_prefix('F').and_then(|n| n.parse::<u32>().ok()) {
+        if (1..=24).contains(&n) {
+            return Some(0x70 + n - 1);
+        }
+    }
+    if let Some(n) = name
+        .strip_prefix("NUMPAD")
+        .and_then(|n| n.parse::<u32>().ok())
+    {
+        if n <= 9 {
+            return Some(0x60 + n);
+        }
+    }
+
+    NAMED_KEYS
+        .iter()
+        .find(|(code_name, _)| code_name.eq_ignore_ascii_case(name))
+        .map(|(_, key_code)| *key_code)
+}
+
+/// W3C code name of a VK code, as used by the global shortcut plugin (e.g. "KeyT")
+pub fn code_name_for_key_code(key_code: u32) -> Option<String> {
+    let name = match key_code {
+        0x41..=0x5A => format!("Key{}", char::from_u32(key_code)?),
+        0x30..=0x39 => format!("Digit{}", key_code - 0x30),
+        0x60..=0x69 => format!("Numpad{}", key_code - 0x60),
+        0x70..=0x87 => format!("F{}", key_code - 0x70 + 1),
+        _ => NAMED_KEYS
+            .iter()
+            .find(|(_, code)| *code == key_code)
+            .map(|(code_name, _)| code_name.to_string())?,
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_code_for_name() {
+        // Settings panel names
+        assert_eq!(key_code_for_name("F3"), Some(0x72));
+        assert_eq!(key_code_for_name("T"), Some(0x54));
+        assert_eq!(key_code_for_name("Arrow LEFT"), Some(0x25));
+        // Global shortcut plugin codes
+        assert_eq!(key_code_for_name("KeyT"), Some(0x54));
+        assert_eq!(key_code_for_name("Digit7"), Some(0x37));
+        assert_eq!(key_code_for_name("F24"), Some(0x87));
+        assert_eq!(key_code_for_name("Numpad0"), Some(0x60));
+        // Unknown names
+        assert_eq!(key_code_for_name("F25"), None);
+        assert_eq!(key_code_for_name("Keyboard"), None);
+        assert_eq!(key_code_for_name("Ü"), None);
+        assert_eq!(key_code_for_name("Semicolon"), Some(0xBA));
+    }
+
+    #[test]
+    fn test_code_name_round_trip() {
+        assert_eq!(code_name_for_key_code(0x54).as_deref(), Some("KeyT"));
+        assert_eq!(code_name_for_key_code(0x72).as_deref(), Some("F3"));
+        assert_eq!(code_name_for_key_code(0x25).as_deref(), Some("ArrowLeft"));
+        assert_eq!(code_name_for_key_code(0x11), None);
+
+        for key_code in (0x08..=0xFE).filter(|code| !is_modifier_key_code(*code)) {
+            if let Some(name) = code_name_for_key_code(key_code) {
+                assert_eq!(key_code_for_name(&name), Some(key_code), "{}", name);
+            }
+        }
+    }
+}
@@ -0,0 +1,548 @@
+// Hotkey matcher - turns key events into action triggers
+// Evaluates plain presses, two-step sequences ("Ctrl+K, N"), hold-to-activate and
+// double-tap bindings. The low-level keyboard hook and the global-shortcut fallback
+// both feed their key-down and key-up events through here, so they behave the same.
+
+use std::collections::{HashMap, HashSet};
+
+use super::keys;
+use super::registry::HotkeyAction;
+use crate::settings::types::{HotkeyBinding, HotkeySettings, HotkeyTrigger};
+
+/// Default time allowed between the two keys of a sequence (milliseconds)
+pub const DEFAULT_SEQUENCE_TIMEOUT_MS: u64 = 1_000;
+/// Default time a hold binding must be held down (milliseconds)
+pub const DEFAULT_HOLD_MS: u64 = 500;
+/// Default maximum time between the two presses of a double-tap (milliseconds)
+pub const DEFAULT_DOUBLE_TAP_MS: u64 = 300;
+/// A held key without events for this long lost its key-up (focus change, hook
+/// restart); its next key-down is a new press rather than auto-repeat. Longer than
+/// the longest keyboard repeat delay Windows allows (1s).
+pub const HELD_KEY_TIMEOUT_MS: u64 = 2_000;
+
+/// Sequence, hold or double-tap window of a binding (0 for plain presses)
+pub fn timeout_ms(binding: &HotkeyBinding) -> u64 {
+    let default = match (binding.next.is_some(), binding.trigger) {
+        (true, _) => DEFAULT_SEQUENCE_TIMEOUT_MS,
+        (false, HotkeyTrigger::Hold) => DEFAULT_HOLD_MS,
+        (false, HotkeyTrigger::DoubleTap) => DEFAULT_DOUBLE_TAP_MS,
+        (false, HotkeyTrigger::Press) => 0,
+    };
+    binding.timeout_ms.unwrap_or(default)
+}
+
+/// A key event with the modifier state at the time of the event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyPress {
+    pub key_code: u32,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyPress {
+    /// Key and modifiers must match exactly (Ctrl+T does not trigger T)
+    pub fn matches(&self, binding: &HotkeyBinding) -> bool {
+        self.key_code == binding.key_code
+            && self.ctrl == binding.ctrl
+            && self.shift == binding.shift
+            && self.alt == binding.alt
+    }
+}
+
+/// Outcome of a key event for one matching action
+#[derive(Debug, Clone, PartialEq)]
+pub enum Dispatch {
+    /// Trigger the action
+    Fire {
+        action: HotkeyAction,
+        binding: HotkeyBinding,
+    },
+    /// Triggered again within the action's debounce window
+    Debounced {
+        action: HotkeyAction,
+        binding: HotkeyBinding,
+        elapsed_ms: u64,
+    },
+    /// First key of a sequence pressed, waiting for the second one
+    Pending {
+        action: HotkeyAction,
+        binding: HotkeyBinding,
+    },
+}
+
+/// Per-source matching state (timestamps are milliseconds since UNIX epoch)
+#[derive(Debug, Default)]
+pub struct MatchState {
+    /// Last trigger time of each action
+    last_fired: HashMap<String, u64>,
+    /// Keys currently down as (time they went down, time of their last key-down)
+    held: HashMap<u32, (u64, u64)>,
+    /// Hold actions already triggered during the current hold
+    hold_fired: HashSet<String>,
+    /// Sequences waiting for their second key (action ID -> first key time)
+    pending: HashMap<String, u64>,
+    /// Double-taps waiting for the second tap (action ID -> first tap time)
+    first_tap: HashMap<String, u64>,
+}
+
+impl MatchState {
+    pub fn last_fired(&self, action_id: &str) -> u64 {
+        self.last_fired.get(action_id).copied().unwrap_or(0)
+    }
+
+    // Trigger an action unless it fired within its debounce window
+    fn fire(&mut self, action: &HotkeyAction, binding: &HotkeyBinding, now_ms: u64) -> Dispatch {
+        let elapsed_ms = now_ms.saturating_sub(self.last_fired(&action.id));
+        if elapsed_ms < action.debounce_ms {
+            return Dispatch::Debounced {
+                action: action.clone(),
+                binding: binding.clone(),
+                elapsed_ms,
+            };
+        }
+        self.last_fired.insert(action.id.clone(), now_ms);
+        Dispatch::Fire {
+            action: action.clone(),
+            binding: binding.clone(),
+        }
+    }
+}
+
+// Configured binding of an action, falling back to its default
+fn binding_for<'a>(
+    action: &'a HotkeyAction,
+    hotkeys: &'a HotkeySettings,
+) -> Option<&'a HotkeyBinding> {
+    hotkeys.get(&action.id).or(action.default_binding.as_ref())
+}
+
+/// Match a key-down event against the bindings of all actions.
+/// Returns one entry per action the press triggered or advanced.
+pub fn dispatch(
+    actions: &[HotkeyAction],
+    hotkeys: &HotkeySettings,
+    press: &KeyPress,
+    now_ms: u64,
+    state: &mut MatchState,
+) -> Vec<Dispatch> {
+    // Modifiers belong to the next key; they neither trigger nor cancel anything
+    if keys::is_modifier_key_code(press.key_code) {
+        return Vec::new();
+    }
+
+    // Auto-repeat sends further key-downs while a key is held
+    let held = state
+        .held
+        .get(&press.key_code)
+        .copied()
+        .filter(|(_, last_seen)| now_ms.saturating_sub(*last_seen) <= HELD_KEY_TIMEOUT_MS);
+    let repeat = held.is_some();
+    let held_since = held.map_or(now_ms, |(since, _)| since);
+    state.held.insert(press.key_code, (held_since, now_ms));
+    let mut dispatches = Vec::new();
+
+    for action in actions {
+        let Some(binding) = binding_for(action, hotkeys) else {
+            continue;
+        };
+        let timeout = timeout_ms(binding);
+
+        if let Some(next) = &binding.next {
+            if repeat {
+                continue;
+            }
+            // Any other key cancels a waiting sequence
+            let waiting = state
+                .pending
+                .remove(&action.id)
+                .is_some_and(|since| now_ms.saturating_sub(since) <= timeout);
+            if waiting && press.matches(next) {
+                dispatches.push(state.fire(action, binding, now_ms));
+            } else if press.matches(binding) {
+                state.pending.insert(action.id.clone(), now_ms);
+                dispatches.push(Dispatch::Pending {
+                    action: action.clone(),
+                    binding: binding.clone(),
+                });
+            }
+            continue;
+        }
+
+        match binding.trigger {
+            // Auto-repeat of a plain press is left to debouncing
+            HotkeyTrigger::Press => {
+                if press.matches(binding) {
+                    dispatches.push(state.fire(action, binding, now_ms));
+                }
+            }
+            HotkeyTrigger::Hold => {
+                // A new hold, even if the key-up of the previous one was missed
+                if !repeat && binding.key_code == press.key_code {
+                    state.hold_fired.remove(&action.id);
+                }
+                if repeat
+                    && press.matches(binding)
+                    && now_ms.saturating_sub(held_since) >= timeout
+                    && state.hold_fired.insert(action.id.clone())
+                {
+                    dispatches.push(state.fire(action, binding, now_ms));
+                }
+            }
+            HotkeyTrigger::DoubleTap => {
+                if repeat {
+                    continue;
+                }
+                let tapped = state
+                    .first_tap
+                    .remove(&action.id)
+                    .is_some_and(|since| now_ms.saturating_sub(since) <= timeout);
+                if !press.matches(binding) {
+                    continue;
+                }
+                if tapped {
+                    dispatches.push(state.fire(action, binding, now_ms));
+                } else {
+                    state.first_tap.insert(action.id.clone(), now_ms);
+                }
+            }
+        }
+    }
+
+    dispatches
+}
+
+/// Match a key-up event. Hold bindings that were held long enough but saw no
+/// auto-repeat key-down (the global shortcut plugin does not repeat) fire here.
+pub fn dispatch_release(
+    actions: &[HotkeyAction],
+    hotkeys: &HotkeySettings,
+    press: &KeyPress,
+    now_ms: u64,
+    state: &mut MatchState,
+) -> Vec<Dispatch> {
+    let Some((held_since, _)) = state.held.remove(&press.key_code) else {
+        return Vec::new();
+    };
+    let mut dispatches = Vec::new();
+
+    for action in actions {
+        let Some(binding) = binding_for(action, hotkeys) else {
+            continue;
+        };
+        if binding.trigger != HotkeyTrigger::Hold
+            || binding.next.is_some()
+            || binding.key_code != press.key_code
+        {
+            continue;
+        }
+        let already_fired = state.hold_fired.remove(&action.id);
+        if !already_fired
+            && press.matches(binding)
+            && now_ms.saturating_sub(held_since) >= timeout_ms(binding)
+        {
+            dispatches.push(state.fire(action, binding, now_ms));
+        }
+    }
+
+    dispatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hotkey::registry::{
+        builtin_actions, CHRONOMETER_RESET, CHRONOMETER_START_PAUSE, TOGGLE_MODE, TOGGLE_VISIBILITY,
+    };
+
+    fn press(key_code: u32, ctrl: bool) -> KeyPress {
+        KeyPress {
+            key_code,
+            ctrl,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    fn fired(dispatches: &[Dispatch]) -> Vec<&str> {
+        dispatches
+            .iter()
+            .filter_map(|d| match d {
+                Dispatch::Fire { action, .. } => Some(action.id.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Key-down immediately followed by key-up
+    fn tap(
+        actions: &[HotkeyAction],
+        hotkeys: &HotkeySettings,
+        key: KeyPress,
+        now_ms: u64,
+        state: &mut MatchState,
+    ) -> Vec<Dispatch> {
+        let dispatches = dispatch(actions, hotkeys, &key, now_ms, state);
+        dispatch_release(actions, hotkeys, &key, now_ms + 50, state);
+        dispatches
+    }
+
+    #[test]
+    fn test_dispatch_matches_modifiers_exactly() {
+        let actions = builtin_actions();
+        let hotkeys = HotkeySettings::default();
+        let mut debounce = MatchState::default();
+
+        assert_eq!(
+            fired(&dispatch(
+                &actions,
+                &hotkeys,
+                &press(0x72, false),
+                1_000,
+                &mut debounce
+            )),
+            vec![TOGGLE_VISIBILITY]
+        );
+        // Ctrl+F3 is not F3, and T without Ctrl is not the chronometer hotkey
+        assert!(dispatch(&actions, &hotkeys, &press(0x72, true), 2_000, &mut debounce).is_empty());
+        assert!(dispatch(
+            &actions,
+            &hotkeys,
+            &press(0x54, false),
+            2_000,
+            &mut debounce
+        )
+        .is_empty());
+        assert_eq!(
+            fired(&dispatch(
+                &actions,
+                &hotkeys,
+                &press(0x54, true),
+                2_000,
+                &mut debounce
+            )),
+            vec![CHRONOMETER_START_PAUSE]
+        );
+    }
+
+    #[test]
+    fn test_dispatch_debounces_per_action() {
+        let mut actions = builtin_actions();
+        actions[1].debounce_ms = 500;
+        let hotkeys = HotkeySettings::default();
+        let mut debounce = MatchState::default();
+
+        let f3 = press(0x72, false);
+        let f5 = press(0x74, false);
+        assert_eq!(
+            fired(&dispatch(&actions, &hotkeys, &f3, 10_000, &mut debounce)).len(),
+            1
+        );
+        assert_eq!(
+            fired(&dispatch(&actions, &hotkeys, &f5, 10_000, &mut debounce)).len(),
+            1
+        );
+
+        // 250ms later: F3 (200ms window) fires again, F5 (500ms window) is debounced
+        assert_eq!(
+            fired(&dispatch(&actions, &hotkeys, &f3, 10_250, &mut debounce)).len(),
+            1
+        );
+        let result = dispatch(&actions, &hotkeys, &f5, 10_250, &mut debounce);
+        assert_eq!(
+            result,
+            vec![Dispatch::Debounced {
+                action: actions[1].clone(),
+                binding: HotkeyBinding::new("F5", 0x74),
+                elapsed_ms: 250,
+            }]
+        );
+        // A debounced press does not extend the window
+        assert_eq!(
+            fired(&dispatch(&actions, &hotkeys, &f5, 10_500, &mut debounce)).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_custom_action_uses_configured_binding() {
+        let mut actions = builtin_actions();
+        actions.push(HotkeyAction::new(
+            "openNotes",
+            "open-notes",
+            "open notes",
+            None,
+        ));
+        let mut hotkeys = HotkeySettings::default();
+        let mut debounce = MatchState::default();
+
+        // Unbound until the user assigns a key
+        assert!(dispatch(&actions, &hotkeys, &press(0x4E, true), 1_000, &mut debounce).is_empty());
+
+        hotkeys.set("openNotes", HotkeyBinding::new("N", 0x4E).with_ctrl());
+        assert_eq!(
+            fired(&dispatch(
+                &actions,
+                &hotkeys,
+                &press(0x4E, true),
+                1_000,
+                &mut debounce
+            )),
+            vec!["openNotes"]
+        );
+    }
+
+    #[test]
+    fn test_sequence() {
+        let actions = builtin_actions();
+        let mut hotkeys = HotkeySettings::default();
+        hotkeys.set(
+            TOGGLE_MODE,
+            HotkeyBinding::new("K", 0x4B)
+                .with_ctrl()
+                .then(HotkeyBinding::new("N", 0x4E)),
+        );
+        let mut state = MatchState::default();
+        let ctrl_k = press(0x4B, true);
+        let n = press(0x4E, false);
+
+        // Ctrl+K arms the sequence, N completes it; releasing Ctrl in between is fine
+        assert!(matches!(
+            tap(&actions, &hotkeys, ctrl_k, 1_000, &mut state).as_slice(),
+            [Dispatch::Pending { .. }]
+        ));
+        assert!(dispatch(&actions, &hotkeys, &press(0x11, false), 1_100, &mut state).is_empty());
+        assert_eq!(
+            fired(&tap(&actions, &hotkeys, n, 1_200, &mut state)),
+            vec![TOGGLE_MODE]
+        );
+        // N alone does nothing
+        assert!(tap(&actions, &hotkeys, n, 2_000, &mut state).is_empty());
+
+        // Too slow
+        tap(&actions, &hotkeys, ctrl_k, 3_000, &mut state);
+        assert!(tap(
+            &actions,
+            &hotkeys,
+            n,
+            3_000 + DEFAULT_SEQUENCE_TIMEOUT_MS + 1,
+            &mut state
+        )
+        .is_empty());
+
+        // Another key in between cancels the sequence
+        tap(&actions, &hotkeys, ctrl_k, 6_000, &mut state);
+        tap(&actions, &hotkeys, press(0x57, false), 6_100, &mut state);
+        assert!(tap(&actions, &hotkeys, n, 6_200, &mut state).is_empty());
+    }
+
+    #[test]
+    fn test_hold() {
+        let actions = builtin_actions();
+        let mut hotkeys = HotkeySettings::default();
+        hotkeys.set(
+            TOGGLE_MODE,
+            HotkeyBinding::new("F5", 0x74).with_trigger(HotkeyTrigger::Hold),
+        );
+        let mut state = MatchState::default();
+        let f5 = press(0x74, false);
+
+        // Short press does nothing
+        assert!(tap(&actions, &hotkeys, f5, 1_000, &mut state).is_empty());
+
+        // Auto-repeat: fires once the hold time is reached, once per hold
+        assert!(dispatch(&actions, &hotkeys, &f5, 2_000, &mut state).is_empty());
+        assert!(dispatch(&actions, &hotkeys, &f5, 2_300, &mut state).is_empty());
+        assert_eq!(
+            fired(&dispatch(&actions, &hotkeys, &f5, 2_530, &mut state)),
+            vec![TOGGLE_MODE]
+        );
+        assert!(dispatch(&actions, &hotkeys, &f5, 2_800, &mut state).is_empty());
+        assert!(dispatch_release(&actions, &hotkeys, &f5, 3_000, &mut state).is_empty());
+
+        // No auto-repeat (global shortcut plugin): fires on release
+        dispatch(&actions, &hotkeys, &f5, 5_000, &mut state);
+        assert_eq!(
+            fired(&dispatch_release(
+                &actions, &hotkeys, &f5, 5_600, &mut state
+            )),
+            vec![TOGGLE_MODE]
+        );
+    }
+
+    #[test]
+    fn test_missed_key_up_expires() {
+        let actions = builtin_actions();
+        let mut hotkeys = HotkeySettings::default();
+        hotkeys.set(
+            TOGGLE_MODE,
+            HotkeyBinding::new("F5", 0x74).with_trigger(HotkeyTrigger::Hold),
+        );
+        let mut state = MatchState::default();
+        let f3 = press(0x72, false);
+        let f5 = press(0x74, false);
+
+        // Key-ups lost, e.g. while another window had focus
+        assert_eq!(
+            fired(&dispatch(&actions, &hotkeys, &f3, 1_000, &mut state)),
+            vec![TOGGLE_VISIBILITY]
+        );
+        dispatch(&actions, &hotkeys, &f5, 1_000, &mut state);
+        assert_eq!(
+            fired(&dispatch(&actions, &hotkeys, &f5, 1_600, &mut state)),
+            vec![TOGGLE_MODE]
+        );
+
+        // Later presses are new presses, not auto-repeat of the stuck keys
+        let later = 1_600 + HELD_KEY_TIMEOUT_MS + 1;
+        assert_eq!(
+            fired(&dispatch(&actions, &hotkeys, &f3, later, &mut state)),
+            vec![TOGGLE_VISIBILITY]
+        );
+        assert!(dispatch(&actions, &hotkeys, &f5, later, &mut state).is_empty());
+        assert_eq!(
+            fired(&dispatch(&actions, &hotkeys, &f5, later + 600, &mut state)),
+            vec![TOGGLE_MODE]
+        );
+    }
+
+    #[test]
+    fn test_double_tap() {
+        let actions = builtin_actions();
+        let mut hotkeys = HotkeySettings::default();
+        hotkeys.set(
+            CHRONOMETER_RESET,
+            HotkeyBinding::new("Y", 0x59)
+                .with_ctrl()
+                .with_trigger(HotkeyTrigger::DoubleTap),
+        );
+        let mut state = MatchState::default();
+        let ctrl_y = press(0x59, true);
+
+        assert!(tap(&actions, &hotkeys, ctrl_y, 1_000, &mut state).is_empty());
+        assert_eq!(
+            fired(&tap(&actions, &hotkeys, ctrl_y, 1_250, &mut state)),
+            vec![CHRONOMETER_RESET]
+        );
+
+        // Taps too far apart: the second one starts a new double-tap
+        assert!(tap(&actions, &hotkeys, ctrl_y, 5_000, &mut state).is_empty());
+        assert!(tap(&actions, &hotkeys, ctrl_y, 5_400, &mut state).is_empty());
+        assert_eq!(
+            fired(&tap(&actions, &hotkeys, ctrl_y, 5_600, &mut state)),
+            vec![CHRONOMETER_RESET]
+        );
+
+        // Other bindings still work as plain presses
+        assert_eq!(
+            fired(&tap(
+                &actions,
+                &hotkeys,
+                press(0x54, true),
+                9_000,
+                &mut state
+            )),
+            vec![CHRONOMETER_START_PAUSE]
+        );
+    }
+}
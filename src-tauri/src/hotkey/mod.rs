@@ -1,6 +1,10 @@
 // Hotkey module - global shortcut handling and keyboard hooks
 
+pub mod keys;
+pub mod matcher;
+pub mod registry;
 pub mod shortcuts;
+pub mod validation;
 
 // Re-exports for public API
 pub use shortcuts::*;
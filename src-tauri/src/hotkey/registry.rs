@@ -0,0 +1,157 @@
+// Hotkey action registry
+// Maps action IDs to the event they emit, their default binding and debounce window.
+// Key events are matched against the bindings in `hotkey::matcher`, so a new action
+// (e.g., opening a notes window) only needs a `register_action` call and a binding.
+
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use serde::Serialize;
+
+use crate::settings::types::HotkeyBinding;
+
+/// Default minimum time between two triggers of the same action (milliseconds)
+pub const DEFAULT_DEBOUNCE_MS: u64 = 200;
+
+// Built-in action IDs (also the keys in HotkeySettings)
+pub const TOGGLE_VISIBILITY: &str = "toggleVisibility";
+pub const TOGGLE_MODE: &str = "toggleMode";
+pub const CHRONOMETER_START_PAUSE: &str = "chronometerStartPause";
+pub const CHRONOMETER_RESET: &str = "chronometerReset";
+
+/// An action that can be bound to a hotkey
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyAction {
+    /// Action ID, also the key in HotkeySettings (e.g., "toggleVisibility")
+    pub id: String,
+    /// Event emitted to the frontend when triggered (e.g., "toggle-visibility")
+    pub event: String,
+    /// Human-readable description for logs and the settings panel
+    pub description: String,
+    /// Binding used when the user has not configured one (None = unbound)
+    pub default_binding: Option<HotkeyBinding>,
+    /// Minimum time between two triggers (milliseconds)
+    pub debounce_ms: u64,
+}
+
+impl HotkeyAction {
+    pub fn new(
+        id: &str,
+        event: &str,
+        description: &str,
+        default_binding: Option<HotkeyBinding>,
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            event: event.to_string(),
+            description: description.to_string(),
+            default_binding,
+            debounce_ms: DEFAULT_DEBOUNCE_MS,
+        }
+    }
+}
+
+// T006 (029), T006 (045): The overlay and chronometer actions
+pub(crate) fn builtin_actions() -> Vec<HotkeyAction> {
+    vec![
+        HotkeyAction::new(
+            TOGGLE_VISIBILITY,
+            "toggle-visibility",
+            "toggle visibility",
+            Some(HotkeyBinding::new("F3", 0x72)), // VK_F3
+        ),
+        HotkeyAction::new(
+            TOGGLE_MODE,
+            "toggle-mode",
+            "toggle mode",
+            Some(HotkeyBinding::new("F5", 0x74)), // VK_F5
+        ),
+        HotkeyAction::new(
+            CHRONOMETER_START_PAUSE,
+            "chronometer-start-pause",
+            "chronometer toggle",
+            Some(HotkeyBinding::new("T", 0x54).with_ctrl()), // VK_T
+        ),
+        HotkeyAction::new(
+            CHRONOMETER_RESET,
+            "chronometer-reset",
+            "chronometer reset",
+            Some(HotkeyBinding::new("Y", 0x59).with_ctrl()), // VK_Y
+        ),
+    ]
+}
+
+lazy_static::lazy_static! {
+    static ref ACTIONS: RwLock<Vec<HotkeyAction>> = RwLock::new(builtin_actions());
+}
+
+/// Register a new hotkey action. Fails if the ID is already taken.
+pub fn register_action(action: HotkeyAction) -> Result<(), String> {
+    let mut actions = ACTIONS
+        .write()
+        .map_err(|_| "Failed to acquire hotkey registry lock".to_string())?;
+    if actions.iter().any(|a| a.id == action.id) {
+        return Err(format!(
+            "Hotkey action '{}' is already registered",
+            action.id
+        ));
+    }
+    log::debug!(
+        "Registered hotkey action '{}' ({})",
+        action.id,
+        action.event
+    );
+    actions.push(action);
+    Ok(())
+}
+
+/// All registered actions, in registration order
+pub fn registered_actions() -> Vec<HotkeyAction> {
+    ACTIONS.read().map(|a| a.clone()).unwrap_or_default()
+}
+
+/// Default binding of every registered action that has one
+pub fn default_bindings() -> BTreeMap<String, HotkeyBinding> {
+    registered_actions()
+        .into_iter()
+        .filter_map(|action| Some((action.id, action.default_binding?)))
+        .collect()
+}
+
+/// List the registered hotkey actions (for the settings panel)
+#[tauri::command]
+pub fn list_hotkey_actions() -> Vec<HotkeyAction> {
+    registered_actions()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::types::HotkeySettings;
+
+    #[test]
+    fn test_default_bindings() {
+        let hotkeys = HotkeySettings::default();
+        assert_eq!(hotkeys.get(TOGGLE_VISIBILITY).unwrap().label(), "F3");
+        assert_eq!(hotkeys.get(TOGGLE_MODE).unwrap().label(), "F5");
+        assert_eq!(
+            hotkeys.get(CHRONOMETER_START_PAUSE).unwrap().label(),
+            "Ctrl+T"
+        );
+        assert_eq!(hotkeys.get(CHRONOMETER_RESET).unwrap().label(), "Ctrl+Y");
+    }
+
+    #[test]
+    fn test_hotkey_settings_serialization() {
+        // Older files without the chronometer bindings get the defaults
+        let json = r#"{"toggleVisibility":{"key":"F6","keyCode":117,"ctrl":false,"shift":false,"alt":false}}"#;
+        let hotkeys: HotkeySettings = serde_json::from_str(json).unwrap();
+        assert_eq!(hotkeys.get(TOGGLE_VISIBILITY).unwrap().key, "F6");
+        assert_eq!(hotkeys.get(CHRONOMETER_RESET).unwrap().label(), "Ctrl+Y");
+
+        let value = serde_json::to_value(&hotkeys).unwrap();
+        assert_eq!(value["toggleMode"]["keyCode"], 0x74);
+        assert_eq!(value["chronometerStartPause"]["ctrl"], true);
+    }
+}
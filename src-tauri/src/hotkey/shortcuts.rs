@@ -1,14 +1,24 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::Emitter;
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 
-// Debounce time in milliseconds
-const DEBOUNCE_MS: u64 = 200;
+use super::keys;
+use super::matcher::{self, Dispatch, KeyPress, MatchState};
+use super::registry;
+use crate::settings::types::{HotkeyBinding, HotkeySettings};
 
-// Last press timestamps for debouncing (separate for each hotkey)
-static LAST_PRESS_F3: AtomicU64 = AtomicU64::new(0);
-static LAST_PRESS_F5: AtomicU64 = AtomicU64::new(0);
+lazy_static::lazy_static! {
+    // Debounce, sequence, hold and double-tap state of the fallback path
+    static ref MATCH_STATE: Mutex<MatchState> = Mutex::new(MatchState::default());
+    // Shortcuts registered for the current bindings (replaced on every registration)
+    static ref REGISTERED: Mutex<Vec<Shortcut>> = Mutex::new(Vec::new());
+}
+
+// Whether hotkeys go through global shortcuts (hook failed, or not on Windows)
+static SHORTCUTS_ACTIVE: AtomicBool = AtomicBool::new(false);
 
 fn current_time_ms() -> u64 {
     SystemTime::now()
@@ -17,73 +27,194 @@ fn current_time_ms() -> u64 {
         .as_millis() as u64
 }
 
-/// Build the global shortcut plugin with F3 and F5 handlers
-/// F3: Toggle visibility (show/hide)
-/// F5: Toggle mode (click-through/interactive)
+/// Build the global shortcut plugin
+/// Shortcut presses and releases go through the same matcher as the low-level hook,
+/// so sequence, hold and double-tap bindings work here too. Only registered shortcuts
+/// are seen, so unrelated keys do not cancel a waiting sequence on this path.
 pub fn build_shortcut_plugin<R: tauri::Runtime>() -> tauri_plugin_global_shortcut::Builder<R> {
     tauri_plugin_global_shortcut::Builder::new().with_handler(
         move |app: &tauri::AppHandle<R>, shortcut: &Shortcut, event| {
-            if event.state != ShortcutState::Pressed {
+            let Some(key_code) = keys::key_code_for_name(&shortcut.key.to_string()) else {
+                log::debug!(
+                    "Shortcut {} has no matching key code, ignored",
+                    shortcut.key
+                );
                 return;
-            }
+            };
+            let press = KeyPress {
+                key_code,
+                ctrl: shortcut.mods.contains(Modifiers::CONTROL),
+                shift: shortcut.mods.contains(Modifiers::SHIFT),
+                alt: shortcut.mods.contains(Modifiers::ALT),
+            };
 
             let now = current_time_ms();
-
-            // F3: Toggle visibility (show/hide)
-            if shortcut.matches(Modifiers::empty(), Code::F3) {
-                let last = LAST_PRESS_F3.load(Ordering::SeqCst);
-                if now - last < DEBOUNCE_MS {
-                    log::debug!("F3 pressed: debounced ({}ms since last)", now - last);
-                    return;
+            let hotkeys = crate::settings::user::get_hotkey_settings();
+            let actions = registry::registered_actions();
+            let dispatches = {
+                let mut state = match MATCH_STATE.lock() {
+                    Ok(guard) => guard,
+                    Err(poisoned) => poisoned.into_inner(),
+                };
+                match event.state {
+                    ShortcutState::Pressed => {
+                        matcher::dispatch(&actions, &hotkeys, &press, now, &mut state)
+                    }
+                    ShortcutState::Released => {
+                        matcher::dispatch_release(&actions, &hotkeys, &press, now, &mut state)
+                    }
                 }
-                LAST_PRESS_F3.store(now, Ordering::SeqCst);
-
-                // T025 (028): Log F3 hotkey press
-                log::info!("F3 pressed: toggle visibility requested");
-
-                if let Err(e) = app.emit("toggle-visibility", ()) {
-                    log::error!("Failed to emit toggle-visibility event: {}", e);
+            };
+
+            for dispatch in dispatches {
+                match dispatch {
+                    Dispatch::Fire { action, binding } => {
+                        // T025, T026 (028): Log hotkey press
+                        log::info!(
+                            "{} pressed: {} requested (global shortcut)",
+                            binding.label(),
+                            action.description
+                        );
+
+                        if let Err(e) = app.emit(&action.event, ()) {
+                            log::error!("Failed to emit {} event: {}", action.event, e);
+                        }
+                    }
+                    Dispatch::Debounced {
+                        binding,
+                        elapsed_ms,
+                        ..
+                    } => {
+                        log::debug!(
+                            "{} pressed: debounced ({}ms since last)",
+                            binding.label(),
+                            elapsed_ms
+                        );
+                    }
+                    Dispatch::Pending { binding, .. } => {
+                        log::debug!("{} started: waiting for the second key", binding.label());
+                    }
                 }
-                return;
             }
+        },
+    )
+}
 
-            // F5: Toggle mode (click-through/interactive)
-            if shortcut.matches(Modifiers::empty(), Code::F5) {
-                let last = LAST_PRESS_F5.load(Ordering::SeqCst);
-                if now - last < DEBOUNCE_MS {
-                    log::debug!("F5 pressed: debounced ({}ms since last)", now - last);
-                    return;
-                }
-                LAST_PRESS_F5.store(now, Ordering::SeqCst);
-
-                // T026 (028): Log F5 hotkey press
-                log::info!("F5 pressed: toggle mode requested");
+/// Global shortcut for one key of a binding (None if the plugin has no code for it)
+fn shortcut_for(step: &HotkeyBinding) -> Option<Shortcut> {
+    let code = Code::from_str(&keys::code_name_for_key_code(step.key_code)?).ok()?;
+    let mut mods = Modifiers::empty();
+    for (held, modifier) in [
+        (step.ctrl, Modifiers::CONTROL),
+        (step.shift, Modifiers::SHIFT),
+        (step.alt, Modifiers::ALT),
+    ] {
+        if held {
+            mods |= modifier;
+        }
+    }
+    Some(Shortcut::new(Some(mods), code))
+}
 
-                if let Err(e) = app.emit("toggle-mode", ()) {
-                    log::error!("Failed to emit toggle-mode event: {}", e);
-                }
+/// Shortcuts for the bindings of all registered actions, including the second key
+/// of sequences (which the OS then reserves for the overlay, like every other key here)
+fn configured_shortcuts(hotkeys: &HotkeySettings) -> Vec<Shortcut> {
+    let mut shortcuts = Vec::new();
+
+    for action in registry::registered_actions() {
+        let Some(binding) = hotkeys.get(&action.id).or(action.default_binding.as_ref()) else {
+            continue;
+        };
+        for step in std::iter::once(binding).chain(binding.next.as_deref()) {
+            match shortcut_for(step) {
+                Some(shortcut) if !shortcuts.contains(&shortcut) => shortcuts.push(shortcut),
+                Some(_) => {}
+                None => log::warn!(
+                    "{}: {} has no global shortcut equivalent, {} is unavailable",
+                    action.id,
+                    step.label(),
+                    action.description
+                ),
             }
-        },
-    )
+        }
+    }
+
+    shortcuts
 }
 
-/// Register global shortcuts (F3 and F5)
+/// Register a global shortcut for every configured hotkey, replacing the previous set.
+/// Shortcuts that fail (e.g. taken by another application) are skipped and reported.
 pub fn register_shortcuts<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<(), String> {
     let shortcut_manager = app.global_shortcut();
+    let shortcuts = configured_shortcuts(&crate::settings::user::get_hotkey_settings());
+
+    let mut registered = match REGISTERED.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+
+    // Unregister first so changed or removed bindings are released
+    for shortcut in registered.drain(..) {
+        if let Err(e) = shortcut_manager.unregister(shortcut) {
+            log::debug!("Failed to unregister {}: {}", shortcut, e);
+        }
+    }
+
+    let mut failed = Vec::new();
+    for shortcut in shortcuts {
+        match shortcut_manager.register(shortcut) {
+            Ok(()) => registered.push(shortcut),
+            Err(e) => failed.push(format!("{} ({})", shortcut, e)),
+        }
+    }
+    SHORTCUTS_ACTIVE.store(true, Ordering::SeqCst);
+    log::info!("Registered {} global shortcuts", registered.len());
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Failed to register hotkeys: {}", failed.join(", ")))
+    }
+}
 
-    // Unregister first in case already registered
-    let _ = shortcut_manager.unregister("F3");
-    let _ = shortcut_manager.unregister("F5");
-
-    // Register F3 for visibility toggle
-    shortcut_manager
-        .register("F3")
-        .map_err(|e| format!("Failed to register F3 hotkey: {}", e))?;
-
-    // Register F5 for mode toggle
-    shortcut_manager
-        .register("F5")
-        .map_err(|e| format!("Failed to register F5 hotkey: {}", e))?;
+/// Re-register the global shortcuts after the bindings changed.
+/// Does nothing while the low-level keyboard hook handles hotkeys.
+pub fn refresh_shortcuts<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<(), String> {
+    if !SHORTCUTS_ACTIVE.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+    register_shortcuts(app)
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hotkey::registry::TOGGLE_MODE;
+
+    #[test]
+    fn test_configured_shortcuts() {
+        let mut hotkeys = HotkeySettings::default();
+        let shortcuts = configured_shortcuts(&hotkeys);
+        assert_eq!(
+            shortcuts,
+            vec![
+                Shortcut::new(Some(Modifiers::empty()), Code::F3),
+                Shortcut::new(Some(Modifiers::empty()), Code::F5),
+                Shortcut::new(Some(Modifiers::CONTROL), Code::KeyT),
+                Shortcut::new(Some(Modifiers::CONTROL), Code::KeyY),
+            ]
+        );
+
+        // Both keys of a sequence are registered
+        hotkeys.set(
+            TOGGLE_MODE,
+            HotkeyBinding::new("K", 0x4B)
+                .with_ctrl()
+                .then(HotkeyBinding::new("F6", 0x75)),
+        );
+        let shortcuts = configured_shortcuts(&hotkeys);
+        assert!(shortcuts.contains(&Shortcut::new(Some(Modifiers::CONTROL), Code::KeyK)));
+        assert!(shortcuts.contains(&Shortcut::new(None, Code::F6)));
+        assert!(!shortcuts.contains(&Shortcut::new(None, Code::F5)));
+    }
 }
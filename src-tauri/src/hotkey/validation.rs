@@ -0,0 +1,538 @@
+// Hotkey binding validation
+// Checks a HotkeySettings for duplicate bindings, keys that cannot or should not be
+// bound, and key names that disagree with their VK code. Used by update_hotkeys and
+// when user-settings.json is loaded, so bad bindings never reach the keyboard hook.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::keys::{self, VK_DELETE, VK_ESCAPE, VK_F4, VK_TAB};
+use super::registry;
+use crate::settings::types::{HotkeyBinding, HotkeySettings, HotkeyTrigger};
+use crate::settings::validation::DiagnosticSeverity;
+
+/// Kind of problem found in a hotkey binding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HotkeyConflictKind {
+    /// Two or more actions share the same key combination (a warning when one
+    /// binding is only the first key of another action's sequence or double-tap)
+    Duplicate,
+    /// Key code outside the VK range or empty key name
+    InvalidKey,
+    /// Key name does not match the key code (hand-edited settings file)
+    KeyMismatch,
+    /// Modifier-only key or a combination reserved by Windows
+    Reserved,
+    /// Unmodified key Star Citizen uses in its default bindings
+    GameKey,
+    /// Unsupported sequence/trigger combination or timeout out of range
+    InvalidTrigger,
+}
+
+/// A single problem found in the hotkey bindings
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyConflict {
+    pub kind: HotkeyConflictKind,
+    pub severity: DiagnosticSeverity,
+    /// Action IDs involved (more than one for duplicates)
+    pub actions: Vec<String>,
+    /// Binding label, e.g. "Ctrl+T"
+    pub binding: String,
+    pub message: String,
+}
+
+/// Result of validating a set of hotkey bindings
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyValidationReport {
+    /// False if any conflict is an error (the bindings must not be applied)
+    pub valid: bool,
+    pub conflicts: Vec<HotkeyConflict>,
+}
+
+impl HotkeyValidationReport {
+    /// Actions with at least one error-level conflict
+    pub fn invalid_actions(&self) -> Vec<&str> {
+        let mut actions: Vec<&str> = self
+            .conflicts
+            .iter()
+            .filter(|c| c.severity == DiagnosticSeverity::Error)
+            .flat_map(|c| c.actions.iter().map(String::as_str))
+            .collect();
+        actions.sort_unstable();
+        actions.dedup();
+        actions
+    }
+}
+
+// Windows shortcuts as (key code, ctrl, shift, alt)
+const RESERVED_COMBINATIONS: &[(u32, bool, bool, bool)] = &[
+    (VK_DELETE, true, false, true),  // Ctrl+Alt+Delete
+    (VK_F4, false, false, true),     // Alt+F4
+    (VK_TAB, false, false, true),    // Alt+Tab
+    (VK_TAB, true, false, true),     // Ctrl+Alt+Tab
+    (VK_ESCAPE, true, false, false), // Ctrl+Escape (Start menu)
+    (VK_ESCAPE, true, true, false),  // Ctrl+Shift+Escape (Task Manager)
+];
+
+// Star Citizen default keyboard bindings (flight, on-foot and mobiGlas)
+const GAME_KEYS: &[(u32, &str)] = &[
+    (0x57, "forward / throttle"),
+    (0x41, "strafe left"),
+    (0x53, "backward / throttle"),
+    (0x44, "strafe right"),
+    (0x51, "roll left"),
+    (0x45, "roll right"),
+    (0x52, "reload"),
+    (0x46, "interact"),
+    (0x43, "crouch"),
+    (0x56, "toggle view"),
+    (0x58, "space brake"),
+    (0x5A, "prone"),
+    (0x47, "power"),
+    (0x42, "quantum travel"),
+    (0x4E, "landing gear"),
+    (0x4D, "star map"),
+    (0x20, "jump / strafe up"),
+    (VK_TAB, "inventory"),
+    (VK_ESCAPE, "game menu"),
+    (0x70, "mobiGlas"),
+    (0x71, "star map"),
+    (VK_F4, "camera"),
+];
+
+/// Accepted range for sequence, hold and double-tap timeouts (milliseconds)
+const MIN_TIMEOUT_MS: u64 = 50;
+const MAX_TIMEOUT_MS: u64 = 5_000;
+
+fn is_unmodified(binding: &HotkeyBinding) -> bool {
+    !binding.ctrl && !binding.shift && !binding.alt
+}
+
+// Problems with one key of a binding as (kind, severity, message)
+fn step_conflicts(
+    action: &str,
+    step: &HotkeyBinding,
+) -> Vec<(HotkeyConflictKind, DiagnosticSeverity, String)> {
+    if step.key.trim().is_empty() || step.key_code == 0 || step.key_code > 0xFE {
+        return vec![(
+            HotkeyConflictKind::InvalidKey,
+            DiagnosticSeverity::Error,
+            format!(
+                "{}: '{}' (key code {}) is not a valid key",
+                action, step.key, step.key_code
+            ),
+        )];
+    }
+
+    let mut conflicts = Vec::new();
+    // Only the keys themselves: "Hold F3" is reported as F3
+    let keys_label = HotkeyBinding {
+        trigger: HotkeyTrigger::Press,
+        next: None,
+        ..step.clone()
+    }
+    .label();
+
+    if let Some(expected) = keys::key_code_for_name(&step.key) {
+        if expected != step.key_code {
+            conflicts.push((
+                HotkeyConflictKind::KeyMismatch,
+                DiagnosticSeverity::Error,
+                format!(
+                    "{}: key '{}' has key code {:#04X} but {:#04X} was configured",
+                    action, step.key, expected, step.key_code
+                ),
+            ));
+        }
+    }
+
+    if keys::is_modifier_key_code(step.key_code) {
+        conflicts.push((
+            HotkeyConflictKind::Reserved,
+            DiagnosticSeverity::Error,
+            format!("{}: a modifier key cannot be used alone", action),
+        ));
+    } else if RESERVED_COMBINATIONS.contains(&keys_of(step)) {
+        conflicts.push((
+            HotkeyConflictKind::Reserved,
+            DiagnosticSeverity::Error,
+            format!("{}: {} is a reserved system shortcut", action, keys_label),
+        ));
+    } else if is_unmodified(step) {
+        if let Some((_, usage)) = GAME_KEYS.iter().find(|(code, _)| *code == step.key_code) {
+            conflicts.push((
+                HotkeyConflictKind::GameKey,
+                DiagnosticSeverity::Warning,
+                format!(
+                    "{}: {} is a Star Citizen key ({}) and will also trigger in game",
+                    action, keys_label, usage
+                ),
+            ));
+        }
+    }
+
+    conflicts
+}
+
+// Problems with a single binding, independent of the other actions
+fn binding_conflicts(action: &str, binding: &HotkeyBinding) -> Vec<HotkeyConflict> {
+    let conflict = |(kind, severity, message)| HotkeyConflict {
+        kind,
+        severity,
+        actions: vec![action.to_string()],
+        binding: binding.label(),
+        message,
+    };
+    let mut conflicts = Vec::new();
+
+    if let Some(next) = &binding.next {
+        if !binding.trigger.is_press()
+            || !next.trigger.is_press()
+            || next.next.is_some()
+            || next.timeout_ms.is_some()
+        {
+            conflicts.push(conflict((
+                HotkeyConflictKind::InvalidTrigger,
+                DiagnosticSeverity::Error,
+                format!(
+                    "{}: sequences are limited to two key presses without hold or double-tap",
+                    action
+                ),
+            )));
+        }
+    }
+    if let Some(timeout_ms) = binding.timeout_ms {
+        if !(MIN_TIMEOUT_MS..=MAX_TIMEOUT_MS).contains(&timeout_ms) {
+            conflicts.push(conflict((
+                HotkeyConflictKind::InvalidTrigger,
+                DiagnosticSeverity::Error,
+                format!(
+                    "{}: timeout of {}ms is outside {}-{}ms",
+                    action, timeout_ms, MIN_TIMEOUT_MS, MAX_TIMEOUT_MS
+                ),
+            )));
+        }
+    }
+
+    conflicts.extend(step_conflicts(action, binding).into_iter().map(conflict));
+    if let Some(next) = &binding.next {
+        conflicts.extend(step_conflicts(action, next).into_iter().map(conflict));
+    }
+    conflicts
+}
+
+fn keys_of(binding: &HotkeyBinding) -> (u32, bool, bool, bool) {
+    (binding.key_code, binding.ctrl, binding.shift, binding.alt)
+}
+
+/// Validate all bindings, including duplicates across actions
+pub fn validate_hotkeys(hotkeys: &HotkeySettings) -> HotkeyValidationReport {
+    type Signature = (
+        (u32, bool, bool, bool),
+        HotkeyTrigger,
+        Option<(u32, bool, bool, bool)>,
+    );
+    let mut conflicts = Vec::new();
+    let mut by_signature: BTreeMap<Signature, Vec<&str>> = BTreeMap::new();
+
+    for (action, binding) in hotkeys.iter() {
+        conflicts.extend(binding_conflicts(action, binding));
+        let signature = (
+            keys_of(binding),
+            binding.trigger,
+            binding.next.as_deref().map(keys_of),
+        );
+        by_signature.entry(signature).or_default().push(action);
+    }
+
+    for actions in by_signature.values().filter(|a| a.len() > 1) {
+        let label = hotkeys
+            .get(actions[0])
+            .map(|b| b.label())
+            .unwrap_or_default();
+        conflicts.push(HotkeyConflict {
+            kind: HotkeyConflictKind::Duplicate,
+            severity: DiagnosticSeverity::Error,
+            actions: actions.iter().map(|a| a.to_string()).collect(),
+            message: format!(
+                "{} is bound to more than one action: {}",
+                label,
+                actions.join(", ")
+            ),
+            binding: label,
+        });
+    }
+
+    // A plain press that is also the first key of a sequence, hold or double-tap
+    // fires on the way to the other action
+    for (action, binding) in hotkeys.iter() {
+        if binding.trigger.is_press() && binding.next.is_none() {
+            continue;
+        }
+        for (other, plain) in hotkeys.iter() {
+            if other == action
+                || !plain.trigger.is_press()
+                || plain.next.is_some()
+                || !plain.same_keys(binding)
+            {
+                continue;
+            }
+            conflicts.push(HotkeyConflict {
+                kind: HotkeyConflictKind::Duplicate,
+                severity: DiagnosticSeverity::Warning,
+                actions: vec![action.to_string(), other.to_string()],
+                binding: binding.label(),
+                message: format!(
+                    "{}: {} starts with {}, which also triggers {}",
+                    action,
+                    binding.label(),
+                    plain.label(),
+                    other
+                ),
+            });
+        }
+    }
+
+    HotkeyValidationReport {
+        valid: !conflicts
+            .iter()
+            .any(|c| c.severity == DiagnosticSeverity::Error),
+        conflicts,
+    }
+}
+
+/// Reset every action with an error-level conflict to its default binding.
+/// Of actions sharing a binding only the later ones are reset, and the bindings
+/// are validated again until no reset collides with a kept binding.
+/// Returns the report of the original bindings.
+pub fn reset_invalid_bindings(hotkeys: &mut HotkeySettings) -> HotkeyValidationReport {
+    let original = validate_hotkeys(hotkeys);
+    let defaults = registry::default_bindings();
+    let mut reset: Vec<String> = Vec::new();
+    let mut report = original.clone();
+
+    while !report.valid {
+        let is_reset = |action: &str| reset.iter().any(|r| r == action);
+        let mut to_reset: Vec<String> = Vec::new();
+        for conflict in report
+            .conflicts
+            .iter()
+            .filter(|c| c.severity == DiagnosticSeverity::Error)
+        {
+            let mut actions: Vec<&str> = conflict.actions.iter().map(String::as_str).collect();
+            if conflict.kind == HotkeyConflictKind::Duplicate {
+                // Keep a binding already back at its default, else the first one
+                let kept = actions.iter().position(|a| is_reset(a)).unwrap_or(0);
+                actions.remove(kept);
+            }
+            to_reset.extend(
+                actions
+                    .into_iter()
+                    .filter(|a| !is_reset(a))
+                    .map(str::to_string),
+            );
+        }
+        to_reset.sort_unstable();
+        to_reset.dedup();
+        // Nothing left that a reset can fix
+        if to_reset.is_empty() {
+            break;
+        }
+
+        for action in to_reset {
+            if let Some(default) = defaults.get(&action) {
+                hotkeys.set(&action, default.clone());
+            }
+            reset.push(action);
+        }
+        report = validate_hotkeys(hotkeys);
+    }
+    original
+}
+
+/// Validate hotkey bindings without applying them (for the settings panel)
+#[tauri::command]
+pub fn validate_hotkey_bindings(hotkeys: HotkeySettings) -> HotkeyValidationReport {
+    validate_hotkeys(&hotkeys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hotkey::registry::{
+        CHRONOMETER_RESET, CHRONOMETER_START_PAUSE, TOGGLE_MODE, TOGGLE_VISIBILITY,
+    };
+
+    fn kinds(report: &HotkeyValidationReport) -> Vec<HotkeyConflictKind> {
+        report.conflicts.iter().map(|c| c.kind).collect()
+    }
+
+    #[test]
+    fn test_default_bindings_are_valid() {
+        let report = validate_hotkeys(&HotkeySettings::default());
+        assert!(report.valid);
+        assert!(report.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_binding() {
+        let mut hotkeys = HotkeySettings::default();
+        hotkeys.set(TOGGLE_MODE, HotkeyBinding::new("F3", 0x72));
+
+        let report = validate_hotkeys(&hotkeys);
+        assert!(!report.valid);
+        assert_eq!(kinds(&report), vec![HotkeyConflictKind::Duplicate]);
+        assert_eq!(
+            report.conflicts[0].actions,
+            vec![TOGGLE_MODE, TOGGLE_VISIBILITY]
+        );
+        assert_eq!(report.conflicts[0].binding, "F3");
+    }
+
+    #[test]
+    fn test_invalid_and_mismatched_keys() {
+        let mut hotkeys = HotkeySettings::default();
+        hotkeys.set(TOGGLE_VISIBILITY, HotkeyBinding::new("F3", 0));
+        hotkeys.set(TOGGLE_MODE, HotkeyBinding::new("F5", 0x72));
+        // Names the frontend produces ("Arrow LEFT") and unknown names are accepted
+        hotkeys.set(
+            CHRONOMETER_START_PAUSE,
+            HotkeyBinding::new("Arrow LEFT", 0x25),
+        );
+        hotkeys.set(CHRONOMETER_RESET, HotkeyBinding::new("Ü", 0xBA).with_ctrl());
+
+        let report = validate_hotkeys(&hotkeys);
+        assert_eq!(
+            kinds(&report),
+            vec![
+                HotkeyConflictKind::KeyMismatch,
+                HotkeyConflictKind::InvalidKey
+            ]
+        );
+        assert_eq!(
+            report.invalid_actions(),
+            vec![TOGGLE_MODE, TOGGLE_VISIBILITY]
+        );
+    }
+
+    #[test]
+    fn test_reserved_and_game_keys() {
+        let mut hotkeys = HotkeySettings::default();
+        let mut alt_f4 = HotkeyBinding::new("F4", 0x73);
+        alt_f4.alt = true;
+        hotkeys.set(TOGGLE_VISIBILITY, alt_f4);
+        hotkeys.set(TOGGLE_MODE, HotkeyBinding::new("Control", 0x11));
+        hotkeys.set(CHRONOMETER_START_PAUSE, HotkeyBinding::new("M", 0x4D));
+        // Modifiers make a game key safe
+        hotkeys.set(CHRONOMETER_RESET, HotkeyBinding::new("M", 0x4D).with_ctrl());
+
+        let report = validate_hotkeys(&hotkeys);
+        assert_eq!(
+            kinds(&report),
+            vec![
+                HotkeyConflictKind::GameKey,
+                HotkeyConflictKind::Reserved,
+                HotkeyConflictKind::Reserved
+            ]
+        );
+        assert_eq!(report.conflicts[0].severity, DiagnosticSeverity::Warning);
+        assert_eq!(
+            report.conflicts[2].message,
+            "toggleVisibility: Alt+F4 is a reserved system shortcut"
+        );
+    }
+
+    #[test]
+    fn test_sequence_and_trigger_bindings() {
+        let mut hotkeys = HotkeySettings::default();
+        // Ctrl+K, M: the unmodified second key is still a game key
+        hotkeys.set(
+            TOGGLE_MODE,
+            HotkeyBinding::new("K", 0x4B)
+                .with_ctrl()
+                .then(HotkeyBinding::new("M", 0x4D)),
+        );
+        // Double-tap F3 shares its first press with toggleVisibility
+        hotkeys.set(
+            CHRONOMETER_RESET,
+            HotkeyBinding::new("F3", 0x72).with_trigger(HotkeyTrigger::DoubleTap),
+        );
+        let report = validate_hotkeys(&hotkeys);
+        assert!(report.valid);
+        assert_eq!(
+            kinds(&report),
+            vec![HotkeyConflictKind::GameKey, HotkeyConflictKind::Duplicate]
+        );
+        assert_eq!(
+            report.conflicts[1].message,
+            "chronometerReset: Double-tap F3 starts with F3, which also triggers toggleVisibility"
+        );
+
+        // Three steps, a held sequence and silly timeouts are rejected
+        let mut held = HotkeyBinding::new("K", 0x4B)
+            .with_ctrl()
+            .then(HotkeyBinding::new("N", 0x4E).then(HotkeyBinding::new("O", 0x4F)));
+        held.timeout_ms = Some(10);
+        hotkeys.set(TOGGLE_MODE, held);
+        let report = validate_hotkeys(&hotkeys);
+        assert!(!report.valid);
+        assert_eq!(
+            report.invalid_actions(),
+            vec![TOGGLE_MODE],
+            "{:?}",
+            report.conflicts
+        );
+        assert_eq!(
+            kinds(&report)
+                .iter()
+                .filter(|k| **k == HotkeyConflictKind::InvalidTrigger)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_reset_invalid_bindings() {
+        let mut hotkeys = HotkeySettings::default();
+        hotkeys.set(TOGGLE_MODE, HotkeyBinding::new("F3", 0x72));
+        hotkeys.set(CHRONOMETER_RESET, HotkeyBinding::new("W", 0x57));
+
+        let report = reset_invalid_bindings(&mut hotkeys);
+        assert!(!report.valid);
+        assert_eq!(hotkeys.get(TOGGLE_MODE).unwrap().label(), "F5");
+        assert_eq!(hotkeys.get(TOGGLE_VISIBILITY).unwrap().label(), "F3");
+        // Warnings are kept
+        assert_eq!(hotkeys.get(CHRONOMETER_RESET).unwrap().label(), "W");
+        assert!(validate_hotkeys(&hotkeys).valid);
+    }
+
+    #[test]
+    fn test_reset_keeps_first_duplicate_and_revalidates() {
+        let mut hotkeys = HotkeySettings::default();
+        hotkeys.set(CHRONOMETER_RESET, HotkeyBinding::new("F8", 0x77));
+        hotkeys.set(CHRONOMETER_START_PAUSE, HotkeyBinding::new("F8", 0x77));
+        reset_invalid_bindings(&mut hotkeys);
+        assert_eq!(hotkeys.get(CHRONOMETER_RESET).unwrap().label(), "F8");
+        assert_eq!(
+            hotkeys.get(CHRONOMETER_START_PAUSE).unwrap().label(),
+            "Ctrl+T"
+        );
+
+        // The reset default collides with the kept binding, which is reset in turn
+        let mut ctrl_t = HotkeyBinding::new("T", 0x54);
+        ctrl_t.ctrl = true;
+        hotkeys.set(CHRONOMETER_RESET, ctrl_t.clone());
+        hotkeys.set(CHRONOMETER_START_PAUSE, ctrl_t);
+        reset_invalid_bindings(&mut hotkeys);
+        assert_eq!(
+            hotkeys.get(CHRONOMETER_START_PAUSE).unwrap().label(),
+            "Ctrl+T"
+        );
+        assert_eq!(hotkeys.get(CHRONOMETER_RESET).unwrap().label(), "Ctrl+Y");
+        assert!(validate_hotkeys(&hotkeys).valid);
+    }
+}
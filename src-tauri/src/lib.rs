@@ -16,8 +16,13 @@ pub mod hotkey;
 // ============================================================================
 // Feature modules - Domain-specific functionality
 // ============================================================================
+pub mod alarms;
 pub mod browser;
+pub mod gamelog;
+pub mod remote;
+pub mod rules;
 pub mod settings;
+pub mod timers;
 pub mod update;
 
 // ============================================================================
@@ -35,7 +40,10 @@ pub mod commands;
 // ============================================================================
 use core::types::{OverlayReadyPayload, Position};
 use core::OverlayState;
-use persistence::{delete_window_content, load_state, save_state, save_window_content};
+use persistence::{
+    delete_window_content, list_state_snapshots, load_state, restore_state_snapshot, save_state,
+    save_window_content,
+};
 use tauri::{Emitter, Manager};
 
 // T002 (039): Helper function to get prevent-default plugin with conditional debug/release configuration
@@ -83,7 +91,12 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
-        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            // Forward command-line commands (e.g. --toggle-visibility) to this instance
+            // without stealing focus from the game
+            if remote::handle_second_instance(app, &argv) {
+                return;
+            }
             // Focus existing window when second instance is launched
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.set_focus();
@@ -115,12 +128,30 @@ pub fn run() {
             commands::toggle_visibility,
             commands::toggle_mode,
             commands::get_target_window_info,
+            commands::diagnose_target_detection,
             commands::dismiss_error_modal,
             // Persistence commands
             load_state,
             save_state,
             save_window_content,
             delete_window_content,
+            list_state_snapshots,
+            restore_state_snapshot,
+            // Layout profile commands
+            persistence::profiles::list_profiles,
+            persistence::profiles::create_profile,
+            persistence::profiles::clone_profile,
+            persistence::profiles::rename_profile,
+            persistence::profiles::delete_profile,
+            persistence::profiles::switch_profile,
+            // Workspace archive commands
+            persistence::workspace::export_workspace,
+            persistence::workspace::import_workspace,
+            persistence::queue::flush_persistence,
+            persistence::history::list_window_history,
+            persistence::history::restore_window_revision,
+            persistence::sessions::list_play_sessions,
+            persistence::sessions::get_play_session_stats,
             // Logging commands
             logging::cleanup_old_logs,
             logging::get_log_file_path,
@@ -128,10 +159,32 @@ pub fn run() {
             // Settings commands
             settings::runtime::get_settings_command,
             settings::runtime::get_settings_sources,
+            settings::validation::validate_settings,
             // T011 (038): Settings panel commands
             settings::user::load_user_settings,
             settings::user::save_user_settings,
             settings::user::update_hotkeys,
+            hotkey::registry::list_hotkey_actions,
+            hotkey::validation::validate_hotkey_bindings,
+            // Backend timers (chronometer and named stopwatches/countdowns)
+            timers::list_timers,
+            timers::create_timer,
+            timers::start_timer,
+            timers::pause_timer,
+            timers::toggle_timer,
+            timers::reset_timer,
+            timers::lap_timer,
+            timers::set_timer_elapsed,
+            timers::delete_timer,
+            // Alarms and reminders
+            alarms::list_alarms,
+            alarms::list_fired_alarms,
+            alarms::create_alarm,
+            alarms::delete_alarm,
+            // Automation rules (rules.toml)
+            rules::list_rules,
+            rules::reload_rules,
+            rules::dry_run_rules,
             settings::window::open_settings_window,
             // T007 (040): Browser WebView commands
             browser::create_browser_webview,
@@ -172,10 +225,20 @@ pub fn run() {
         .setup(|app| {
             let handle = app.handle().clone();
 
+            // Narrow the log plugin's filter to the configured level (adjustable on reload)
+            logging::apply_log_level(logging::get_log_level());
+
             // Log application startup
             log::info!("RAIC Overlay starting up");
             log::debug!("Log level: {:?}", logging::get_log_level());
 
+            // Report where each setting came from and any problems in settings.toml
+            // (settings are loaded before the logger exists, so log them here)
+            settings::runtime::log_settings_sources(&settings::get_settings());
+            settings::validation::log_validation_report(
+                &settings::validation::validate_settings_file(),
+            );
+
             // Clean up old log files on startup
             if let Err(e) = logging::cleanup_old_logs(handle.clone()) {
                 log::warn!("Failed to cleanup old logs: {}", e);
@@ -184,6 +247,21 @@ pub fn run() {
             // T026 (038): Initialize user settings cache
             settings::user::init_user_settings(&handle);
 
+            // Start the write-behind persistence queue flush timer
+            persistence::queue::start_flush_timer();
+
+            // Timers live in the backend and keep running across webview reloads
+            timers::start_timer_service(&handle);
+
+            // Alarm scheduler; fires alarms that came due while the app was closed
+            alarms::start_alarm_service(&handle);
+
+            // Watch settings.toml and apply changes without a restart
+            settings::reload::start_settings_watcher(handle.clone());
+
+            // Local control API for companion apps (opt-in via [control_api] in settings.toml)
+            remote::start_control_api(&handle);
+
             // T006 (054): Conditionally open Settings panel on startup
             // If start_minimized is false (default), show Settings panel
             if !settings::user::get_start_minimized() {
@@ -198,14 +276,14 @@ pub fn run() {
                 log::debug!("start_minimized=true, skipping Settings panel on startup");
             }
 
-            // T018 (029): Start low-level keyboard hook for F3/F5 hotkeys
+            // T018 (029): Start low-level keyboard hook for the configured hotkeys
             // This replaces global shortcut registration which doesn't work with Star Citizen
             #[cfg(windows)]
             {
                 if platform::keyboard_hook::start_keyboard_hook(handle.clone()) {
                     log::info!("Low-level keyboard hook started successfully");
                 } else {
-                    // Fallback to global shortcuts if hook fails
+                    // Fallback to global shortcuts for every configured binding if hook fails
                     log::warn!("Low-level keyboard hook failed, falling back to global shortcuts");
                     if let Err(e) = hotkey::register_shortcuts(&handle) {
                         log::warn!("Failed to register fallback shortcuts: {}", e);
@@ -224,8 +302,17 @@ pub fn run() {
                 log::error!("Failed to setup tray icon: {}", e);
             }
 
+            // Follow Game.log while the game runs (listens for the process monitor's events)
+            gamelog::start_game_log_watcher(&handle);
+
+            // Record play sessions; closes sessions left open by a crash first
+            persistence::sessions::start_session_recorder(&handle);
+
+            // User-defined automation rules (rules.toml next to settings.toml)
+            rules::start_rules_engine(&handle);
+
             // T048 (028): Start process monitor for automatic game detection
-            #[cfg(windows)]
+            #[cfg(any(windows, target_os = "linux"))]
             platform::process_monitor::start_process_monitor(handle.clone());
 
             // Start browser WebView URL polling for tracking navigation changes
@@ -264,8 +351,8 @@ pub fn run() {
 
             log::info!("RAIC Overlay initialized successfully");
 
-            // T026: Start focus monitor (Windows only)
-            #[cfg(windows)]
+            // T026: Start focus monitor (Windows and Linux/X11)
+            #[cfg(any(windows, target_os = "linux"))]
             platform::focus_monitor::start_focus_monitor(handle.clone());
 
             // T010-T011 (051): Trigger update check after 3 second delay
@@ -18,14 +18,25 @@ pub fn get_log_level() -> LevelFilter {
     settings::get_settings().log_level
 }
 
+/// Apply a log level filter at runtime (e.g. after settings.toml is reloaded).
+/// The log plugin passes every level through, so the global max level is the
+/// effective filter.
+pub fn apply_log_level(log_level: LevelFilter) {
+    log::set_max_level(log_level);
+    log::info!("Log level set to {:?}", log_level);
+}
+
 /// Build the logging plugin with configured targets.
 /// - Logs to file in app log directory (uses default "RAIC Overlay.log" from productName)
 /// - 10MB max file size with KeepAll rotation strategy
 /// - Custom JSON formatter for structured logs
 /// - Adds Stdout target when log level is DEBUG or INFO for development visibility
+///
+/// The plugin itself accepts all levels; call `apply_log_level` once the app is
+/// set up so the configured level can later be changed without a restart.
 pub fn build_log_plugin(log_level: LevelFilter) -> tauri::plugin::TauriPlugin<tauri::Wry> {
     let mut builder = tauri_plugin_log::Builder::new()
-        .level(log_level)
+        .level(LevelFilter::Trace)
         .max_file_size(MAX_FILE_SIZE)
         .rotation_strategy(RotationStrategy::KeepAll)
         // Custom JSON formatter for structured output
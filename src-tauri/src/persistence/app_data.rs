@@ -0,0 +1,131 @@
+//! App Data Files
+//!
+//! JSON files in the app data directory that belong to the whole app rather than to a
+//! layout profile (timers.json, alarms.json), each owned by a backend service. A file
+//! that cannot be parsed is moved aside before the service starts from its defaults.
+//! Without an app data directory the services still run, but nothing is saved.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::commands::{atomic_write, preserve_corrupt_file};
+
+/// A JSON file in the app data directory
+pub struct AppDataFile {
+    name: &'static str,
+    /// None when there is no app data directory
+    path: Option<PathBuf>,
+}
+
+impl AppDataFile {
+    /// `name` in the app data directory, which is created if needed
+    pub fn open(app: &AppHandle, name: &'static str) -> Self {
+        match app.path().app_data_dir() {
+            Ok(dir) => Self::in_dir(&dir, name),
+            Err(e) => {
+                log::warn!(
+                    "{} will not be saved: failed to get app data dir: {}",
+                    name,
+                    e
+                );
+                Self { name, path: None }
+            }
+        }
+    }
+
+    fn in_dir(dir: &Path, name: &'static str) -> Self {
+        if let Err(e) = fs::create_dir_all(dir) {
+            log::warn!("Failed to create data directory: {}", e);
+        }
+        Self {
+            name,
+            path: Some(dir.join(name)),
+        }
+    }
+
+    /// Parse the file. A missing file gives the defaults, and so does a file that cannot
+    /// be parsed, after it is moved aside.
+    pub fn load<T: Default>(&self, parse: impl FnOnce(&str) -> Result<T, String>) -> T {
+        let Some(json) = self
+            .path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+        else {
+            return T::default();
+        };
+        parse(&json).unwrap_or_else(|e| {
+            log::warn!("{}; starting from the defaults", e);
+            if let Some(path) = &self.path {
+                preserve_corrupt_file(path);
+            }
+            T::default()
+        })
+    }
+
+    /// Write the serialized contents (a serialization error is logged like a write error)
+    pub fn save(&self, json: Result<String, String>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Err(e) = json.and_then(|json| atomic_write(path, json.as_bytes())) {
+            log::warn!("Failed to save {}: {}", self.name, e);
+        }
+    }
+}
+
+/// Emit an event of a service. Called after the service state is unlocked, so listeners
+/// can call back into the service.
+pub fn emit_event<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    if let Err(e) = app.emit(event, payload) {
+        log::error!("Failed to emit {} event: {}", event, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::test_support::TempDir;
+
+    fn parse(json: &str) -> Result<Vec<u32>, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid numbers file: {}", e))
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = TempDir::new("app-data");
+        let file = AppDataFile::in_dir(&dir, "numbers.json");
+        assert_eq!(file.load(parse), Vec::<u32>::new());
+
+        file.save(Ok("[1, 2]".to_string()));
+        assert_eq!(file.load(parse), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_unreadable_file_is_kept_aside() {
+        let dir = TempDir::new("app-data-corrupt");
+        let file = AppDataFile::in_dir(&dir, "numbers.json");
+        fs::write(dir.join("numbers.json"), "[1, ").unwrap();
+
+        assert_eq!(file.load(parse), Vec::<u32>::new());
+        let names: Vec<String> = fs::read_dir(&*dir)
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names.len(), 1);
+        assert!(names[0].starts_with("numbers.corrupt-"));
+    }
+
+    #[test]
+    fn test_nothing_saved_without_data_dir() {
+        let file = AppDataFile {
+            name: "numbers.json",
+            path: None,
+        };
+        file.save(Ok("[1]".to_string()));
+        assert_eq!(file.load(parse), Vec::<u32>::new());
+    }
+}
@@ -0,0 +1,400 @@
+//! Snapshot Ring for Persisted State
+//!
+//! Keeps a rotating set of timestamped snapshots of `state.json` and all
+//! `window-{id}.json` files under `snapshots/` in the app data directory.
+//! Snapshots are taken before saves (throttled), only from a state file that
+//! parses cleanly, so every snapshot is a known-good restore point.
+//!
+//! @feature 010-state-persistence-system
+
+use super::commands::atomic_write;
+use super::migrations;
+use super::types::{PersistedState, SnapshotInfo};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+const STATE_FILE: &str = "state.json";
+const WINDOW_FILE_PREFIX: &str = "window-";
+const SNAPSHOT_DIR: &str = "snapshots";
+
+/// Snapshot directory names are UTC timestamps, which sort chronologically.
+const SNAPSHOT_ID_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// Maximum number of snapshots kept in the ring
+pub const MAX_SNAPSHOTS: usize = 10;
+
+/// Minimum time between automatic snapshots taken on save
+pub const SNAPSHOT_MIN_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Serializes snapshot creation, pruning and restore across concurrent saves
+static SNAPSHOT_LOCK: Mutex<()> = Mutex::new(());
+
+fn snapshots_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(SNAPSHOT_DIR)
+}
+
+fn is_window_file(file_name: &str) -> bool {
+    file_name.starts_with(WINDOW_FILE_PREFIX) && file_name.ends_with(".json")
+}
+
+/// Check whether state.json contents can be loaded (after migration).
+pub fn is_valid_state(contents: &str) -> bool {
+    let Ok(mut value) = serde_json::from_str::<serde_json::Value>(contents) else {
+        return false;
+    };
+    if migrations::migrate_state(&mut value).is_err() {
+        return false;
+    }
+    serde_json::from_value::<PersistedState>(value).is_ok()
+}
+
+/// Parse a snapshot ID back into its creation time.
+fn parse_snapshot_id(id: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::NaiveDateTime::parse_from_str(id, SNAPSHOT_ID_FORMAT)
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+/// Snapshot IDs in the ring, newest first.
+fn snapshot_ids(data_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(snapshots_dir(data_dir)) else {
+        return Vec::new();
+    };
+
+    let mut ids: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|id| parse_snapshot_id(id).is_some())
+        .collect();
+
+    ids.sort();
+    ids.reverse();
+    ids
+}
+
+/// Build the listing entry for a snapshot directory.
+fn describe_snapshot(data_dir: &Path, id: &str) -> Option<SnapshotInfo> {
+    let dir = snapshots_dir(data_dir).join(id);
+    let created_at = parse_snapshot_id(id)?;
+
+    let mut window_count = 0u32;
+    let mut total_bytes = 0u64;
+    for entry in fs::read_dir(&dir).ok()?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if is_window_file(&name) {
+            window_count += 1;
+        }
+        total_bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+    }
+
+    let valid = fs::read_to_string(dir.join(STATE_FILE))
+        .map(|c| is_valid_state(&c))
+        .unwrap_or(false);
+
+    Some(SnapshotInfo {
+        id: id.to_string(),
+        created_at: created_at.to_rfc3339(),
+        window_count,
+        total_bytes,
+        valid,
+    })
+}
+
+/// List all snapshots, newest first.
+pub fn list_snapshots(data_dir: &Path) -> Vec<SnapshotInfo> {
+    snapshot_ids(data_dir)
+        .iter()
+        .filter_map(|id| describe_snapshot(data_dir, id))
+        .collect()
+}
+
+/// Copy the current state.json and window files into a new snapshot.
+///
+/// Returns `Ok(None)` when there is nothing worth keeping: no state file, a
+/// state file that fails to parse, or (unless `force`) a snapshot newer than
+/// `SNAPSHOT_MIN_INTERVAL` already exists.
+fn create_snapshot_locked(data_dir: &Path, force: bool) -> Result<Option<String>, String> {
+    let state_path = data_dir.join(STATE_FILE);
+    let Ok(state_contents) = fs::read_to_string(&state_path) else {
+        return Ok(None);
+    };
+
+    if !is_valid_state(&state_contents) {
+        log::warn!("Skipping snapshot: current state.json is not valid");
+        return Ok(None);
+    }
+
+    let now = chrono::Utc::now();
+    if !force {
+        if let Some(newest) = snapshot_ids(data_dir)
+            .first()
+            .and_then(|id| parse_snapshot_id(id))
+        {
+            let age = now.signed_duration_since(newest);
+            if age
+                .to_std()
+                .map(|a| a < SNAPSHOT_MIN_INTERVAL)
+                .unwrap_or(true)
+            {
+                return Ok(None);
+            }
+        }
+    }
+
+    let id = now.format(SNAPSHOT_ID_FORMAT).to_string();
+    let dir = snapshots_dir(data_dir).join(&id);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create snapshot directory: {}", e))?;
+
+    fs::write(dir.join(STATE_FILE), &state_contents)
+        .map_err(|e| format!("Failed to write snapshot state: {}", e))?;
+
+    if let Ok(entries) = fs::read_dir(data_dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if is_window_file(&name) {
+                if let Err(e) = fs::copy(entry.path(), dir.join(&name)) {
+                    log::warn!("Failed to snapshot {}: {}", name, e);
+                }
+            }
+        }
+    }
+
+    log::info!("Created state snapshot {}", id);
+    Ok(Some(id))
+}
+
+/// Take a snapshot if the newest one is older than `SNAPSHOT_MIN_INTERVAL`.
+/// Called before each save so the ring holds the last known-good state.
+pub fn snapshot_if_due(data_dir: &Path) -> Result<Option<String>, String> {
+    let _guard = SNAPSHOT_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let created = create_snapshot_locked(data_dir, false)?;
+    prune_snapshots(data_dir, MAX_SNAPSHOTS);
+    Ok(created)
+}
+
+/// Take a snapshot unconditionally (still skipped if state.json is invalid).
+pub fn create_snapshot(data_dir: &Path) -> Result<Option<String>, String> {
+    let _guard = SNAPSHOT_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let created = create_snapshot_locked(data_dir, true)?;
+    prune_snapshots(data_dir, MAX_SNAPSHOTS);
+    Ok(created)
+}
+
+/// Delete the oldest snapshots so that at most `keep` remain.
+fn prune_snapshots(data_dir: &Path, keep: usize) {
+    for id in snapshot_ids(data_dir).into_iter().skip(keep) {
+        let dir = snapshots_dir(data_dir).join(&id);
+        if let Err(e) = fs::remove_dir_all(&dir) {
+            log::warn!("Failed to prune snapshot {}: {}", id, e);
+        } else {
+            log::debug!("Pruned state snapshot {}", id);
+        }
+    }
+}
+
+/// Replace state.json and all window files with the contents of a snapshot.
+fn restore_snapshot_locked(data_dir: &Path, id: &str) -> Result<(), String> {
+    if parse_snapshot_id(id).is_none() {
+        return Err(format!("Invalid snapshot id: {}", id));
+    }
+
+    let dir = snapshots_dir(data_dir).join(id);
+    let state_contents = fs::read_to_string(dir.join(STATE_FILE))
+        .map_err(|e| format!("Failed to read snapshot {}: {}", id, e))?;
+    if !is_valid_state(&state_contents) {
+        return Err(format!("Snapshot {} does not contain a valid state", id));
+    }
+
+    let snapshot_files: Vec<String> = fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read snapshot {}: {}", id, e))?
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| is_window_file(name))
+        .collect();
+
+    for name in &snapshot_files {
+        let content =
+            fs::read(dir.join(name)).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        atomic_write(&data_dir.join(name), &content)?;
+    }
+
+    atomic_write(&data_dir.join(STATE_FILE), state_contents.as_bytes())?;
+
+    // Remove window files that are not part of the snapshot only once its
+    // state is in place, so a failed restore never loses windows the current
+    // state still references
+    if let Ok(entries) = fs::read_dir(data_dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if is_window_file(&name) && !snapshot_files.contains(&name) {
+                if let Err(e) = fs::remove_file(entry.path()) {
+                    log::warn!("Failed to remove {} during restore: {}", name, e);
+                }
+            }
+        }
+    }
+
+    log::info!("Restored state snapshot {}", id);
+    Ok(())
+}
+
+/// Restore a snapshot chosen by the user.
+/// The current state is snapshotted first so the restore can be undone.
+pub fn restore_snapshot(data_dir: &Path, id: &str) -> Result<(), String> {
+    let _guard = SNAPSHOT_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+
+    if let Err(e) = create_snapshot_locked(data_dir, true) {
+        log::warn!("Failed to snapshot current state before restore: {}", e);
+    }
+
+    // Prune only after restoring so the ring cannot drop the snapshot being restored
+    let result = restore_snapshot_locked(data_dir, id);
+    prune_snapshots(data_dir, MAX_SNAPSHOTS);
+    result
+}
+
+/// Recover from a corrupt state.json by restoring the newest valid snapshot.
+///
+/// The corrupt file is kept as `state.corrupt-{timestamp}.json` for inspection.
+/// Returns the ID of the snapshot that was restored.
+pub fn recover_from_snapshot(data_dir: &Path) -> Result<String, String> {
+    let _guard = SNAPSHOT_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+
+    let id = snapshot_ids(data_dir)
+        .into_iter()
+        .find(|id| {
+            fs::read_to_string(snapshots_dir(data_dir).join(id).join(STATE_FILE))
+                .map(|c| is_valid_state(&c))
+                .unwrap_or(false)
+        })
+        .ok_or_else(|| "No valid snapshot available".to_string())?;
+
+    let state_path = data_dir.join(STATE_FILE);
+    if state_path.exists() {
+        let corrupt_path = data_dir.join(format!(
+            "state.corrupt-{}.json",
+            chrono::Utc::now().format("%Y%m%dT%H%M%S")
+        ));
+        if let Err(e) = fs::rename(&state_path, &corrupt_path) {
+            log::warn!("Failed to preserve corrupt state file: {}", e);
+        } else {
+            log::warn!("Corrupt state file preserved at {}", corrupt_path.display());
+        }
+    }
+
+    restore_snapshot_locked(data_dir, &id)?;
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::test_support::TempDir;
+    use crate::persistence::types::CURRENT_STATE_VERSION;
+
+    fn valid_state_json() -> String {
+        serde_json::to_string(&PersistedState::default()).unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_id_roundtrip() {
+        let now = chrono::Utc::now();
+        let id = now.format(SNAPSHOT_ID_FORMAT).to_string();
+        let parsed = parse_snapshot_id(&id).unwrap();
+        assert_eq!(parsed.timestamp_millis(), now.timestamp_millis());
+        assert!(parse_snapshot_id("not-a-snapshot").is_none());
+    }
+
+    #[test]
+    fn test_is_valid_state() {
+        assert!(is_valid_state(&valid_state_json()));
+        assert!(!is_valid_state("{ \"version\": "));
+        assert!(!is_valid_state(&format!(
+            "{{ \"version\": {} }}",
+            CURRENT_STATE_VERSION + 1
+        )));
+    }
+
+    #[test]
+    fn test_invalid_state_is_not_snapshotted() {
+        let dir = TempDir::new("backups-invalid");
+        fs::write(dir.join(STATE_FILE), "{ broken").unwrap();
+
+        assert_eq!(create_snapshot(&dir).unwrap(), None);
+        assert!(list_snapshots(&dir).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_if_due_is_throttled() {
+        let dir = TempDir::new("backups-throttle");
+        fs::write(dir.join(STATE_FILE), valid_state_json()).unwrap();
+
+        assert!(snapshot_if_due(&dir).unwrap().is_some());
+        assert!(snapshot_if_due(&dir).unwrap().is_none());
+        assert_eq!(list_snapshots(&dir).len(), 1);
+    }
+
+    #[test]
+    fn test_prune_keeps_newest() {
+        let dir = TempDir::new("backups-prune");
+        for id in [
+            "20250101T000000.000Z",
+            "20250102T000000.000Z",
+            "20250103T000000.000Z",
+        ] {
+            fs::create_dir_all(snapshots_dir(&dir).join(id)).unwrap();
+        }
+
+        prune_snapshots(&dir, 2);
+        assert_eq!(
+            snapshot_ids(&dir),
+            vec!["20250103T000000.000Z", "20250102T000000.000Z"]
+        );
+    }
+
+    #[test]
+    fn test_recover_from_corrupt_state() {
+        let dir = TempDir::new("backups-recover");
+        fs::write(dir.join(STATE_FILE), valid_state_json()).unwrap();
+        fs::write(dir.join("window-notes-1.json"), "{}").unwrap();
+        let id = create_snapshot(&dir).unwrap().unwrap();
+
+        fs::write(dir.join(STATE_FILE), "{ corrupted").unwrap();
+        fs::remove_file(dir.join("window-notes-1.json")).unwrap();
+
+        assert_eq!(recover_from_snapshot(&dir).unwrap(), id);
+        let restored = fs::read_to_string(dir.join(STATE_FILE)).unwrap();
+        assert!(is_valid_state(&restored));
+        assert!(dir.join("window-notes-1.json").exists());
+    }
+
+    #[test]
+    fn test_failed_restore_keeps_current_windows() {
+        let dir = TempDir::new("backups-failed-restore");
+        fs::write(dir.join(STATE_FILE), valid_state_json()).unwrap();
+        let id = create_snapshot(&dir).unwrap().unwrap();
+        // A snapshot window file that cannot be read fails the restore
+        fs::create_dir_all(snapshots_dir(&dir).join(&id).join("window-notes-1.json")).unwrap();
+        fs::write(dir.join("window-notes-2.json"), "{}").unwrap();
+
+        assert!(restore_snapshot_locked(&dir, &id).is_err());
+        assert!(dir.join("window-notes-2.json").exists());
+
+        fs::remove_dir_all(snapshots_dir(&dir).join(&id).join("window-notes-1.json")).unwrap();
+        restore_snapshot_locked(&dir, &id).unwrap();
+        assert!(!dir.join("window-notes-2.json").exists());
+    }
+
+    #[test]
+    fn test_recover_without_snapshots_fails() {
+        let dir = TempDir::new("backups-nosnap");
+        fs::write(dir.join(STATE_FILE), "{ corrupted").unwrap();
+
+        assert!(recover_from_snapshot(&dir).is_err());
+        // Corrupt file is left in place when nothing can be restored
+        assert!(dir.join(STATE_FILE).exists());
+    }
+}
@@ -2,29 +2,33 @@
 //!
 //! Provides Tauri commands for loading, saving, and deleting persisted state.
 //! All file operations use atomic writes (temp file + rename) to prevent corruption.
+//! Files from older schema versions are backed up and migrated on load.
 //!
 //! @feature 010-state-persistence-system
 
+use super::backups;
+use super::history;
+use super::migrations;
+use super::profiles;
+use super::queue;
 use super::types::*;
 use log::{error, info};
 use std::fs::{self, File};
 use std::io::{Read, Write};
 use std::path::PathBuf;
-use tauri::Manager;
 
 const STATE_FILE: &str = "state.json";
 const WINDOW_FILE_PREFIX: &str = "window-";
+const MIGRATION_BACKUP_DIR: &str = "migration-backups";
 
-/// Get the application data directory path.
-fn get_app_data_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    app.path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))
+/// Get the data directory of the active layout profile.
+fn get_profile_data_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    profiles::get_active_profile_dir(app)
 }
 
 /// Atomically write content to a file using temp file + rename pattern.
 /// This prevents corruption if the process crashes during write.
-fn atomic_write(path: &PathBuf, content: &[u8]) -> Result<(), String> {
+pub(crate) fn atomic_write(path: &PathBuf, content: &[u8]) -> Result<(), String> {
     let temp_path = path.with_extension("json.tmp");
 
     // Write to temp file
@@ -44,21 +48,177 @@ fn atomic_write(path: &PathBuf, content: &[u8]) -> Result<(), String> {
     Ok(())
 }
 
+/// Move a file that failed to load aside as `{name}.corrupt-{timestamp}.{ext}`, so the
+/// defaults saved next do not overwrite it. Returns the new path.
+pub(crate) fn preserve_corrupt_file(path: &std::path::Path) -> Option<PathBuf> {
+    let mut name = format!(
+        "{}.corrupt-{}",
+        path.file_stem()?.to_string_lossy(),
+        chrono::Utc::now().format("%Y%m%dT%H%M%S")
+    );
+    if let Some(extension) = path.extension() {
+        name.push('.');
+        name.push_str(&extension.to_string_lossy());
+    }
+    let corrupt_path = path.with_file_name(name);
+
+    match fs::rename(path, &corrupt_path) {
+        Ok(()) => {
+            log::warn!("Corrupt file preserved at {}", corrupt_path.display());
+            Some(corrupt_path)
+        }
+        Err(e) => {
+            log::warn!("Failed to preserve corrupt file {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Copy a file into the migration backup directory before it is rewritten.
+/// The backup name records the original schema version and a timestamp.
+fn backup_before_migration(
+    data_dir: &std::path::Path,
+    path: &std::path::Path,
+    from_version: u32,
+) -> Result<PathBuf, String> {
+    let backup_dir = data_dir.join(MIGRATION_BACKUP_DIR);
+    fs::create_dir_all(&backup_dir)
+        .map_err(|e| format!("Failed to create migration backup directory: {}", e))?;
+
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%S");
+    let backup_path = backup_dir.join(format!("{}.v{}.{}.json", stem, from_version, timestamp));
+
+    fs::copy(path, &backup_path)
+        .map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+
+    Ok(backup_path)
+}
+
+/// Parse a persisted JSON file, migrating it to the current schema first if needed.
+///
+/// When a migration runs, the original file is backed up and the migrated
+/// document is written back so the migration only happens once.
+fn read_and_migrate(
+    data_dir: &std::path::Path,
+    path: &PathBuf,
+    contents: &str,
+    migrate: impl Fn(&mut serde_json::Value) -> Result<Vec<MigrationRecord>, String>,
+    target_version: u32,
+) -> Result<(serde_json::Value, Vec<MigrationRecord>), String> {
+    let mut value: serde_json::Value =
+        serde_json::from_str(contents).map_err(|e| format!("Invalid JSON: {}", e))?;
+
+    if !migrations::needs_migration(&value, target_version) {
+        // Still validates the version is not newer than supported
+        let applied = migrate(&mut value)?;
+        return Ok((value, applied));
+    }
+
+    let from_version = migrations::document_version(&value);
+    let backup_path = backup_before_migration(data_dir, path, from_version)?;
+    info!(
+        "Backed up {} to {} before migration",
+        path.display(),
+        backup_path.display()
+    );
+
+    let applied = migrate(&mut value)?;
+    for record in &applied {
+        info!(
+            "Migrated {} v{} -> v{}: {}",
+            record.file, record.from_version, record.to_version, record.description
+        );
+    }
+
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize migrated file: {}", e))?;
+    atomic_write(path, json.as_bytes())?;
+
+    Ok((value, applied))
+}
+
+/// Parse and migrate state.json contents into a `PersistedState`.
+fn parse_state_file(
+    data_dir: &std::path::Path,
+    state_path: &PathBuf,
+    contents: &str,
+) -> Result<(PersistedState, Vec<MigrationRecord>), String> {
+    let (value, applied) = read_and_migrate(
+        data_dir,
+        state_path,
+        contents,
+        migrations::migrate_state,
+        CURRENT_STATE_VERSION,
+    )?;
+    let state = serde_json::from_value(value).map_err(|e| format!("Invalid state JSON: {}", e))?;
+    Ok((state, applied))
+}
+
+/// Replace a corrupt state.json with the newest valid snapshot and parse it.
+///
+/// Returns the restored snapshot ID and parsed state, or None if recovery is
+/// not possible. A state file written by a newer app version is not corrupt,
+/// so it is left untouched rather than rolled back.
+fn recover_state(
+    data_dir: &std::path::Path,
+    state_path: &PathBuf,
+    contents: &str,
+) -> Option<(String, (PersistedState, Vec<MigrationRecord>))> {
+    if serde_json::from_str::<serde_json::Value>(contents)
+        .map(|v| migrations::is_newer_than(&v, CURRENT_STATE_VERSION))
+        .unwrap_or(false)
+    {
+        log::warn!("State file is from a newer version, not recovering from snapshot");
+        return None;
+    }
+
+    let snapshot_id = match backups::recover_from_snapshot(data_dir) {
+        Ok(id) => id,
+        Err(e) => {
+            log::warn!("Could not recover state from snapshot: {}", e);
+            return None;
+        }
+    };
+
+    let restored = fs::read_to_string(state_path).ok()?;
+    match parse_state_file(data_dir, state_path, &restored) {
+        Ok(parsed) => {
+            log::warn!("Recovered state from snapshot {}", snapshot_id);
+            Some((snapshot_id, parsed))
+        }
+        Err(e) => {
+            error!("Restored snapshot {} failed to load: {}", snapshot_id, e);
+            None
+        }
+    }
+}
+
 /// Load the persisted state from disk on application startup.
 ///
 /// Returns the main state file and all window content files.
 /// If no state file exists, returns empty result (not an error).
+/// Files written by older schema versions are migrated (with a backup) first.
+/// If state.json is corrupt, the newest valid snapshot is restored instead.
 #[tauri::command]
 pub async fn load_state(app: tauri::AppHandle) -> Result<LoadStateResult, String> {
-    let data_dir = get_app_data_dir(&app)?;
+    let data_dir = get_profile_data_dir(&app)?;
     let state_path = data_dir.join(STATE_FILE);
 
+    // Make sure queued saves are on disk before reading
+    queue::flush_all();
+
     // If no state file exists, return empty result
     if !state_path.exists() {
         return Ok(LoadStateResult {
             success: true,
             state: None,
             window_contents: vec![],
+            migrations: vec![],
+            recovered_from_snapshot: None,
             error: None,
         });
     }
@@ -72,6 +232,8 @@ pub async fn load_state(app: tauri::AppHandle) -> Result<LoadStateResult, String
                 success: false,
                 state: None,
                 window_contents: vec![],
+                migrations: vec![],
+                recovered_from_snapshot: None,
                 error: Some(format!("Failed to open state file: {}", e)),
             });
         }
@@ -84,38 +246,69 @@ pub async fn load_state(app: tauri::AppHandle) -> Result<LoadStateResult, String
             success: false,
             state: None,
             window_contents: vec![],
+            migrations: vec![],
+            recovered_from_snapshot: None,
             error: Some(format!("Failed to read state file: {}", e)),
         });
     }
 
-    let state: PersistedState = match serde_json::from_str(&contents) {
-        Ok(s) => s,
+    let mut recovered_from_snapshot = None;
+    let (state, mut applied_migrations) = match parse_state_file(&data_dir, &state_path, &contents)
+    {
+        Ok(parsed) => parsed,
         Err(e) => {
-            error!("Invalid state JSON: {}", e);
-            return Ok(LoadStateResult {
-                success: false,
-                state: None,
-                window_contents: vec![],
-                error: Some(format!("Invalid state JSON: {}", e)),
-            });
+            error!("Failed to load state file: {}", e);
+            match recover_state(&data_dir, &state_path, &contents) {
+                Some((snapshot_id, parsed)) => {
+                    recovered_from_snapshot = Some(snapshot_id);
+                    parsed
+                }
+                None => {
+                    return Ok(LoadStateResult {
+                        success: false,
+                        state: None,
+                        window_contents: vec![],
+                        migrations: vec![],
+                        recovered_from_snapshot: None,
+                        error: Some(format!("Failed to load state file: {}", e)),
+                    });
+                }
+            }
         }
     };
 
     // Load window content files for each window in state
     let mut window_contents = Vec::new();
     for window in &state.windows {
-        let content_path = data_dir.join(format!("{}{}.json", WINDOW_FILE_PREFIX, window.id));
+        let file_name = format!("{}{}.json", WINDOW_FILE_PREFIX, window.id);
+        let content_path = data_dir.join(&file_name);
         if content_path.exists() {
             if let Ok(mut file) = File::open(&content_path) {
                 let mut content_str = String::new();
                 if file.read_to_string(&mut content_str).is_ok() {
-                    if let Ok(content) = serde_json::from_str::<WindowContentFile>(&content_str) {
-                        window_contents.push(content);
-                    } else {
-                        log::warn!(
-                            "Failed to parse window content file: {}",
-                            content_path.display()
-                        );
+                    let migrated = read_and_migrate(
+                        &data_dir,
+                        &content_path,
+                        &content_str,
+                        |value| migrations::migrate_window_content(value, &file_name),
+                        CURRENT_WINDOW_CONTENT_VERSION,
+                    );
+                    match migrated.and_then(|(value, applied)| {
+                        serde_json::from_value::<WindowContentFile>(value)
+                            .map(|content| (content, applied))
+                            .map_err(|e| e.to_string())
+                    }) {
+                        Ok((content, applied)) => {
+                            applied_migrations.extend(applied);
+                            window_contents.push(content);
+                        }
+                        Err(e) => {
+                            log::warn!(
+                                "Failed to parse window content file {}: {}",
+                                content_path.display(),
+                                e
+                            );
+                        }
                     }
                 }
             }
@@ -170,6 +363,8 @@ pub async fn load_state(app: tauri::AppHandle) -> Result<LoadStateResult, String
         success: true,
         state: Some(state),
         window_contents,
+        migrations: applied_migrations,
+        recovered_from_snapshot,
         error: None,
     })
 }
@@ -180,9 +375,12 @@ pub async fn load_state(app: tauri::AppHandle) -> Result<LoadStateResult, String
 #[tauri::command]
 pub async fn save_state(
     app: tauri::AppHandle,
-    state: PersistedState,
+    mut state: PersistedState,
 ) -> Result<SaveResult, String> {
-    let data_dir = get_app_data_dir(&app)?;
+    let data_dir = get_profile_data_dir(&app)?;
+
+    // The backend owns the schema version - always write the current one
+    state.version = CURRENT_STATE_VERSION;
 
     // Ensure directory exists
     if let Err(e) = fs::create_dir_all(&data_dir) {
@@ -206,15 +404,10 @@ pub async fn save_state(
         }
     };
 
-    if let Err(e) = atomic_write(&state_path, json.as_bytes()) {
-        error!("Failed to write state file: {}", e);
-        return Ok(SaveResult {
-            success: false,
-            error: Some(e),
-        });
-    }
+    // Written by the write-behind queue (coalesced with later saves)
+    queue::enqueue_write(&data_dir, state_path, json.into_bytes());
 
-    info!("State saved successfully");
+    log::debug!("State save queued");
     Ok(SaveResult {
         success: true,
         error: None,
@@ -226,7 +419,7 @@ pub async fn save_state(
 pub async fn save_window_content(
     app: tauri::AppHandle,
     window_id: String,
-    content: WindowContentFile,
+    mut content: WindowContentFile,
 ) -> Result<SaveResult, String> {
     // Validate window_id matches content
     if window_id != content.window_id {
@@ -236,7 +429,10 @@ pub async fn save_window_content(
         });
     }
 
-    let data_dir = get_app_data_dir(&app)?;
+    // The backend owns the schema version - always write the current one
+    content.version = CURRENT_WINDOW_CONTENT_VERSION;
+
+    let data_dir = get_profile_data_dir(&app)?;
 
     // Ensure directory exists
     if let Err(e) = fs::create_dir_all(&data_dir) {
@@ -258,12 +454,8 @@ pub async fn save_window_content(
         }
     };
 
-    if let Err(e) = atomic_write(&content_path, json.as_bytes()) {
-        return Ok(SaveResult {
-            success: false,
-            error: Some(e),
-        });
-    }
+    // Written by the write-behind queue (coalesced with later saves)
+    queue::enqueue_write(&data_dir, content_path, json.into_bytes());
 
     Ok(SaveResult {
         success: true,
@@ -277,9 +469,13 @@ pub async fn delete_window_content(
     app: tauri::AppHandle,
     window_id: String,
 ) -> Result<DeleteResult, String> {
-    let data_dir = get_app_data_dir(&app)?;
+    let data_dir = get_profile_data_dir(&app)?;
     let content_path = data_dir.join(format!("{}{}.json", WINDOW_FILE_PREFIX, window_id));
 
+    // A queued save must not recreate the file after it is deleted
+    queue::cancel_write(&content_path);
+    history::delete_history(&data_dir, &window_id);
+
     if !content_path.exists() {
         return Ok(DeleteResult {
             success: true,
@@ -302,3 +498,39 @@ pub async fn delete_window_content(
         error: None,
     })
 }
+
+/// List the snapshots in the state backup ring, newest first.
+#[tauri::command]
+pub async fn list_state_snapshots(app: tauri::AppHandle) -> Result<Vec<SnapshotInfo>, String> {
+    let data_dir = get_profile_data_dir(&app)?;
+    Ok(backups::list_snapshots(&data_dir))
+}
+
+/// Restore state.json and all window content files from a snapshot.
+///
+/// The current state is snapshotted first, so a restore can itself be undone.
+/// The frontend should call load_state afterwards to pick up the restored data.
+#[tauri::command]
+pub async fn restore_state_snapshot(
+    app: tauri::AppHandle,
+    snapshot_id: String,
+) -> Result<SaveResult, String> {
+    let data_dir = get_profile_data_dir(&app)?;
+
+    // Flush first so queued saves cannot overwrite the restored files
+    queue::flush_all();
+
+    if let Err(e) = backups::restore_snapshot(&data_dir, &snapshot_id) {
+        error!("Failed to restore snapshot {}: {}", snapshot_id, e);
+        return Ok(SaveResult {
+            success: false,
+            error: Some(e),
+        });
+    }
+
+    info!("State restored from snapshot {}", snapshot_id);
+    Ok(SaveResult {
+        success: true,
+        error: None,
+    })
+}
@@ -0,0 +1,463 @@
+//! Per-Window Content History
+//!
+//! Before a `window-{id}.json` file is overwritten, its previous contents are
+//! kept as a timestamped revision under `history/{id}/`. Revisions are
+//! throttled (at most one per `REVISION_MIN_INTERVAL`, except before the
+//! content is cleared or mostly deleted) and pruned by count and by total
+//! size, so an accidental "clear all" can be undone without letting the
+//! history grow without bound.
+//!
+//! @feature 010-state-persistence-system
+
+use super::commands::atomic_write;
+use super::migrations;
+use super::profiles;
+use super::queue;
+use super::types::{
+    RestoreRevisionResult, RevisionInfo, WindowContentFile, CURRENT_WINDOW_CONTENT_VERSION,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+const WINDOW_FILE_PREFIX: &str = "window-";
+const HISTORY_DIR: &str = "history";
+
+/// Revision file names are UTC timestamps, which sort chronologically.
+const REVISION_ID_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// Maximum number of revisions kept per window
+pub const MAX_REVISIONS: usize = 20;
+
+/// Maximum total size of a window's revisions (the newest is always kept)
+pub const MAX_HISTORY_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Minimum time between automatic revisions of the same window
+pub const REVISION_MIN_INTERVAL: Duration = Duration::from_secs(30);
+
+/// New content with less text than this fraction of the content it replaces
+/// is a large deletion, recorded regardless of the throttle
+const LARGE_DELETION_RATIO: f64 = 0.5;
+
+/// Serializes revision creation, pruning and restore
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// Window IDs become directory names, so only a safe subset is accepted.
+fn is_valid_window_id(window_id: &str) -> bool {
+    !window_id.is_empty()
+        && window_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn history_dir(data_dir: &Path, window_id: &str) -> PathBuf {
+    data_dir.join(HISTORY_DIR).join(window_id)
+}
+
+fn window_file(data_dir: &Path, window_id: &str) -> PathBuf {
+    data_dir.join(format!("{}{}.json", WINDOW_FILE_PREFIX, window_id))
+}
+
+/// Extract the window ID from a `window-{id}.json` path.
+pub fn window_id_from_path(path: &Path) -> Option<&str> {
+    path.file_name()?
+        .to_str()?
+        .strip_prefix(WINDOW_FILE_PREFIX)?
+        .strip_suffix(".json")
+}
+
+/// Parse a revision ID back into its creation time.
+fn parse_revision_id(id: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::NaiveDateTime::parse_from_str(id, REVISION_ID_FORMAT)
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+/// Revisions of a window with their sizes, newest first.
+fn revisions(data_dir: &Path, window_id: &str) -> Vec<(String, u64)> {
+    if !is_valid_window_id(window_id) {
+        return Vec::new();
+    }
+    let Ok(entries) = fs::read_dir(history_dir(data_dir, window_id)) else {
+        return Vec::new();
+    };
+
+    let mut revisions: Vec<(String, u64)> = entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let id = name.strip_suffix(".json")?.to_string();
+            parse_revision_id(&id)?;
+            Some((id, e.metadata().map(|m| m.len()).unwrap_or(0)))
+        })
+        .collect();
+
+    revisions.sort();
+    revisions.reverse();
+    revisions
+}
+
+/// List the revisions of a window, newest first.
+pub fn list_revisions(data_dir: &Path, window_id: &str) -> Vec<RevisionInfo> {
+    revisions(data_dir, window_id)
+        .into_iter()
+        .filter_map(|(id, size_bytes)| {
+            let created_at = parse_revision_id(&id)?.to_rfc3339();
+            Some(RevisionInfo {
+                id,
+                created_at,
+                size_bytes,
+            })
+        })
+        .collect()
+}
+
+/// Select the revisions to delete so that at most `max_count` remain and
+/// their total size stays within `max_bytes`. `revisions` is newest first;
+/// the newest revision is always kept.
+pub fn revisions_to_prune(
+    revisions: &[(String, u64)],
+    max_count: usize,
+    max_bytes: u64,
+) -> Vec<String> {
+    let mut total = 0u64;
+    revisions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, (id, size))| {
+            total += size;
+            let keep = index == 0 || (index < max_count && total <= max_bytes);
+            (!keep).then(|| id.clone())
+        })
+        .collect()
+}
+
+fn prune_revisions(data_dir: &Path, window_id: &str) {
+    let dir = history_dir(data_dir, window_id);
+    let all = revisions(data_dir, window_id);
+    for id in revisions_to_prune(&all, MAX_REVISIONS, MAX_HISTORY_BYTES) {
+        if let Err(e) = fs::remove_file(dir.join(format!("{}.json", id))) {
+            log::warn!("Failed to prune revision {} of {}: {}", id, window_id, e);
+        }
+    }
+}
+
+/// Copy the current window file into a new revision. Returns the revision ID,
+/// or None if there is no file, it matches the newest revision, or (unless
+/// `force`) the newest revision is younger than `REVISION_MIN_INTERVAL`.
+fn record_revision_locked(
+    data_dir: &Path,
+    window_id: &str,
+    force: bool,
+) -> Result<Option<String>, String> {
+    if !is_valid_window_id(window_id) {
+        return Err(format!("Invalid window ID '{}'", window_id));
+    }
+    let Ok(contents) = fs::read(window_file(data_dir, window_id)) else {
+        return Ok(None);
+    };
+
+    let dir = history_dir(data_dir, window_id);
+    if let Some((newest, _)) = revisions(data_dir, window_id).first() {
+        if !force {
+            let age = parse_revision_id(newest)
+                .map(|t| chrono::Utc::now().signed_duration_since(t))
+                .and_then(|d| d.to_std().ok())
+                .unwrap_or_default();
+            if age < REVISION_MIN_INTERVAL {
+                return Ok(None);
+            }
+        }
+        if fs::read(dir.join(format!("{}.json", newest))).ok().as_ref() == Some(&contents) {
+            return Ok(None);
+        }
+    }
+
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create history directory: {}", e))?;
+    let id = chrono::Utc::now().format(REVISION_ID_FORMAT).to_string();
+    atomic_write(&dir.join(format!("{}.json", id)), &contents)?;
+    prune_revisions(data_dir, window_id);
+
+    log::debug!("Recorded revision {} of window {}", id, window_id);
+    Ok(Some(id))
+}
+
+/// Total length of the text in a JSON value (its string values)
+fn text_len(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::String(s) => s.len(),
+        serde_json::Value::Array(items) => items.iter().map(text_len).sum(),
+        serde_json::Value::Object(map) => map.values().map(text_len).sum(),
+        _ => 0,
+    }
+}
+
+/// Whether replacing a window file's contents with `replacement` clears or
+/// mostly deletes its content
+fn is_large_deletion(current: &[u8], replacement: &[u8]) -> bool {
+    let content_len = |bytes: &[u8]| {
+        serde_json::from_slice::<WindowContentFile>(bytes)
+            .map(|file| text_len(&file.content))
+            .unwrap_or(bytes.len())
+    };
+    let current = content_len(current);
+    let replacement = content_len(replacement);
+    current > 0
+        && (replacement == 0 || (replacement as f64) < current as f64 * LARGE_DELETION_RATIO)
+}
+
+/// Keep the current contents of a window file as a revision before it is
+/// overwritten with `replacement`. Throttled by `REVISION_MIN_INTERVAL`, unless
+/// the replacement clears or mostly deletes the content.
+pub fn record_revision(
+    data_dir: &Path,
+    window_id: &str,
+    replacement: &[u8],
+) -> Result<Option<String>, String> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let force = is_valid_window_id(window_id)
+        && fs::read(window_file(data_dir, window_id))
+            .is_ok_and(|current| is_large_deletion(&current, replacement));
+    record_revision_locked(data_dir, window_id, force)
+}
+
+/// Replace a window file with one of its revisions.
+///
+/// The current contents are recorded as a revision first, so a restore can
+/// itself be undone. Returns the restored (migrated) content.
+pub fn restore_revision(
+    data_dir: &Path,
+    window_id: &str,
+    revision_id: &str,
+) -> Result<WindowContentFile, String> {
+    if !is_valid_window_id(window_id) {
+        return Err(format!("Invalid window ID '{}'", window_id));
+    }
+    if parse_revision_id(revision_id).is_none() {
+        return Err(format!("Invalid revision ID '{}'", revision_id));
+    }
+
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let revision_path = history_dir(data_dir, window_id).join(format!("{}.json", revision_id));
+    let contents = fs::read_to_string(&revision_path)
+        .map_err(|e| format!("Failed to read revision {}: {}", revision_id, e))?;
+
+    let mut value: serde_json::Value =
+        serde_json::from_str(&contents).map_err(|e| format!("Invalid revision JSON: {}", e))?;
+    migrations::migrate_window_content(&mut value, revision_id)?;
+    let mut content: WindowContentFile =
+        serde_json::from_value(value).map_err(|e| format!("Invalid revision content: {}", e))?;
+
+    if content.window_id != window_id {
+        return Err("Revision belongs to a different window".to_string());
+    }
+
+    record_revision_locked(data_dir, window_id, true)?;
+
+    content.version = CURRENT_WINDOW_CONTENT_VERSION;
+    content.last_modified = chrono::Utc::now().to_rfc3339();
+    let json = serde_json::to_string_pretty(&content)
+        .map_err(|e| format!("Serialization failed: {}", e))?;
+    atomic_write(&window_file(data_dir, window_id), json.as_bytes())?;
+
+    Ok(content)
+}
+
+/// Remove all revisions of a window (called when the window is closed).
+pub fn delete_history(data_dir: &Path, window_id: &str) {
+    if !is_valid_window_id(window_id) {
+        return;
+    }
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let dir = history_dir(data_dir, window_id);
+    if dir.exists() {
+        if let Err(e) = fs::remove_dir_all(&dir) {
+            log::warn!("Failed to delete history of window {}: {}", window_id, e);
+        }
+    }
+}
+
+// ============================================================================
+// Tauri commands
+// ============================================================================
+
+/// List the stored content revisions of a window, newest first.
+#[tauri::command]
+pub async fn list_window_history(
+    app: tauri::AppHandle,
+    window_id: String,
+) -> Result<Vec<RevisionInfo>, String> {
+    let data_dir = profiles::get_active_profile_dir(&app)?;
+    Ok(list_revisions(&data_dir, &window_id))
+}
+
+/// Restore a window's content from one of its revisions.
+///
+/// Returns the restored content, which the frontend should apply to the open
+/// window so its next save does not overwrite the restore.
+#[tauri::command]
+pub async fn restore_window_revision(
+    app: tauri::AppHandle,
+    window_id: String,
+    revision_id: String,
+) -> Result<RestoreRevisionResult, String> {
+    let data_dir = profiles::get_active_profile_dir(&app)?;
+
+    // Queued saves must not overwrite the restored file
+    queue::flush_all();
+
+    match restore_revision(&data_dir, &window_id, &revision_id) {
+        Ok(content) => {
+            log::info!("Window {} restored to revision {}", window_id, revision_id);
+            Ok(RestoreRevisionResult {
+                success: true,
+                content: Some(content),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!(
+                "Failed to restore revision {} of {}: {}",
+                revision_id,
+                window_id,
+                e
+            );
+            Ok(RestoreRevisionResult {
+                success: false,
+                content: None,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::test_support::TempDir;
+
+    fn content(window_id: &str, doc: &str) -> Vec<u8> {
+        serde_json::json!({
+            "version": CURRENT_WINDOW_CONTENT_VERSION,
+            "windowId": window_id,
+            "type": "notes",
+            "content": { "doc": doc },
+            "lastModified": "2025-01-01T00:00:00Z"
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    fn write_content(data_dir: &Path, window_id: &str, doc: &str) {
+        fs::write(window_file(data_dir, window_id), content(window_id, doc)).unwrap();
+    }
+
+    fn revs(sizes: &[u64]) -> Vec<(String, u64)> {
+        sizes
+            .iter()
+            .enumerate()
+            .map(|(i, size)| (format!("r{}", i), *size))
+            .collect()
+    }
+
+    #[test]
+    fn test_window_id_from_path() {
+        assert_eq!(
+            window_id_from_path(Path::new("/data/window-win_a.json")),
+            Some("win_a")
+        );
+        assert_eq!(window_id_from_path(Path::new("/data/state.json")), None);
+    }
+
+    #[test]
+    fn test_prune_by_count() {
+        let to_prune = revisions_to_prune(&revs(&[1, 1, 1, 1]), 2, 100);
+        assert_eq!(to_prune, vec!["r2", "r3"]);
+    }
+
+    #[test]
+    fn test_prune_by_size_keeps_newest() {
+        assert_eq!(
+            revisions_to_prune(&revs(&[50, 40, 30]), 10, 100),
+            vec!["r2"]
+        );
+        // The newest revision is kept even if it alone exceeds the limit
+        assert_eq!(revisions_to_prune(&revs(&[500, 10]), 10, 100), vec!["r1"]);
+    }
+
+    #[test]
+    fn test_record_is_throttled_and_skips_duplicates() {
+        let dir = TempDir::new("history-record");
+        write_content(&dir, "win_a", "first");
+
+        assert!(record_revision(&dir, "win_a", &content("win_a", "second"))
+            .unwrap()
+            .is_some());
+        // Too soon after the previous revision
+        write_content(&dir, "win_a", "second");
+        assert!(record_revision(&dir, "win_a", &content("win_a", "third"))
+            .unwrap()
+            .is_none());
+
+        // Forced, but identical to the newest revision
+        write_content(&dir, "win_a", "first");
+        assert!(record_revision_locked(&dir, "win_a", true)
+            .unwrap()
+            .is_none());
+        assert_eq!(list_revisions(&dir, "win_a").len(), 1);
+    }
+
+    #[test]
+    fn test_clear_is_recorded_despite_throttle() {
+        let dir = TempDir::new("history-clear");
+        write_content(&dir, "win_a", "draft");
+        let replacement = content("win_a", "a long paragraph of notes");
+        assert!(record_revision(&dir, "win_a", &replacement)
+            .unwrap()
+            .is_some());
+        write_content(&dir, "win_a", "a long paragraph of notes");
+
+        // Small edits within the interval are throttled
+        let replacement = content("win_a", "a long paragraph of notes!");
+        assert!(record_revision(&dir, "win_a", &replacement)
+            .unwrap()
+            .is_none());
+
+        // Mostly deleted, then cleared: the content before each is kept
+        std::thread::sleep(Duration::from_millis(5));
+        let replacement = content("win_a", "a long");
+        assert!(record_revision(&dir, "win_a", &replacement)
+            .unwrap()
+            .is_some());
+        write_content(&dir, "win_a", "a long");
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(record_revision(&dir, "win_a", &content("win_a", ""))
+            .unwrap()
+            .is_some());
+        assert_eq!(list_revisions(&dir, "win_a").len(), 3);
+    }
+
+    #[test]
+    fn test_restore_revision_is_undoable() {
+        let dir = TempDir::new("history-restore");
+        write_content(&dir, "win_a", "before clear");
+        let revision = record_revision(&dir, "win_a", &content("win_a", ""))
+            .unwrap()
+            .unwrap();
+        write_content(&dir, "win_a", "");
+
+        std::thread::sleep(Duration::from_millis(5));
+        let restored = restore_revision(&dir, "win_a", &revision).unwrap();
+        assert_eq!(restored.content["doc"], "before clear");
+
+        // The cleared content was kept as a revision before restoring
+        assert_eq!(list_revisions(&dir, "win_a").len(), 2);
+        assert!(restore_revision(&dir, "win_b", &revision).is_err());
+        assert!(restore_revision(&dir, "win_a", "../state").is_err());
+        assert!(restore_revision(&dir, "../win_a", &revision).is_err());
+
+        delete_history(&dir, "win_a");
+        assert!(list_revisions(&dir, "win_a").is_empty());
+    }
+}
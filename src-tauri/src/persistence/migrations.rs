@@ -0,0 +1,300 @@
+//! Schema Migrations for Persisted State
+//!
+//! Upgrades raw `state.json` and `window-{id}.json` documents from older schema
+//! versions to the current one, one version step at a time. Migrations operate on
+//! `serde_json::Value` so that documents whose shape no longer matches the Rust
+//! types (e.g. the removed `clock` WindowType) can still be read and repaired.
+//!
+//! To add a migration: bump the relevant `CURRENT_*_VERSION` constant in
+//! `types.rs` and append a `Migration` entry whose `from_version` is the previous
+//! current version.
+//!
+//! @feature 010-state-persistence-system
+
+use super::types::{MigrationRecord, CURRENT_STATE_VERSION, CURRENT_WINDOW_CONTENT_VERSION};
+use serde_json::{json, Value};
+
+/// Version assumed for documents written before the `version` field existed.
+const LEGACY_VERSION: u32 = 1;
+
+/// A single migration step from `from_version` to `from_version + 1`.
+pub struct Migration {
+    /// Schema version this step upgrades from
+    pub from_version: u32,
+    /// Human-readable summary reported back to the frontend
+    pub description: &'static str,
+    /// Transform applied to the raw JSON document
+    pub apply: fn(&mut Value) -> Result<(), String>,
+}
+
+/// Registered migrations for state.json, ordered by `from_version`.
+pub const STATE_MIGRATIONS: &[Migration] = &[Migration {
+    from_version: 1,
+    description: "Move legacy clock windows into the widget list",
+    apply: migrate_state_v1_to_v2,
+}];
+
+/// Registered migrations for window-{id}.json files, ordered by `from_version`.
+pub const WINDOW_CONTENT_MIGRATIONS: &[Migration] = &[Migration {
+    from_version: 1,
+    description: "Add schema version to window content file",
+    apply: migrate_window_content_v1_to_v2,
+}];
+
+/// Read the schema version of a raw document.
+/// Documents without a version field are treated as version 1.
+pub fn document_version(value: &Value) -> u32 {
+    value
+        .get("version")
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .unwrap_or(LEGACY_VERSION)
+}
+
+/// Whether a raw document needs to be migrated to reach `target_version`.
+pub fn needs_migration(value: &Value, target_version: u32) -> bool {
+    document_version(value) < target_version
+}
+
+/// Whether a raw document was written by a newer schema than `target_version`.
+pub fn is_newer_than(value: &Value, target_version: u32) -> bool {
+    document_version(value) > target_version
+}
+
+/// Run every migration step needed to bring `value` up to `target_version`.
+///
+/// `file` is only used to label the returned records. Fails without modifying
+/// the version field if the document is newer than `target_version` or if a
+/// step is missing from the registry.
+pub fn migrate(
+    value: &mut Value,
+    migrations: &[Migration],
+    target_version: u32,
+    file: &str,
+) -> Result<Vec<MigrationRecord>, String> {
+    if !value.is_object() {
+        return Err(format!("{}: expected a JSON object", file));
+    }
+
+    let mut version = document_version(value);
+    if is_newer_than(value, target_version) {
+        return Err(format!(
+            "{}: schema version {} is newer than supported version {}",
+            file, version, target_version
+        ));
+    }
+
+    let mut applied = Vec::new();
+    while version < target_version {
+        let step = migrations
+            .iter()
+            .find(|m| m.from_version == version)
+            .ok_or_else(|| format!("{}: no migration registered from version {}", file, version))?;
+
+        (step.apply)(value).map_err(|e| {
+            format!(
+                "{}: migration v{} -> v{} failed: {}",
+                file,
+                version,
+                version + 1,
+                e
+            )
+        })?;
+
+        value["version"] = json!(version + 1);
+        applied.push(MigrationRecord {
+            file: file.to_string(),
+            from_version: version,
+            to_version: version + 1,
+            description: step.description.to_string(),
+        });
+        version += 1;
+    }
+
+    Ok(applied)
+}
+
+/// Migrate a raw state.json document to `CURRENT_STATE_VERSION`.
+pub fn migrate_state(value: &mut Value) -> Result<Vec<MigrationRecord>, String> {
+    migrate(value, STATE_MIGRATIONS, CURRENT_STATE_VERSION, "state.json")
+}
+
+/// Migrate a raw window content document to `CURRENT_WINDOW_CONTENT_VERSION`.
+pub fn migrate_window_content(
+    value: &mut Value,
+    file: &str,
+) -> Result<Vec<MigrationRecord>, String> {
+    migrate(
+        value,
+        WINDOW_CONTENT_MIGRATIONS,
+        CURRENT_WINDOW_CONTENT_VERSION,
+        file,
+    )
+}
+
+// ============================================================================
+// Migration Steps
+// ============================================================================
+
+/// v1 -> v2: `clock` windows were replaced by clock widgets in 027-widget-container.
+/// Each clock window becomes a clock widget at the same position, size and opacity.
+/// @feature 027-widget-container
+fn migrate_state_v1_to_v2(value: &mut Value) -> Result<(), String> {
+    let obj = value
+        .as_object_mut()
+        .ok_or_else(|| "state is not an object".to_string())?;
+
+    let windows = match obj.remove("windows") {
+        Some(Value::Array(windows)) => windows,
+        Some(_) => return Err("'windows' is not an array".to_string()),
+        None => Vec::new(),
+    };
+
+    let mut widgets = match obj.remove("widgets") {
+        Some(Value::Array(widgets)) => widgets,
+        Some(_) => return Err("'widgets' is not an array".to_string()),
+        None => Vec::new(),
+    };
+
+    let mut kept = Vec::with_capacity(windows.len());
+    for window in windows {
+        if window.get("type").and_then(Value::as_str) != Some("clock") {
+            kept.push(window);
+            continue;
+        }
+
+        widgets.push(json!({
+            "id": window.get("id").cloned().unwrap_or(Value::Null),
+            "type": "clock",
+            "position": window
+                .get("position")
+                .cloned()
+                .unwrap_or_else(|| json!({ "x": 0, "y": 0 })),
+            "size": window
+                .get("size")
+                .cloned()
+                .unwrap_or_else(|| json!({ "width": 200, "height": 80 })),
+            "opacity": window.get("opacity").cloned().unwrap_or(json!(0.6)),
+        }));
+    }
+
+    obj.insert("windows".to_string(), Value::Array(kept));
+    obj.insert("widgets".to_string(), Value::Array(widgets));
+    Ok(())
+}
+
+/// v1 -> v2: content files gained an explicit `version` field. No shape change.
+fn migrate_window_content_v1_to_v2(value: &mut Value) -> Result<(), String> {
+    if !value.is_object() {
+        return Err("window content is not an object".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::types::{PersistedState, WidgetType, WindowContentFile};
+
+    fn v1_state_with_clock() -> Value {
+        json!({
+            "version": 1,
+            "lastModified": "2025-01-01T00:00:00Z",
+            "global": { "overlayMode": "windowed", "overlayVisible": false },
+            "windows": [
+                {
+                    "id": "notes-1",
+                    "type": "notes",
+                    "position": { "x": 10, "y": 20 },
+                    "size": { "width": 300, "height": 200 },
+                    "zIndex": 1,
+                    "flags": { "minimized": false, "maximized": false },
+                    "opacity": 0.8
+                },
+                {
+                    "id": "clock-1",
+                    "type": "clock",
+                    "position": { "x": 50, "y": 60 },
+                    "size": { "width": 200, "height": 80 },
+                    "zIndex": 2,
+                    "flags": { "minimized": false, "maximized": false },
+                    "opacity": 0.5
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_document_version_defaults_to_legacy() {
+        assert_eq!(document_version(&json!({})), 1);
+        assert_eq!(document_version(&json!({ "version": 3 })), 3);
+    }
+
+    #[test]
+    fn test_v1_state_with_clock_fails_to_deserialize() {
+        let result: Result<PersistedState, _> = serde_json::from_value(v1_state_with_clock());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_migrate_state_moves_clock_windows_to_widgets() {
+        let mut value = v1_state_with_clock();
+        let applied = migrate_state(&mut value).unwrap();
+
+        assert_eq!(applied.len(), 1);
+        assert_eq!(applied[0].from_version, 1);
+        assert_eq!(applied[0].to_version, 2);
+
+        let state: PersistedState = serde_json::from_value(value).unwrap();
+        assert_eq!(state.version, CURRENT_STATE_VERSION);
+        assert_eq!(state.windows.len(), 1);
+        assert_eq!(state.windows[0].id, "notes-1");
+        assert_eq!(state.widgets.len(), 1);
+        assert_eq!(state.widgets[0].id, "clock-1");
+        assert!(matches!(state.widgets[0].widget_type, WidgetType::Clock));
+        assert_eq!(state.widgets[0].position.x, 50);
+        assert_eq!(state.widgets[0].opacity, 0.5);
+    }
+
+    #[test]
+    fn test_migrate_state_current_version_is_noop() {
+        let mut value = v1_state_with_clock();
+        migrate_state(&mut value).unwrap();
+        let before = value.clone();
+
+        let applied = migrate_state(&mut value).unwrap();
+        assert!(applied.is_empty());
+        assert_eq!(value, before);
+    }
+
+    #[test]
+    fn test_migrate_rejects_newer_version() {
+        let mut value = json!({ "version": CURRENT_STATE_VERSION + 1 });
+        assert!(migrate_state(&mut value).is_err());
+        assert_eq!(document_version(&value), CURRENT_STATE_VERSION + 1);
+    }
+
+    #[test]
+    fn test_migrate_rejects_missing_step() {
+        let mut value = json!({ "version": 1 });
+        let result = migrate(&mut value, &[], 2, "test.json");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_migrate_window_content_adds_version() {
+        let mut value = json!({
+            "windowId": "notes-1",
+            "type": "notes",
+            "content": { "doc": "hello" },
+            "lastModified": "2025-01-01T00:00:00Z"
+        });
+        let applied = migrate_window_content(&mut value, "window-notes-1.json").unwrap();
+
+        assert_eq!(applied.len(), 1);
+        assert_eq!(applied[0].file, "window-notes-1.json");
+
+        let content: WindowContentFile = serde_json::from_value(value).unwrap();
+        assert_eq!(content.version, CURRENT_WINDOW_CONTENT_VERSION);
+    }
+}
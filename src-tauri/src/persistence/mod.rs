@@ -1,7 +1,18 @@
 // Persistence module - state storage and window content management
 
 pub mod types;
+pub mod app_data;
+pub mod backups;
 pub mod commands;
+pub mod history;
+pub mod migrations;
+pub mod profiles;
+pub mod queue;
+pub mod sessions;
+pub mod workspace;
+
+#[cfg(test)]
+pub(crate) mod test_support;
 
 // Re-exports for public API
 pub use commands::*;
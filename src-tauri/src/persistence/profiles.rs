@@ -0,0 +1,450 @@
+//! Named Layout Profiles
+//!
+//! Each profile is a self-contained set of `state.json` + `window-{id}.json`
+//! files (plus its own snapshot ring). The `default` profile lives directly in
+//! the app data directory for backward compatibility; every other profile lives
+//! in `profiles/{name}/`.
+//!
+//! The active profile is recorded in `UserSettings.active_profile`. Switching
+//! only rewrites that pointer (atomically), so a crash mid-switch can never
+//! leave files from two profiles mixed together.
+//!
+//! @feature 010-state-persistence-system
+//! @feature 038-settings-panel
+
+use super::commands::atomic_write;
+use super::queue;
+use super::types::{ProfileInfo, ProfileResult, ProfileSwitchedPayload};
+use crate::settings;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{Emitter, Manager};
+
+/// Name of the profile stored directly in the app data directory
+pub const DEFAULT_PROFILE: &str = "default";
+
+const PROFILES_DIR: &str = "profiles";
+const STATE_FILE: &str = "state.json";
+const WINDOW_FILE_PREFIX: &str = "window-";
+const MAX_PROFILE_NAME_LEN: usize = 64;
+
+// ============================================================================
+// Path helpers (pure filesystem logic, no Tauri dependencies)
+// ============================================================================
+
+/// Validate a user-supplied profile name.
+/// Names become directory names, so only a safe subset of characters is allowed.
+pub fn validate_profile_name(name: &str) -> Result<(), String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    if trimmed != name {
+        return Err("Profile name cannot start or end with whitespace".to_string());
+    }
+    if name.len() > MAX_PROFILE_NAME_LEN {
+        return Err(format!(
+            "Profile name cannot be longer than {} characters",
+            MAX_PROFILE_NAME_LEN
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == ' ')
+    {
+        return Err(
+            "Profile name may only contain letters, digits, spaces, '-' and '_'".to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Whether `name` refers to the built-in default profile.
+pub fn is_default_profile(name: &str) -> bool {
+    name.eq_ignore_ascii_case(DEFAULT_PROFILE)
+}
+
+/// Directory holding the files of profile `name`.
+pub fn profile_dir(root: &Path, name: &str) -> PathBuf {
+    if is_default_profile(name) {
+        root.to_path_buf()
+    } else {
+        root.join(PROFILES_DIR).join(name)
+    }
+}
+
+/// List profile names: the default profile first, then the others alphabetically.
+pub fn list_profile_names(root: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(root.join(PROFILES_DIR))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| validate_profile_name(name).is_ok() && !is_default_profile(name))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    names.sort_by_key(|n| n.to_lowercase());
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+/// Find an existing profile by name (case-insensitive), returning its stored name.
+pub fn find_profile(root: &Path, name: &str) -> Option<String> {
+    list_profile_names(root)
+        .into_iter()
+        .find(|n| n.eq_ignore_ascii_case(name))
+}
+
+fn ensure_new_profile_name(root: &Path, name: &str) -> Result<(), String> {
+    validate_profile_name(name)?;
+    if find_profile(root, name).is_some() {
+        return Err(format!("Profile '{}' already exists", name));
+    }
+    Ok(())
+}
+
+fn is_profile_file(file_name: &str) -> bool {
+    file_name == STATE_FILE
+        || (file_name.starts_with(WINDOW_FILE_PREFIX) && file_name.ends_with(".json"))
+}
+
+/// Create an empty profile directory.
+pub fn create_profile_dir(root: &Path, name: &str) -> Result<(), String> {
+    ensure_new_profile_name(root, name)?;
+    fs::create_dir_all(profile_dir(root, name))
+        .map_err(|e| format!("Failed to create profile directory: {}", e))
+}
+
+/// Copy the state and window files of `source` into a new profile `target`.
+///
+/// Files are copied into a hidden staging directory which is renamed into place
+/// once complete, so a partially copied profile never becomes visible.
+pub fn clone_profile_dir(root: &Path, source: &str, target: &str) -> Result<(), String> {
+    let source =
+        find_profile(root, source).ok_or_else(|| format!("Profile '{}' does not exist", source))?;
+    ensure_new_profile_name(root, target)?;
+
+    let profiles_root = root.join(PROFILES_DIR);
+    let staging = profiles_root.join(format!(".staging-{}", target));
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging)
+        .map_err(|e| format!("Failed to create staging directory: {}", e))?;
+
+    let copy_result = (|| -> Result<(), String> {
+        let entries = fs::read_dir(profile_dir(root, &source))
+            .map_err(|e| format!("Failed to read profile '{}': {}", source, e))?;
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !entry.path().is_file() || !is_profile_file(&file_name) {
+                continue;
+            }
+            let content = fs::read(entry.path())
+                .map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
+            atomic_write(&staging.join(&file_name), &content)?;
+        }
+        fs::rename(&staging, profile_dir(root, target))
+            .map_err(|e| format!("Failed to finalize profile '{}': {}", target, e))
+    })();
+
+    if copy_result.is_err() {
+        let _ = fs::remove_dir_all(&staging);
+    }
+    copy_result
+}
+
+/// Rename a non-default profile.
+pub fn rename_profile_dir(root: &Path, old_name: &str, new_name: &str) -> Result<String, String> {
+    let old_name = find_profile(root, old_name)
+        .ok_or_else(|| format!("Profile '{}' does not exist", old_name))?;
+    if is_default_profile(&old_name) {
+        return Err("The default profile cannot be renamed".to_string());
+    }
+
+    // Allow changing only the letter case of a name
+    if !old_name.eq_ignore_ascii_case(new_name) {
+        ensure_new_profile_name(root, new_name)?;
+    } else {
+        validate_profile_name(new_name)?;
+    }
+
+    fs::rename(profile_dir(root, &old_name), profile_dir(root, new_name))
+        .map_err(|e| format!("Failed to rename profile: {}", e))?;
+    Ok(old_name)
+}
+
+/// Delete a non-default profile and all of its files.
+pub fn delete_profile_dir(root: &Path, name: &str) -> Result<(), String> {
+    let name =
+        find_profile(root, name).ok_or_else(|| format!("Profile '{}' does not exist", name))?;
+    if is_default_profile(&name) {
+        return Err("The default profile cannot be deleted".to_string());
+    }
+    fs::remove_dir_all(profile_dir(root, &name))
+        .map_err(|e| format!("Failed to delete profile: {}", e))
+}
+
+// ============================================================================
+// Active profile resolution
+// ============================================================================
+
+/// Get the application data directory path (root of all profiles).
+fn get_app_data_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))
+}
+
+/// Get the name of the active profile, falling back to the default profile
+/// if the recorded one no longer exists.
+pub fn get_active_profile(app: &tauri::AppHandle) -> Result<String, String> {
+    let root = get_app_data_dir(app)?;
+    let recorded = settings::user::get_active_profile();
+
+    match find_profile(&root, &recorded) {
+        Some(name) => Ok(name),
+        None => {
+            log::warn!(
+                "Active profile '{}' not found, using '{}'",
+                recorded,
+                DEFAULT_PROFILE
+            );
+            Ok(DEFAULT_PROFILE.to_string())
+        }
+    }
+}
+
+/// Get the directory holding the active profile's state and window files.
+pub fn get_active_profile_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let root = get_app_data_dir(app)?;
+    let active = get_active_profile(app)?;
+    Ok(profile_dir(&root, &active))
+}
+
+fn profile_result(result: Result<(), String>) -> ProfileResult {
+    match result {
+        Ok(()) => ProfileResult {
+            success: true,
+            error: None,
+        },
+        Err(e) => {
+            log::warn!("Profile operation failed: {}", e);
+            ProfileResult {
+                success: false,
+                error: Some(e),
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Tauri commands
+// ============================================================================
+
+/// List all layout profiles, default first.
+#[tauri::command]
+pub async fn list_profiles(app: tauri::AppHandle) -> Result<Vec<ProfileInfo>, String> {
+    let root = get_app_data_dir(&app)?;
+    let active = get_active_profile(&app)?;
+
+    Ok(list_profile_names(&root)
+        .into_iter()
+        .map(|name| {
+            let last_modified = fs::metadata(profile_dir(&root, &name).join(STATE_FILE))
+                .and_then(|m| m.modified())
+                .ok()
+                .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339());
+            ProfileInfo {
+                active: name == active,
+                is_default: is_default_profile(&name),
+                name,
+                last_modified,
+            }
+        })
+        .collect())
+}
+
+/// Create a new, empty profile.
+#[tauri::command]
+pub async fn create_profile(app: tauri::AppHandle, name: String) -> Result<ProfileResult, String> {
+    let root = get_app_data_dir(&app)?;
+    let result = create_profile_dir(&root, &name);
+    if result.is_ok() {
+        log::info!("Profile '{}' created", name);
+    }
+    Ok(profile_result(result))
+}
+
+/// Create a new profile as a copy of an existing one.
+#[tauri::command]
+pub async fn clone_profile(
+    app: tauri::AppHandle,
+    source: String,
+    name: String,
+) -> Result<ProfileResult, String> {
+    let root = get_app_data_dir(&app)?;
+    // Queued saves must land in the profile's current directory first
+    queue::flush_all();
+    let result = clone_profile_dir(&root, &source, &name);
+    if result.is_ok() {
+        log::info!("Profile '{}' cloned to '{}'", source, name);
+    }
+    Ok(profile_result(result))
+}
+
+/// Rename a profile. If it is the active profile, the pointer follows it.
+#[tauri::command]
+pub async fn rename_profile(
+    app: tauri::AppHandle,
+    name: String,
+    new_name: String,
+) -> Result<ProfileResult, String> {
+    let root = get_app_data_dir(&app)?;
+    let active = get_active_profile(&app)?;
+
+    // Queued saves must land in the profile's current directory first
+    queue::flush_all();
+    let result = rename_profile_dir(&root, &name, &new_name).and_then(|old_name| {
+        log::info!("Profile '{}' renamed to '{}'", old_name, new_name);
+        if old_name == active {
+            settings::user::set_active_profile(&app, &new_name)?;
+        }
+        Ok(())
+    });
+    Ok(profile_result(result))
+}
+
+/// Delete a profile. The active and default profiles cannot be deleted.
+#[tauri::command]
+pub async fn delete_profile(app: tauri::AppHandle, name: String) -> Result<ProfileResult, String> {
+    let root = get_app_data_dir(&app)?;
+    let active = get_active_profile(&app)?;
+
+    if name.eq_ignore_ascii_case(&active) {
+        return Ok(profile_result(Err(
+            "The active profile cannot be deleted".to_string()
+        )));
+    }
+
+    let result = delete_profile_dir(&root, &name);
+    if result.is_ok() {
+        log::info!("Profile '{}' deleted", name);
+    }
+    Ok(profile_result(result))
+}
+
+/// Make another profile active.
+///
+/// Only the `active_profile` pointer in user-settings.json is rewritten, after
+/// queued saves of the previous profile have been flushed. The frontend should
+/// reload via load_state when it receives the `profile-switched` event.
+#[tauri::command]
+pub async fn switch_profile(app: tauri::AppHandle, name: String) -> Result<ProfileResult, String> {
+    let root = get_app_data_dir(&app)?;
+    let previous = get_active_profile(&app)?;
+
+    let Some(target) = find_profile(&root, &name) else {
+        return Ok(profile_result(Err(format!(
+            "Profile '{}' does not exist",
+            name
+        ))));
+    };
+
+    if target == previous {
+        return Ok(profile_result(Ok(())));
+    }
+
+    queue::flush_all();
+
+    if let Err(e) = settings::user::set_active_profile(&app, &target) {
+        return Ok(profile_result(Err(e)));
+    }
+
+    log::info!("Switched profile '{}' -> '{}'", previous, target);
+    let payload = ProfileSwitchedPayload {
+        previous,
+        current: target,
+    };
+    if let Err(e) = app.emit("profile-switched", payload) {
+        log::error!("Failed to emit profile-switched event: {}", e);
+    }
+
+    Ok(profile_result(Ok(())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::test_support::TempDir;
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("mining").is_ok());
+        assert!(validate_profile_name("Combat 2_PTU-test").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name(" mining").is_err());
+        assert!(validate_profile_name("../escape").is_err());
+        assert!(validate_profile_name(".staging-x").is_err());
+        assert!(validate_profile_name(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_default_profile_uses_root() {
+        let root = Path::new("/data");
+        assert_eq!(profile_dir(root, "default"), PathBuf::from("/data"));
+        assert_eq!(
+            profile_dir(root, "mining"),
+            PathBuf::from("/data/profiles/mining")
+        );
+    }
+
+    #[test]
+    fn test_create_list_and_delete() {
+        let root = TempDir::new("profiles-crud");
+        create_profile_dir(&root, "trading").unwrap();
+        create_profile_dir(&root, "Mining").unwrap();
+
+        assert_eq!(
+            list_profile_names(&root),
+            vec!["default", "Mining", "trading"]
+        );
+        assert!(create_profile_dir(&root, "mining").is_err());
+        assert!(create_profile_dir(&root, "Default").is_err());
+
+        delete_profile_dir(&root, "trading").unwrap();
+        assert_eq!(list_profile_names(&root), vec!["default", "Mining"]);
+        assert!(delete_profile_dir(&root, "default").is_err());
+    }
+
+    #[test]
+    fn test_clone_copies_only_profile_files() {
+        let root = TempDir::new("profiles-clone");
+        fs::write(root.join("state.json"), "{}").unwrap();
+        fs::write(root.join("window-a.json"), "{}").unwrap();
+        fs::write(root.join("user-settings.json"), "{}").unwrap();
+
+        clone_profile_dir(&root, "default", "combat").unwrap();
+
+        let cloned = profile_dir(&root, "combat");
+        assert!(cloned.join("state.json").exists());
+        assert!(cloned.join("window-a.json").exists());
+        assert!(!cloned.join("user-settings.json").exists());
+        assert_eq!(list_profile_names(&root), vec!["default", "combat"]);
+    }
+
+    #[test]
+    fn test_rename_profile() {
+        let root = TempDir::new("profiles-rename");
+        create_profile_dir(&root, "mining").unwrap();
+
+        assert_eq!(
+            rename_profile_dir(&root, "MINING", "Mining").unwrap(),
+            "mining"
+        );
+        assert_eq!(list_profile_names(&root), vec!["default", "Mining"]);
+        assert!(rename_profile_dir(&root, "default", "other").is_err());
+        assert!(rename_profile_dir(&root, "missing", "other").is_err());
+    }
+}
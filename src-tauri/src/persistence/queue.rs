@@ -0,0 +1,397 @@
+//! Write-Behind Persistence Queue
+//!
+//! `save_state` and `save_window_content` are called very often by the
+//! frontend. Instead of serializing and fsyncing every call, saves are queued
+//! per file and coalesced: only the latest contents of each file are written,
+//! once the file has been idle for `DEBOUNCE` (or after `MAX_DELAY` at most).
+//!
+//! The queue is flushed by a background timer, by the `flush_persistence`
+//! command, before any operation that reads or replaces files on disk, and on
+//! app exit. A write that fails stays queued and is retried with a growing
+//! delay (`RETRY_DELAY` doubling up to `MAX_RETRY_DELAY`).
+//!
+//! @feature 010-state-persistence-system
+
+use super::backups;
+use super::commands::atomic_write;
+use super::history;
+use super::types::FlushResult;
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Time a file must go without new saves before it is written
+pub const DEBOUNCE: Duration = Duration::from_millis(1000);
+
+/// Upper bound on how long a continuously updated file stays unwritten
+pub const MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// Delay before the first retry of a failed write
+pub const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest delay between retries of a failed write
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// How often the background timer checks for due writes
+const FLUSH_TICK: Duration = Duration::from_millis(250);
+
+const STATE_FILE: &str = "state.json";
+
+/// Latest queued contents for a single file
+#[derive(Debug)]
+pub struct PendingWrite {
+    /// Profile directory the file belongs to (used for snapshots)
+    pub data_dir: PathBuf,
+    pub contents: Vec<u8>,
+    pub first_queued: Instant,
+    pub last_queued: Instant,
+    /// Failed attempts to write this file so far
+    pub attempts: u32,
+    /// After a failure, not retried before this time (except by a forced flush)
+    pub retry_at: Option<Instant>,
+}
+
+/// Pending writes keyed by target path, plus running counters
+#[derive(Debug, Default)]
+pub struct WriteQueue {
+    pending: HashMap<PathBuf, PendingWrite>,
+    /// Writes superseded by a newer save before reaching disk
+    dropped: u64,
+    /// Writes that failed when flushed
+    failed: u64,
+    /// Writes that reached disk
+    written: u64,
+}
+
+impl WriteQueue {
+    /// Queue new contents for `path`. Returns true if an unwritten save was replaced.
+    pub fn enqueue(
+        &mut self,
+        data_dir: &Path,
+        path: PathBuf,
+        contents: Vec<u8>,
+        now: Instant,
+    ) -> bool {
+        match self.pending.get_mut(&path) {
+            Some(existing) => {
+                existing.contents = contents;
+                existing.last_queued = now;
+                self.dropped += 1;
+                true
+            }
+            None => {
+                self.pending.insert(
+                    path,
+                    PendingWrite {
+                        data_dir: data_dir.to_path_buf(),
+                        contents,
+                        first_queued: now,
+                        last_queued: now,
+                        attempts: 0,
+                        retry_at: None,
+                    },
+                );
+                false
+            }
+        }
+    }
+
+    /// Queue a write that failed again, to be retried after a backoff. A newer save
+    /// queued for the same file in the meantime is kept instead.
+    pub fn requeue(&mut self, path: PathBuf, mut write: PendingWrite, now: Instant) {
+        write.attempts += 1;
+        let delay = (RETRY_DELAY * 2u32.pow((write.attempts - 1).min(6))).min(MAX_RETRY_DELAY);
+        match self.pending.get_mut(&path) {
+            Some(newer) => {
+                newer.attempts = write.attempts;
+                newer.retry_at = Some(now + delay);
+                self.dropped += 1;
+            }
+            None => {
+                write.retry_at = Some(now + delay);
+                self.pending.insert(path, write);
+            }
+        }
+    }
+
+    /// Discard a pending write (e.g. the file is being deleted).
+    pub fn cancel(&mut self, path: &Path) -> bool {
+        self.pending.remove(path).is_some()
+    }
+
+    /// Remove and return the writes that are due, or all of them if `force`.
+    ///
+    /// Window content files are ordered before state.json so that state never
+    /// references content that has not been written yet.
+    pub fn take_due(&mut self, now: Instant, force: bool) -> Vec<(PathBuf, PendingWrite)> {
+        let due: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, w)| {
+                force
+                    || (w.retry_at.is_none_or(|at| now >= at)
+                        && (now.duration_since(w.last_queued) >= DEBOUNCE
+                            || now.duration_since(w.first_queued) >= MAX_DELAY))
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        let mut writes: Vec<(PathBuf, PendingWrite)> = due
+            .into_iter()
+            .filter_map(|path| self.pending.remove(&path).map(|w| (path, w)))
+            .collect();
+        writes.sort_by_key(|(path, _)| is_state_file(path));
+        writes
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+fn is_state_file(path: &Path) -> bool {
+    path.file_name().and_then(|n| n.to_str()) == Some(STATE_FILE)
+}
+
+lazy_static! {
+    static ref WRITE_QUEUE: Mutex<WriteQueue> = Mutex::new(WriteQueue::default());
+}
+
+/// Held while writes are on their way to disk, so that cancel_write and
+/// flushes from different threads never interleave on the same file.
+static FLUSH_LOCK: Mutex<()> = Mutex::new(());
+
+fn lock_queue() -> std::sync::MutexGuard<'static, WriteQueue> {
+    WRITE_QUEUE.lock().unwrap_or_else(|p| p.into_inner())
+}
+
+/// Queue a file write; it reaches disk on the next flush that finds it due.
+pub fn enqueue_write(data_dir: &Path, path: PathBuf, contents: Vec<u8>) {
+    if lock_queue().enqueue(data_dir, path.clone(), contents, Instant::now()) {
+        log::trace!("Coalesced pending write for {}", path.display());
+    }
+}
+
+/// Drop any pending write for `path`. Waits for an in-progress flush to finish,
+/// so the file can safely be deleted once this returns.
+pub fn cancel_write(path: &Path) -> bool {
+    let _flush = FLUSH_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    lock_queue().cancel(path)
+}
+
+fn flush(force: bool) -> FlushResult {
+    let _flush = FLUSH_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let writes = lock_queue().take_due(Instant::now(), force);
+
+    let mut flushed = 0;
+    let mut failed = 0;
+    let mut last_error = None;
+    let mut snapshotted = HashSet::new();
+
+    for (path, write) in writes {
+        // Keep the last known-good state in the snapshot ring before overwriting it
+        if snapshotted.insert(write.data_dir.clone()) {
+            if let Err(e) = std::fs::create_dir_all(&write.data_dir) {
+                log::error!("Failed to create directory: {}", e);
+            } else if let Err(e) = backups::snapshot_if_due(&write.data_dir) {
+                log::warn!("Failed to create state snapshot: {}", e);
+            }
+        }
+
+        // Keep the previous window content as an undo revision
+        if let Some(window_id) = history::window_id_from_path(&path) {
+            if let Err(e) = history::record_revision(&write.data_dir, window_id, &write.contents) {
+                log::warn!("Failed to record revision of {}: {}", window_id, e);
+            }
+        }
+
+        match atomic_write(&path, &write.contents) {
+            Ok(()) => flushed += 1,
+            Err(e) => {
+                log::error!(
+                    "Failed to write {} (attempt {}), will retry: {}",
+                    path.display(),
+                    write.attempts + 1,
+                    e
+                );
+                failed += 1;
+                last_error = Some(e);
+                lock_queue().requeue(path, write, Instant::now());
+            }
+        }
+    }
+
+    let mut queue = lock_queue();
+    queue.written += flushed;
+    queue.failed += failed;
+
+    if flushed > 0 || failed > 0 {
+        log::debug!(
+            "Persistence flush: {} written, {} failed, {} still pending",
+            flushed,
+            failed,
+            queue.pending_count()
+        );
+    }
+
+    FlushResult {
+        success: failed == 0,
+        flushed: flushed as u32,
+        failed: failed as u32,
+        pending: queue.pending_count() as u32,
+        dropped: queue.dropped,
+        total_written: queue.written,
+        total_failed: queue.failed,
+        error: last_error,
+    }
+}
+
+/// Write every pending file now.
+pub fn flush_all() -> FlushResult {
+    flush(true)
+}
+
+/// Write the pending files whose debounce period has elapsed.
+pub fn flush_due() -> FlushResult {
+    flush(false)
+}
+
+/// Start the background thread that flushes due writes.
+pub fn start_flush_timer() {
+    thread::spawn(|| loop {
+        thread::sleep(FLUSH_TICK);
+        flush_due();
+    });
+    log::debug!("Persistence flush timer started");
+}
+
+/// Write all queued saves to disk immediately.
+///
+/// Returns how many files were written, how many failed, how many are still
+/// pending and how many saves were dropped because a newer one replaced them.
+#[tauri::command]
+pub async fn flush_persistence() -> Result<FlushResult, String> {
+    Ok(flush_all())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue_with(paths: &[&str], now: Instant) -> WriteQueue {
+        let mut queue = WriteQueue::default();
+        for path in paths {
+            queue.enqueue(Path::new("/data"), PathBuf::from(path), b"{}".to_vec(), now);
+        }
+        queue
+    }
+
+    #[test]
+    fn test_enqueue_coalesces_same_file() {
+        let now = Instant::now();
+        let mut queue = queue_with(&["/data/state.json"], now);
+
+        assert!(queue.enqueue(
+            Path::new("/data"),
+            PathBuf::from("/data/state.json"),
+            b"new".to_vec(),
+            now
+        ));
+        assert_eq!(queue.pending_count(), 1);
+        assert_eq!(queue.dropped, 1);
+
+        let writes = queue.take_due(now, true);
+        assert_eq!(writes[0].1.contents, b"new".to_vec());
+    }
+
+    #[test]
+    fn test_take_due_respects_debounce_and_max_delay() {
+        let start = Instant::now();
+        let mut queue = queue_with(&["/data/window-a.json"], start);
+
+        assert!(queue.take_due(start + DEBOUNCE / 2, false).is_empty());
+
+        // Continuous updates keep postponing the write until MAX_DELAY
+        let mut now = start;
+        while now.duration_since(start) < MAX_DELAY {
+            now += DEBOUNCE / 2;
+            queue.enqueue(
+                Path::new("/data"),
+                PathBuf::from("/data/window-a.json"),
+                b"{}".to_vec(),
+                now,
+            );
+            if now.duration_since(start) < MAX_DELAY {
+                assert!(queue.take_due(now, false).is_empty());
+            }
+        }
+        assert_eq!(queue.take_due(now, false).len(), 1);
+        assert_eq!(queue.pending_count(), 0);
+    }
+
+    #[test]
+    fn test_take_due_writes_state_last() {
+        let now = Instant::now();
+        let mut queue = queue_with(
+            &[
+                "/data/state.json",
+                "/data/window-a.json",
+                "/data/window-b.json",
+            ],
+            now,
+        );
+
+        let writes = queue.take_due(now, true);
+        assert_eq!(writes.len(), 3);
+        assert!(is_state_file(&writes[2].0));
+    }
+
+    #[test]
+    fn test_failed_write_is_retried_with_backoff() {
+        let now = Instant::now();
+        let mut queue = queue_with(&["/data/window-a.json"], now);
+        let (path, write) = queue.take_due(now, true).pop().unwrap();
+
+        queue.requeue(path.clone(), write, now);
+        assert_eq!(queue.pending_count(), 1);
+        assert!(queue.take_due(now + RETRY_DELAY / 2, false).is_empty());
+        let (path, write) = queue.take_due(now + RETRY_DELAY, false).pop().unwrap();
+        assert_eq!(write.attempts, 1);
+
+        // The delay doubles, up to MAX_RETRY_DELAY
+        queue.requeue(path.clone(), write, now);
+        assert!(queue.take_due(now + RETRY_DELAY, false).is_empty());
+        let (path, mut write) = queue.take_due(now + 2 * RETRY_DELAY, false).pop().unwrap();
+        write.attempts = 20;
+        queue.requeue(path.clone(), write, now);
+        assert_eq!(queue.take_due(now + MAX_RETRY_DELAY, false).len(), 1);
+
+        // A newer save replaces the failed one but keeps the backoff
+        queue.enqueue(Path::new("/data"), path.clone(), b"new".to_vec(), now);
+        let failed = PendingWrite {
+            data_dir: PathBuf::from("/data"),
+            contents: b"old".to_vec(),
+            first_queued: now,
+            last_queued: now,
+            attempts: 5,
+            retry_at: None,
+        };
+        queue.requeue(path, failed, now);
+        assert!(queue.take_due(now + DEBOUNCE, false).is_empty());
+        let writes = queue.take_due(now, true);
+        assert_eq!(writes[0].1.contents, b"new".to_vec());
+    }
+
+    #[test]
+    fn test_cancel_removes_pending_write() {
+        let now = Instant::now();
+        let mut queue = queue_with(&["/data/window-a.json"], now);
+
+        assert!(queue.cancel(Path::new("/data/window-a.json")));
+        assert!(!queue.cancel(Path::new("/data/window-a.json")));
+        assert!(queue.take_due(now, true).is_empty());
+    }
+}
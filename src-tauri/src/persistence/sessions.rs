@@ -0,0 +1,565 @@
+//! Play Session History
+//!
+//! Every game session (target-process-detected to target-process-terminated) is
+//! recorded in `sessions.jsonl` in the app data directory, shared by all layout
+//! profiles. The file is append-only: one `started` and one `ended` record per
+//! session. While a session runs, a heartbeat file notes when the game was last
+//! seen, so a session left open by a crash or a forced exit is closed at that time
+//! on the next launch.
+//!
+//! Statistics group sessions by the local day (and week, starting Monday) they
+//! started on.
+
+use super::commands::atomic_write;
+use super::types::{PlaySession, PlaySessionStats, PlayTimeTotal};
+use chrono::{DateTime, Datelike, Days, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{Listener, Manager};
+
+const HISTORY_FILE: &str = "sessions.jsonl";
+const HEARTBEAT_FILE: &str = "session-heartbeat.json";
+
+/// Session IDs are UTC start timestamps, which sort chronologically.
+const SESSION_ID_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// How often the heartbeat of a running session is written
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Days and weeks covered by the statistics
+pub const STATS_DAYS: u32 = 14;
+pub const STATS_WEEKS: u32 = 8;
+
+/// Sessions returned by list_play_sessions when no limit is given
+const DEFAULT_SESSION_LIMIT: u32 = 20;
+
+/// One line of sessions.jsonl
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "camelCase")]
+enum SessionRecord {
+    #[serde(rename_all = "camelCase")]
+    Started {
+        id: String,
+        profile_name: String,
+        process_name: String,
+        at: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Ended {
+        id: String,
+        at: String,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        recovered: bool,
+    },
+}
+
+/// Last time the game was seen running during a session
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Heartbeat {
+    id: String,
+    last_seen: String,
+}
+
+/// Payload of target-process-detected (see platform::process_monitor)
+#[derive(Debug, Deserialize)]
+struct ProcessEvent {
+    process_name: String,
+    profile_name: String,
+}
+
+/// ID of the session being recorded
+static CURRENT_SESSION: Mutex<Option<String>> = Mutex::new(None);
+
+/// Serializes appends, recovery and heartbeats
+static SESSIONS_LOCK: Mutex<()> = Mutex::new(());
+
+fn history_file(root: &Path) -> PathBuf {
+    root.join(HISTORY_FILE)
+}
+
+fn heartbeat_file(root: &Path) -> PathBuf {
+    root.join(HEARTBEAT_FILE)
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+fn seconds_between(start: &str, end: DateTime<Utc>) -> u64 {
+    parse_time(start)
+        .map(|start| end.signed_duration_since(start).num_seconds().max(0) as u64)
+        .unwrap_or(0)
+}
+
+fn append_record(root: &Path, record: &SessionRecord) -> Result<(), String> {
+    let mut line = serde_json::to_string(record)
+        .map_err(|e| format!("Failed to serialize session record: {}", e))?;
+    line.push('\n');
+
+    fs::create_dir_all(root).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(history_file(root))
+        .map_err(|e| format!("Failed to open session history: {}", e))?;
+
+    // A crash mid-append can leave a partial last line; start on a new line so
+    // the record is not glued onto it
+    if file.seek(SeekFrom::End(0)).unwrap_or(0) > 0 {
+        let mut last = [0u8; 1];
+        if file.seek(SeekFrom::End(-1)).is_ok()
+            && file.read_exact(&mut last).is_ok()
+            && last[0] != b'\n'
+        {
+            line.insert(0, '\n');
+        }
+    }
+
+    file.write_all(line.as_bytes())
+        .map_err(|e| format!("Failed to write session history: {}", e))
+}
+
+/// Rebuild the sessions from the history records, oldest first.
+/// Sessions without an end record run until `now`. Unreadable lines are skipped.
+fn replay(contents: &str, now: DateTime<Utc>) -> Vec<PlaySession> {
+    let mut sessions: Vec<PlaySession> = Vec::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = match serde_json::from_str::<SessionRecord>(line) {
+            Ok(record) => record,
+            Err(e) => {
+                log::warn!("Skipping session history line {}: {}", index + 1, e);
+                continue;
+            }
+        };
+
+        match record {
+            SessionRecord::Started {
+                id,
+                profile_name,
+                process_name,
+                at,
+            } => sessions.push(PlaySession {
+                id,
+                profile_name,
+                process_name,
+                started_at: at,
+                ended_at: None,
+                duration_secs: 0,
+                recovered: false,
+            }),
+            SessionRecord::Ended { id, at, recovered } => {
+                if let Some(session) = sessions
+                    .iter_mut()
+                    .find(|s| s.id == id && s.ended_at.is_none())
+                {
+                    session.ended_at = Some(at);
+                    session.recovered = recovered;
+                }
+            }
+        }
+    }
+
+    for session in &mut sessions {
+        let end = session
+            .ended_at
+            .as_deref()
+            .and_then(parse_time)
+            .unwrap_or(now);
+        session.duration_secs = seconds_between(&session.started_at, end);
+    }
+    sessions.sort_by(|a, b| a.id.cmp(&b.id));
+    sessions
+}
+
+/// All recorded sessions, oldest first
+pub fn load_sessions(root: &Path) -> Vec<PlaySession> {
+    let contents = fs::read_to_string(history_file(root)).unwrap_or_default();
+    replay(&contents, Utc::now())
+}
+
+/// Close sessions left open by a previous run, at the time the game was last seen.
+/// Returns the number of sessions closed.
+pub fn recover_open_sessions(root: &Path) -> Result<u32, String> {
+    let _guard = SESSIONS_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+
+    let heartbeat: Option<Heartbeat> = fs::read_to_string(heartbeat_file(root))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok());
+
+    let mut closed = 0;
+    for session in load_sessions(root).iter().filter(|s| s.ended_at.is_none()) {
+        // Without a heartbeat for this session only its start is known
+        let at = heartbeat
+            .as_ref()
+            .filter(|h| h.id == session.id)
+            .map(|h| h.last_seen.clone())
+            .unwrap_or_else(|| session.started_at.clone());
+        append_record(
+            root,
+            &SessionRecord::Ended {
+                id: session.id.clone(),
+                at,
+                recovered: true,
+            },
+        )?;
+        log::info!(
+            "Closed play session {} ({}) left open by the previous run",
+            session.id,
+            session.profile_name
+        );
+        closed += 1;
+    }
+
+    let _ = fs::remove_file(heartbeat_file(root));
+    Ok(closed)
+}
+
+/// Start recording a session. An unfinished current session is ended first.
+pub fn begin_session(
+    root: &Path,
+    profile_name: &str,
+    process_name: &str,
+    now: DateTime<Utc>,
+) -> Result<String, String> {
+    end_session(root, now)?;
+
+    let _guard = SESSIONS_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let id = now.format(SESSION_ID_FORMAT).to_string();
+    append_record(
+        root,
+        &SessionRecord::Started {
+            id: id.clone(),
+            profile_name: profile_name.to_string(),
+            process_name: process_name.to_string(),
+            at: now.to_rfc3339(),
+        },
+    )?;
+    *CURRENT_SESSION.lock().unwrap_or_else(|p| p.into_inner()) = Some(id.clone());
+    log::info!("Play session {} started ({})", id, profile_name);
+    Ok(id)
+}
+
+/// End the current session, if one is being recorded
+pub fn end_session(root: &Path, now: DateTime<Utc>) -> Result<(), String> {
+    let _guard = SESSIONS_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let Some(id) = CURRENT_SESSION
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .take()
+    else {
+        return Ok(());
+    };
+
+    append_record(
+        root,
+        &SessionRecord::Ended {
+            id: id.clone(),
+            at: now.to_rfc3339(),
+            recovered: false,
+        },
+    )?;
+    let _ = fs::remove_file(heartbeat_file(root));
+    log::info!("Play session {} ended", id);
+    Ok(())
+}
+
+/// Note that the current session is still running
+fn write_heartbeat(root: &Path, now: DateTime<Utc>) -> Result<(), String> {
+    let _guard = SESSIONS_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let Some(id) = CURRENT_SESSION
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .clone()
+    else {
+        return Ok(());
+    };
+
+    let heartbeat = Heartbeat {
+        id,
+        last_seen: now.to_rfc3339(),
+    };
+    let json = serde_json::to_vec(&heartbeat)
+        .map_err(|e| format!("Failed to serialize heartbeat: {}", e))?;
+    atomic_write(&heartbeat_file(root), &json)
+}
+
+/// Per-day and per-week totals, longest session and overall totals.
+/// Days and weeks are those of `now`'s time zone.
+pub fn compute_stats<Tz: TimeZone>(
+    sessions: &[PlaySession],
+    now: DateTime<Tz>,
+) -> PlaySessionStats {
+    let today = now.date_naive();
+    let this_monday = today - Days::new(u64::from(today.weekday().num_days_from_monday()));
+    let first_day = today - Days::new(u64::from(STATS_DAYS - 1));
+    let first_week = this_monday - Days::new(7 * u64::from(STATS_WEEKS - 1));
+
+    let mut days: BTreeMap<NaiveDate, PlayTimeTotal> = BTreeMap::new();
+    let mut weeks: BTreeMap<NaiveDate, PlayTimeTotal> = BTreeMap::new();
+    let empty = |date: NaiveDate| PlayTimeTotal {
+        period_start: date.format("%Y-%m-%d").to_string(),
+        sessions: 0,
+        total_secs: 0,
+    };
+    for offset in 0..STATS_DAYS {
+        let date = first_day + Days::new(u64::from(offset));
+        days.insert(date, empty(date));
+    }
+    for offset in 0..STATS_WEEKS {
+        let date = first_week + Days::new(7 * u64::from(offset));
+        weeks.insert(date, empty(date));
+    }
+
+    for session in sessions {
+        let Some(started) = parse_time(&session.started_at) else {
+            continue;
+        };
+        let date = started.with_timezone(&now.timezone()).date_naive();
+        let monday = date - Days::new(u64::from(date.weekday().num_days_from_monday()));
+        for total in [days.get_mut(&date), weeks.get_mut(&monday)]
+            .into_iter()
+            .flatten()
+        {
+            total.sessions += 1;
+            total.total_secs += session.duration_secs;
+        }
+    }
+
+    PlaySessionStats {
+        total_sessions: sessions.len() as u32,
+        total_secs: sessions.iter().map(|s| s.duration_secs).sum(),
+        // First of equally long sessions
+        longest_session: sessions
+            .iter()
+            .rev()
+            .max_by_key(|s| s.duration_secs)
+            .cloned(),
+        days: days.into_values().collect(),
+        weeks: weeks.into_values().collect(),
+    }
+}
+
+/// Record sessions from the process monitor's events (call once at startup).
+/// Sessions left open by the previous run are closed first.
+pub fn start_session_recorder(app: &tauri::AppHandle) {
+    let root = match app.path().app_data_dir() {
+        Ok(root) => root,
+        Err(e) => {
+            log::warn!(
+                "Play session history disabled: failed to get app data dir: {}",
+                e
+            );
+            return;
+        }
+    };
+
+    if let Err(e) = recover_open_sessions(&root) {
+        log::warn!("Failed to close open play sessions: {}", e);
+    }
+
+    let detected_root = root.clone();
+    app.listen_any(
+        "target-process-detected",
+        move |event| match serde_json::from_str::<ProcessEvent>(event.payload()) {
+            Ok(process) => {
+                if let Err(e) = begin_session(
+                    &detected_root,
+                    &process.profile_name,
+                    &process.process_name,
+                    Utc::now(),
+                ) {
+                    log::warn!("Failed to record play session start: {}", e);
+                }
+            }
+            Err(e) => log::warn!("Unexpected target-process-detected payload: {}", e),
+        },
+    );
+
+    let terminated_root = root.clone();
+    app.listen_any("target-process-terminated", move |_| {
+        if let Err(e) = end_session(&terminated_root, Utc::now()) {
+            log::warn!("Failed to record play session end: {}", e);
+        }
+    });
+
+    thread::spawn(move || loop {
+        thread::sleep(HEARTBEAT_INTERVAL);
+        if let Err(e) = write_heartbeat(&root, Utc::now()) {
+            log::warn!("Failed to write play session heartbeat: {}", e);
+        }
+    });
+}
+
+/// End the current session when the overlay exits while the game still runs
+pub fn end_session_on_exit(app: &tauri::AppHandle) {
+    let root = match app.path().app_data_dir() {
+        Ok(root) => root,
+        Err(e) => {
+            log::warn!(
+                "Failed to end play session: failed to get app data dir: {}",
+                e
+            );
+            return;
+        }
+    };
+    if let Err(e) = end_session(&root, Utc::now()) {
+        log::warn!("Failed to record play session end: {}", e);
+    }
+}
+
+// ============================================================================
+// Tauri commands
+// ============================================================================
+
+fn get_app_data_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))
+}
+
+/// List recorded play sessions, newest first (the running one included).
+#[tauri::command]
+pub async fn list_play_sessions(
+    app: tauri::AppHandle,
+    limit: Option<u32>,
+) -> Result<Vec<PlaySession>, String> {
+    let root = get_app_data_dir(&app)?;
+    let limit = limit.unwrap_or(DEFAULT_SESSION_LIMIT) as usize;
+    Ok(load_sessions(&root).into_iter().rev().take(limit).collect())
+}
+
+/// Play time per day and week, longest session and totals.
+#[tauri::command]
+pub async fn get_play_session_stats(app: tauri::AppHandle) -> Result<PlaySessionStats, String> {
+    let root = get_app_data_dir(&app)?;
+    Ok(compute_stats(&load_sessions(&root), chrono::Local::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::test_support::TempDir;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        parse_time(value).unwrap()
+    }
+
+    fn session(started_at: &str, duration_secs: u64) -> PlaySession {
+        PlaySession {
+            id: at(started_at).format(SESSION_ID_FORMAT).to_string(),
+            profile_name: "LIVE".to_string(),
+            process_name: "StarCitizen.exe".to_string(),
+            started_at: started_at.to_string(),
+            ended_at: None,
+            duration_secs,
+            recovered: false,
+        }
+    }
+
+    #[test]
+    fn test_replay_records() {
+        let contents = r#"{"event":"started","id":"a","profileName":"LIVE","processName":"StarCitizen.exe","at":"2025-03-01T10:00:00+00:00"}
+{"event":"ended","id":"a","at":"2025-03-01T12:30:00+00:00"}
+not json
+{"event":"started","id":"b","profileName":"PTU","processName":"StarCitizen_PTU.exe","at":"2025-03-02T20:00:00+00:00"}
+"#;
+        let sessions = replay(contents, at("2025-03-02T20:10:00Z"));
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].duration_secs, 2 * 3600 + 30 * 60);
+        assert_eq!(
+            sessions[0].ended_at.as_deref(),
+            Some("2025-03-01T12:30:00+00:00")
+        );
+        // Still running: duration so far
+        assert_eq!(sessions[1].ended_at, None);
+        assert_eq!(sessions[1].duration_secs, 600);
+        assert_eq!(sessions[1].profile_name, "PTU");
+    }
+
+    #[test]
+    fn test_append_after_partial_line() {
+        let root = TempDir::new("sessions-partial");
+        fs::write(history_file(&root), r#"{"event":"started","id":"a","#).unwrap();
+
+        let record = SessionRecord::Ended {
+            id: "a".to_string(),
+            at: "2025-03-01T12:30:00+00:00".to_string(),
+            recovered: false,
+        };
+        append_record(&root, &record).unwrap();
+
+        let contents = fs::read_to_string(history_file(&root)).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(serde_json::from_str::<SessionRecord>(lines[1]).is_ok());
+    }
+
+    #[test]
+    fn test_recover_open_session_at_heartbeat() {
+        let root = TempDir::new("sessions-recover");
+        // Only one session can be current, so this test owns CURRENT_SESSION
+        begin_session(&root, "LIVE", "StarCitizen.exe", at("2025-03-01T10:00:00Z")).unwrap();
+        write_heartbeat(&root, at("2025-03-01T11:15:00Z")).unwrap();
+
+        // Simulate a crash: the session is never ended
+        CURRENT_SESSION.lock().unwrap().take();
+
+        assert_eq!(recover_open_sessions(&root).unwrap(), 1);
+        let sessions = load_sessions(&root);
+        assert_eq!(sessions.len(), 1);
+        assert!(sessions[0].recovered);
+        assert_eq!(sessions[0].duration_secs, 75 * 60);
+        assert!(!heartbeat_file(&root).exists());
+
+        // Nothing left to recover
+        assert_eq!(recover_open_sessions(&root).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let sessions = vec![
+            session("2025-02-20T09:00:00Z", 3600), // previous week, outside the days
+            session("2025-03-03T18:00:00Z", 1800), // Monday
+            session("2025-03-05T18:00:00Z", 7200),
+            session("2025-03-05T22:00:00Z", 600),
+        ];
+        // Wednesday
+        let stats = compute_stats(&sessions, at("2025-03-05T23:00:00Z"));
+
+        assert_eq!(stats.total_sessions, 4);
+        assert_eq!(stats.total_secs, 3600 + 1800 + 7200 + 600);
+        assert_eq!(
+            stats.longest_session.as_ref().map(|s| s.duration_secs),
+            Some(7200)
+        );
+
+        assert_eq!(stats.days.len(), STATS_DAYS as usize);
+        let today = stats.days.last().unwrap();
+        assert_eq!(today.period_start, "2025-03-05");
+        assert_eq!((today.sessions, today.total_secs), (2, 7800));
+        assert_eq!(stats.days[STATS_DAYS as usize - 2].sessions, 0);
+
+        assert_eq!(stats.weeks.len(), STATS_WEEKS as usize);
+        let this_week = stats.weeks.last().unwrap();
+        assert_eq!(this_week.period_start, "2025-03-03");
+        assert_eq!((this_week.sessions, this_week.total_secs), (3, 9600));
+        let february = &stats.weeks[STATS_WEEKS as usize - 3];
+        assert_eq!(february.period_start, "2025-02-17");
+        assert_eq!(february.total_secs, 3600);
+    }
+}
@@ -10,7 +10,13 @@
 
 use serde::{Deserialize, Serialize};
 
-pub const CURRENT_STATE_VERSION: u32 = 1;
+/// Schema version of state.json. Bump together with a new entry in
+/// `migrations::STATE_MIGRATIONS`.
+pub const CURRENT_STATE_VERSION: u32 = 2;
+
+/// Schema version of window-{id}.json files. Bump together with a new entry in
+/// `migrations::WINDOW_CONTENT_MIGRATIONS`.
+pub const CURRENT_WINDOW_CONTENT_VERSION: u32 = 2;
 
 // ============================================================================
 // Persisted State (state.json)
@@ -107,6 +113,7 @@ pub enum WidgetType {
     Clock,
     Timer,
     Chronometer,
+    Alarm,
 }
 
 /// Persisted widget structure for storage
@@ -134,6 +141,9 @@ fn default_widget_opacity() -> f32 {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowContentFile {
+    /// Schema version (files written before versioning are migrated on load)
+    #[serde(default = "default_window_content_version")]
+    pub version: u32,
     pub window_id: String,
     #[serde(rename = "type")]
     pub window_type: WindowType,
@@ -141,6 +151,10 @@ pub struct WindowContentFile {
     pub last_modified: String,
 }
 
+fn default_window_content_version() -> u32 {
+    CURRENT_WINDOW_CONTENT_VERSION
+}
+
 // ============================================================================
 // IPC Result Types
 // ============================================================================
@@ -151,10 +165,39 @@ pub struct LoadStateResult {
     pub success: bool,
     pub state: Option<PersistedState>,
     pub window_contents: Vec<WindowContentFile>,
+    /// Schema migrations applied while loading (empty if none were needed)
+    pub migrations: Vec<MigrationRecord>,
+    /// Snapshot restored because state.json was corrupt (None on a normal load)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovered_from_snapshot: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
 }
 
+/// A single schema migration step applied to a persisted file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationRecord {
+    pub file: String,
+    pub from_version: u32,
+    pub to_version: u32,
+    pub description: String,
+}
+
+/// A snapshot in the state backup ring
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    /// Snapshot directory name (UTC timestamp)
+    pub id: String,
+    /// Creation time (ISO 8601)
+    pub created_at: String,
+    pub window_count: u32,
+    pub total_bytes: u64,
+    /// Whether the snapshot's state.json loads cleanly
+    pub valid: bool,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SaveResult {
@@ -172,6 +215,146 @@ pub struct DeleteResult {
     pub error: Option<String>,
 }
 
+/// A named layout profile
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileInfo {
+    pub name: String,
+    /// Whether this is the currently active profile
+    pub active: bool,
+    /// Whether this is the built-in default profile (cannot be renamed or deleted)
+    pub is_default: bool,
+    /// Modification time of the profile's state.json (ISO 8601), if saved yet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileResult {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Payload of the `profile-switched` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSwitchedPayload {
+    pub previous: String,
+    pub current: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportWorkspaceResult {
+    pub success: bool,
+    /// Number of windows written to the archive
+    pub window_count: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportWorkspaceResult {
+    pub success: bool,
+    /// Number of window content files imported
+    pub window_count: u32,
+    /// Number of window/widget IDs changed to avoid clashes
+    pub remapped_ids: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of flushing the write-behind persistence queue
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlushResult {
+    pub success: bool,
+    /// Files written by this flush
+    pub flushed: u32,
+    /// Files that failed to write in this flush (they stay queued and are retried)
+    pub failed: u32,
+    /// Writes still queued after this flush, including failed writes awaiting a retry
+    pub pending: u32,
+    /// Saves dropped since startup because a newer save replaced them
+    pub dropped: u64,
+    /// Files written since startup
+    pub total_written: u64,
+    /// Failed writes since startup
+    pub total_failed: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A stored revision of a window's content
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionInfo {
+    /// Revision file name (UTC timestamp)
+    pub id: String,
+    /// Creation time (ISO 8601)
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreRevisionResult {
+    pub success: bool,
+    /// The restored content, to be applied to the open window
+    pub content: Option<WindowContentFile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// ============================================================================
+// Play Session History
+// ============================================================================
+
+/// A recorded game session (from target-process-detected to -terminated)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaySession {
+    /// Session ID (UTC start timestamp)
+    pub id: String,
+    /// Target profile the game was detected with (e.g., "LIVE")
+    pub profile_name: String,
+    pub process_name: String,
+    /// Start time (ISO 8601)
+    pub started_at: String,
+    /// End time (ISO 8601), None while the session is running
+    pub ended_at: Option<String>,
+    /// Duration in seconds (so far, for a running session)
+    pub duration_secs: u64,
+    /// Closed on a later launch because the overlay exited while the game ran
+    pub recovered: bool,
+}
+
+/// Play time of one day or week
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayTimeTotal {
+    /// Local date of the day, or of the Monday starting the week (YYYY-MM-DD)
+    pub period_start: String,
+    pub sessions: u32,
+    pub total_secs: u64,
+}
+
+/// Result of the get_play_session_stats command
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaySessionStats {
+    pub total_sessions: u32,
+    pub total_secs: u64,
+    pub longest_session: Option<PlaySession>,
+    /// Recent days, oldest first (days without play included)
+    pub days: Vec<PlayTimeTotal>,
+    /// Recent weeks, oldest first (weeks without play included)
+    pub weeks: Vec<PlayTimeTotal>,
+}
+
 // ============================================================================
 // Default Implementations
 // ============================================================================
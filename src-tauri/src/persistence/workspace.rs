@@ -0,0 +1,634 @@
+//! Workspace Export/Import
+//!
+//! Bundles the active profile's `state.json` (windows, widgets, global settings)
+//! and every `window-{id}.json` into a single versioned JSON archive with a
+//! manifest, so layouts can be shared between installations.
+//!
+//! Imports are merged into the active profile: clashing window and widget IDs
+//! are remapped, and the whole archive is migrated and validated before any
+//! file is written.
+//!
+//! @feature 010-state-persistence-system
+
+use super::backups;
+use super::commands::atomic_write;
+use super::migrations;
+use super::profiles;
+use super::queue;
+use super::types::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Identifies a workspace archive file
+pub const WORKSPACE_FORMAT: &str = "raic-overlay-workspace";
+
+/// Version of the archive layout (manifest + state + window contents).
+/// Independent of the state/window content schema versions, which are
+/// migrated on import.
+pub const CURRENT_WORKSPACE_VERSION: u32 = 1;
+
+const STATE_FILE: &str = "state.json";
+const WINDOW_FILE_PREFIX: &str = "window-";
+
+/// Archive manifest describing what the bundle contains
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceManifest {
+    pub format: String,
+    pub workspace_version: u32,
+    /// Version of the app that wrote the archive
+    pub app_version: String,
+    /// Export time (ISO 8601)
+    pub exported_at: String,
+    pub state_version: u32,
+    pub window_count: u32,
+    pub widget_count: u32,
+}
+
+/// The archive written by export_workspace
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceArchive {
+    pub manifest: WorkspaceManifest,
+    pub state: PersistedState,
+    pub window_contents: Vec<WindowContentFile>,
+}
+
+/// Raw archive as read on import, before migration and validation
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawWorkspaceArchive {
+    manifest: WorkspaceManifest,
+    state: Value,
+    #[serde(default)]
+    window_contents: Vec<Value>,
+}
+
+/// An archive that passed validation and is ready to be merged
+#[derive(Debug)]
+pub struct ValidatedWorkspace {
+    pub state: PersistedState,
+    pub window_contents: Vec<WindowContentFile>,
+}
+
+/// Result of merging an imported workspace into the existing state
+#[derive(Debug)]
+pub struct MergedWorkspace {
+    pub state: PersistedState,
+    pub window_contents: Vec<WindowContentFile>,
+    /// Number of window and widget IDs that had to be changed
+    pub remapped: u32,
+}
+
+// ============================================================================
+// Pure archive logic
+// ============================================================================
+
+/// IDs become file names (`window-{id}.json`), so only a safe subset is allowed.
+fn is_safe_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Build an archive from the current state and window contents.
+pub fn build_archive(
+    state: PersistedState,
+    window_contents: Vec<WindowContentFile>,
+    app_version: &str,
+) -> WorkspaceArchive {
+    WorkspaceArchive {
+        manifest: WorkspaceManifest {
+            format: WORKSPACE_FORMAT.to_string(),
+            workspace_version: CURRENT_WORKSPACE_VERSION,
+            app_version: app_version.to_string(),
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            state_version: state.version,
+            window_count: state.windows.len() as u32,
+            widget_count: state.widgets.len() as u32,
+        },
+        state,
+        window_contents,
+    }
+}
+
+/// Parse, migrate and validate an archive without touching the filesystem.
+pub fn parse_archive(contents: &str) -> Result<ValidatedWorkspace, String> {
+    let raw: RawWorkspaceArchive =
+        serde_json::from_str(contents).map_err(|e| format!("Invalid workspace archive: {}", e))?;
+
+    if raw.manifest.format != WORKSPACE_FORMAT {
+        return Err(format!(
+            "Not a workspace archive (format '{}')",
+            raw.manifest.format
+        ));
+    }
+    if raw.manifest.workspace_version > CURRENT_WORKSPACE_VERSION {
+        return Err(format!(
+            "Workspace archive version {} is newer than supported version {}",
+            raw.manifest.workspace_version, CURRENT_WORKSPACE_VERSION
+        ));
+    }
+
+    let mut state_value = raw.state;
+    migrations::migrate_state(&mut state_value)?;
+    let state: PersistedState = serde_json::from_value(state_value)
+        .map_err(|e| format!("Invalid state in archive: {}", e))?;
+
+    let mut window_contents = Vec::with_capacity(raw.window_contents.len());
+    for (index, mut value) in raw.window_contents.into_iter().enumerate() {
+        let label = format!("windowContents[{}]", index);
+        migrations::migrate_window_content(&mut value, &label)?;
+        let content: WindowContentFile = serde_json::from_value(value)
+            .map_err(|e| format!("Invalid window content in archive ({}): {}", label, e))?;
+        window_contents.push(content);
+    }
+
+    let workspace = ValidatedWorkspace {
+        state,
+        window_contents,
+    };
+    validate_workspace(&workspace)?;
+    Ok(workspace)
+}
+
+/// Check the internal consistency of an archive.
+pub fn validate_workspace(workspace: &ValidatedWorkspace) -> Result<(), String> {
+    let mut window_types = HashMap::new();
+    for window in &workspace.state.windows {
+        if !is_safe_id(&window.id) {
+            return Err(format!("Invalid window ID '{}'", window.id));
+        }
+        if window_types
+            .insert(
+                window.id.as_str(),
+                std::mem::discriminant(&window.window_type),
+            )
+            .is_some()
+        {
+            return Err(format!("Duplicate window ID '{}'", window.id));
+        }
+    }
+
+    let mut widget_ids = HashSet::new();
+    for widget in &workspace.state.widgets {
+        if !is_safe_id(&widget.id) {
+            return Err(format!("Invalid widget ID '{}'", widget.id));
+        }
+        if !widget_ids.insert(widget.id.as_str()) {
+            return Err(format!("Duplicate widget ID '{}'", widget.id));
+        }
+    }
+
+    let mut seen_contents = HashSet::new();
+    for content in &workspace.window_contents {
+        let Some(window_type) = window_types.get(content.window_id.as_str()) else {
+            return Err(format!(
+                "Window content '{}' has no matching window",
+                content.window_id
+            ));
+        };
+        if *window_type != std::mem::discriminant(&content.window_type) {
+            return Err(format!(
+                "Window content '{}' does not match its window type",
+                content.window_id
+            ));
+        }
+        if !seen_contents.insert(content.window_id.as_str()) {
+            return Err(format!(
+                "Duplicate window content for '{}'",
+                content.window_id
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Generate an ID with the frontend's `{prefix}_{base36}` shape that is not in `taken`.
+fn fresh_id(prefix: &str, taken: &HashSet<String>) -> String {
+    let millis = chrono::Utc::now().timestamp_millis().max(0) as u64;
+    let mut n: u64 = 0;
+    loop {
+        let id = format!("{}_{}_{}", prefix, to_base36(millis), to_base36(n));
+        if !taken.contains(&id) {
+            return id;
+        }
+        n += 1;
+    }
+}
+
+fn to_base36(mut value: u64) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    if value == 0 {
+        return "0".to_string();
+    }
+    let mut out = Vec::new();
+    while value > 0 {
+        out.push(DIGITS[(value % 36) as usize]);
+        value /= 36;
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap_or_default()
+}
+
+/// Merge an imported workspace into the existing state.
+///
+/// Imported windows and widgets are appended (windows stacked above existing
+/// ones); any ID already in use is replaced with a fresh one. The existing
+/// global settings are kept. Without existing state, the import is used as-is.
+pub fn merge_workspace(
+    existing: Option<PersistedState>,
+    imported: ValidatedWorkspace,
+) -> MergedWorkspace {
+    let ValidatedWorkspace {
+        state: imported_state,
+        window_contents: mut imported_contents,
+    } = imported;
+
+    let mut state = existing.unwrap_or_else(|| PersistedState {
+        global: imported_state.global.clone(),
+        ..PersistedState::default()
+    });
+
+    let mut taken: HashSet<String> = state
+        .windows
+        .iter()
+        .map(|w| w.id.clone())
+        .chain(state.widgets.iter().map(|w| w.id.clone()))
+        .collect();
+    let mut remapped = 0;
+    let mut window_id_map = HashMap::new();
+
+    let z_offset = state
+        .windows
+        .iter()
+        .map(|w| w.z_index + 1)
+        .max()
+        .unwrap_or(0);
+    for mut window in imported_state.windows {
+        if taken.contains(&window.id) {
+            let new_id = fresh_id("win", &taken);
+            window_id_map.insert(window.id.clone(), new_id.clone());
+            window.id = new_id;
+            remapped += 1;
+        }
+        window.z_index += z_offset;
+        taken.insert(window.id.clone());
+        state.windows.push(window);
+    }
+
+    for mut widget in imported_state.widgets {
+        if taken.contains(&widget.id) {
+            widget.id = fresh_id("wgt", &taken);
+            remapped += 1;
+        }
+        taken.insert(widget.id.clone());
+        state.widgets.push(widget);
+    }
+
+    for content in &mut imported_contents {
+        if let Some(new_id) = window_id_map.get(&content.window_id) {
+            content.window_id = new_id.clone();
+        }
+        content.version = CURRENT_WINDOW_CONTENT_VERSION;
+    }
+
+    state.version = CURRENT_STATE_VERSION;
+    state.last_modified = chrono::Utc::now().to_rfc3339();
+
+    MergedWorkspace {
+        state,
+        window_contents: imported_contents,
+        remapped,
+    }
+}
+
+// ============================================================================
+// File access
+// ============================================================================
+
+/// Read the profile's current state and the content files of its windows.
+fn read_profile_workspace(
+    data_dir: &Path,
+) -> Result<Option<(PersistedState, Vec<WindowContentFile>)>, String> {
+    let state_path = data_dir.join(STATE_FILE);
+    if !state_path.exists() {
+        return Ok(None);
+    }
+
+    let contents =
+        fs::read_to_string(&state_path).map_err(|e| format!("Failed to read state: {}", e))?;
+    let mut value: Value =
+        serde_json::from_str(&contents).map_err(|e| format!("Invalid state JSON: {}", e))?;
+    migrations::migrate_state(&mut value)?;
+    let state: PersistedState =
+        serde_json::from_value(value).map_err(|e| format!("Invalid state JSON: {}", e))?;
+
+    let mut window_contents = Vec::new();
+    for window in &state.windows {
+        let file_name = format!("{}{}.json", WINDOW_FILE_PREFIX, window.id);
+        let Ok(contents) = fs::read_to_string(data_dir.join(&file_name)) else {
+            continue;
+        };
+        let parsed = serde_json::from_str::<Value>(&contents)
+            .map_err(|e| e.to_string())
+            .and_then(|mut value| {
+                migrations::migrate_window_content(&mut value, &file_name)?;
+                serde_json::from_value::<WindowContentFile>(value).map_err(|e| e.to_string())
+            });
+        match parsed {
+            Ok(content) => window_contents.push(content),
+            Err(e) => log::warn!("Skipping {} in workspace: {}", file_name, e),
+        }
+    }
+
+    Ok(Some((state, window_contents)))
+}
+
+/// Write a merged workspace: window files first, state.json last, so an
+/// interrupted import never references window files that were not written.
+fn write_merged_workspace(data_dir: &Path, merged: &MergedWorkspace) -> Result<(), String> {
+    fs::create_dir_all(data_dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    for content in &merged.window_contents {
+        let json = serde_json::to_string_pretty(content)
+            .map_err(|e| format!("Serialization failed: {}", e))?;
+        let path = data_dir.join(format!("{}{}.json", WINDOW_FILE_PREFIX, content.window_id));
+        atomic_write(&path, json.as_bytes())?;
+    }
+
+    let json = serde_json::to_string_pretty(&merged.state)
+        .map_err(|e| format!("Serialization failed: {}", e))?;
+    atomic_write(&data_dir.join(STATE_FILE), json.as_bytes())
+}
+
+/// Export a profile directory to an archive file.
+pub fn export_to_file(data_dir: &Path, path: &Path, app_version: &str) -> Result<u32, String> {
+    let (state, window_contents) =
+        read_profile_workspace(data_dir)?.ok_or_else(|| "No saved state to export".to_string())?;
+    let archive = build_archive(state, window_contents, app_version);
+    let window_count = archive.manifest.window_count;
+
+    let json = serde_json::to_string_pretty(&archive)
+        .map_err(|e| format!("Serialization failed: {}", e))?;
+    atomic_write(&path.to_path_buf(), json.as_bytes())
+        .map_err(|e| format!("Failed to write archive: {}", e))?;
+    Ok(window_count)
+}
+
+/// Import an archive file into a profile directory.
+pub fn import_from_file(data_dir: &Path, path: &Path) -> Result<MergedWorkspace, String> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read archive: {}", e))?;
+
+    // Validate everything before any file is written
+    let imported = parse_archive(&contents)?;
+    let existing = read_profile_workspace(data_dir)?.map(|(state, _)| state);
+
+    let merged = merge_workspace(existing, imported);
+
+    if let Err(e) = backups::create_snapshot(data_dir) {
+        log::warn!("Failed to snapshot state before import: {}", e);
+    }
+    write_merged_workspace(data_dir, &merged)?;
+    Ok(merged)
+}
+
+// ============================================================================
+// Tauri commands
+// ============================================================================
+
+/// Export the active profile to a workspace archive at `path`.
+#[tauri::command]
+pub async fn export_workspace(
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<ExportWorkspaceResult, String> {
+    let data_dir = profiles::get_active_profile_dir(&app)?;
+    queue::flush_all();
+    let app_version = app.package_info().version.to_string();
+
+    match export_to_file(&data_dir, &PathBuf::from(&path), &app_version) {
+        Ok(window_count) => {
+            log::info!("Workspace exported to {} ({} windows)", path, window_count);
+            Ok(ExportWorkspaceResult {
+                success: true,
+                window_count,
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("Workspace export failed: {}", e);
+            Ok(ExportWorkspaceResult {
+                success: false,
+                window_count: 0,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+/// Import a workspace archive from `path` into the active profile.
+///
+/// The frontend should reload via load_state after a successful import.
+#[tauri::command]
+pub async fn import_workspace(
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<ImportWorkspaceResult, String> {
+    let data_dir = profiles::get_active_profile_dir(&app)?;
+    queue::flush_all();
+
+    match import_from_file(&data_dir, &PathBuf::from(&path)) {
+        Ok(merged) => {
+            log::info!(
+                "Workspace imported from {} ({} IDs remapped)",
+                path,
+                merged.remapped
+            );
+            Ok(ImportWorkspaceResult {
+                success: true,
+                window_count: merged.window_contents.len() as u32,
+                remapped_ids: merged.remapped,
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("Workspace import failed: {}", e);
+            Ok(ImportWorkspaceResult {
+                success: false,
+                window_count: 0,
+                remapped_ids: 0,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::test_support::TempDir;
+    use serde_json::json;
+
+    fn window(id: &str, window_type: &str, z_index: u32) -> Value {
+        json!({
+            "id": id,
+            "type": window_type,
+            "position": { "x": 0, "y": 0 },
+            "size": { "width": 300, "height": 200 },
+            "zIndex": z_index,
+            "flags": { "minimized": false, "maximized": false }
+        })
+    }
+
+    fn archive(windows: Vec<Value>, contents: Vec<Value>) -> String {
+        json!({
+            "manifest": {
+                "format": WORKSPACE_FORMAT,
+                "workspaceVersion": 1,
+                "appVersion": "0.0.0",
+                "exportedAt": "2025-01-01T00:00:00Z",
+                "stateVersion": 2,
+                "windowCount": windows.len(),
+                "widgetCount": 1
+            },
+            "state": {
+                "version": 2,
+                "lastModified": "2025-01-01T00:00:00Z",
+                "global": { "overlayMode": "windowed", "overlayVisible": false },
+                "windows": windows,
+                "widgets": [{
+                    "id": "wgt_1",
+                    "type": "timer",
+                    "position": { "x": 0, "y": 0 },
+                    "size": { "width": 100, "height": 50 }
+                }]
+            },
+            "windowContents": contents
+        })
+        .to_string()
+    }
+
+    fn content(window_id: &str, window_type: &str) -> Value {
+        json!({
+            "windowId": window_id,
+            "type": window_type,
+            "content": { "doc": "hello" },
+            "lastModified": "2025-01-01T00:00:00Z"
+        })
+    }
+
+    #[test]
+    fn test_parse_valid_archive() {
+        let archive = archive(
+            vec![window("win_a", "notes", 0)],
+            vec![content("win_a", "notes")],
+        );
+        let workspace = parse_archive(&archive).unwrap();
+        assert_eq!(workspace.state.windows.len(), 1);
+        assert_eq!(workspace.window_contents.len(), 1);
+    }
+
+    #[test]
+    fn test_rejects_wrong_format_and_newer_version() {
+        let mut value: Value = serde_json::from_str(&archive(vec![], vec![])).unwrap();
+        value["manifest"]["format"] = json!("something-else");
+        assert!(parse_archive(&value.to_string()).is_err());
+
+        value["manifest"]["format"] = json!(WORKSPACE_FORMAT);
+        value["manifest"]["workspaceVersion"] = json!(CURRENT_WORKSPACE_VERSION + 1);
+        assert!(parse_archive(&value.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_rejects_inconsistent_archives() {
+        // Content without a window
+        let a = archive(vec![], vec![content("win_a", "notes")]);
+        assert!(parse_archive(&a).is_err());
+
+        // Content type does not match window type
+        let a = archive(
+            vec![window("win_a", "notes", 0)],
+            vec![content("win_a", "draw")],
+        );
+        assert!(parse_archive(&a).is_err());
+
+        // Duplicate window IDs
+        let a = archive(
+            vec![window("win_a", "notes", 0), window("win_a", "draw", 1)],
+            vec![],
+        );
+        assert!(parse_archive(&a).is_err());
+
+        // Path traversal in window ID
+        let a = archive(vec![window("../evil", "notes", 0)], vec![]);
+        assert!(parse_archive(&a).is_err());
+    }
+
+    #[test]
+    fn test_merge_remaps_clashing_ids() {
+        let existing = parse_archive(&archive(
+            vec![window("win_a", "notes", 3)],
+            vec![content("win_a", "notes")],
+        ))
+        .unwrap()
+        .state;
+        let imported = parse_archive(&archive(
+            vec![window("win_a", "notes", 0), window("win_b", "draw", 1)],
+            vec![content("win_a", "notes")],
+        ))
+        .unwrap();
+
+        let merged = merge_workspace(Some(existing), imported);
+
+        // win_a and wgt_1 clash, win_b does not
+        assert_eq!(merged.remapped, 2);
+        assert_eq!(merged.state.windows.len(), 3);
+        assert_eq!(merged.state.widgets.len(), 2);
+
+        let new_id = &merged.state.windows[1].id;
+        assert_ne!(new_id, "win_a");
+        assert!(new_id.starts_with("win_"));
+        assert_eq!(&merged.window_contents[0].window_id, new_id);
+        assert_eq!(merged.state.windows[2].id, "win_b");
+        assert_ne!(merged.state.widgets[1].id, "wgt_1");
+
+        // Imported windows are stacked above existing ones
+        assert_eq!(merged.state.windows[1].z_index, 4);
+        assert_eq!(merged.state.windows[2].z_index, 5);
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let dir = TempDir::new("workspace");
+        let source = dir.join("source");
+        let target = dir.join("target");
+        fs::create_dir_all(&source).unwrap();
+
+        let workspace = parse_archive(&archive(
+            vec![window("win_a", "notes", 0)],
+            vec![content("win_a", "notes")],
+        ))
+        .unwrap();
+        write_merged_workspace(&source, &merge_workspace(None, workspace)).unwrap();
+
+        let archive_path = dir.join("workspace.json");
+        assert_eq!(export_to_file(&source, &archive_path, "1.0.0").unwrap(), 1);
+
+        // Import twice: the second import must not clash with the first
+        import_from_file(&target, &archive_path).unwrap();
+        let merged = import_from_file(&target, &archive_path).unwrap();
+        assert_eq!(merged.state.windows.len(), 2);
+        assert_eq!(merged.remapped, 2);
+        for window in &merged.state.windows {
+            assert!(target.join(format!("window-{}.json", window.id)).exists());
+        }
+    }
+}
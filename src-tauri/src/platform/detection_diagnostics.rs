@@ -0,0 +1,282 @@
+// Detection diagnostics - explain why the overlay did or did not attach
+// Re-checks every candidate of a DetectionResult criterion by criterion and sums the
+// closest match up in a verdict, so the reason is visible without TRACE logs.
+
+use super::window_match::{score_candidate, CompiledCriteria, MatchWeights};
+use crate::core::types::{
+    CandidateDiagnosis, CandidateScore, CriterionCheck, DetectionDiagnostics, DetectionResult,
+    SearchCriteria, WindowCandidate,
+};
+
+// Build the diagnostics report for a detection run
+pub fn diagnose_detection(result: &DetectionResult) -> DetectionDiagnostics {
+    let profiles = &result.profiles_evaluated;
+    let compiled: Vec<CompiledCriteria> = profiles.iter().map(CompiledCriteria::new).collect();
+    let weights = MatchWeights::default();
+    let selected_hwnd = result.matched_window.as_ref().map(|w| w.hwnd);
+    let matched_index = result
+        .matched_profile
+        .as_ref()
+        .and_then(|name| profiles.iter().position(|p| &p.profile_name == name));
+
+    let mut candidates: Vec<CandidateDiagnosis> = result
+        .candidates_evaluated
+        .iter()
+        .filter_map(|candidate| {
+            let selected = selected_hwnd == Some(candidate.hwnd);
+            let scores: Vec<CandidateScore> = compiled
+                .iter()
+                .map(|criteria| score_candidate(candidate, criteria, &weights))
+                .collect();
+
+            // The selected window is checked against the matched profile, any other
+            // window against the profile it scores best for (first one on ties)
+            let index = match matched_index.filter(|_| selected) {
+                Some(index) => index,
+                None => (0..scores.len()).reduce(|best, next| {
+                    if scores[next].score > scores[best].score {
+                        next
+                    } else {
+                        best
+                    }
+                })?,
+            };
+            let score = &scores[index];
+
+            Some(CandidateDiagnosis {
+                window: candidate.clone(),
+                profile_name: profiles[index].profile_name.clone(),
+                checks: criterion_checks(candidate, &profiles[index], score),
+                score: score.score,
+                valid: score.valid,
+                selected,
+            })
+        })
+        .collect();
+
+    // Selected window first, then the closest matches (stable: keeps enumeration order)
+    candidates.sort_by(|a, b| b.selected.cmp(&a.selected).then(b.score.cmp(&a.score)));
+
+    DetectionDiagnostics {
+        success: result.success,
+        verdict: verdict(result, &candidates),
+        matched_profile: result.matched_profile.clone(),
+        matched_window: result.matched_window.clone(),
+        criteria: profiles.clone(),
+        candidates,
+        detection_time_ms: result.detection_time_ms,
+    }
+}
+
+fn criterion_checks(
+    candidate: &WindowCandidate,
+    criteria: &SearchCriteria,
+    score: &CandidateScore,
+) -> Vec<CriterionCheck> {
+    [
+        (
+            "process",
+            &criteria.process_name,
+            &candidate.process_name,
+            score.process_match,
+        ),
+        (
+            "class",
+            &criteria.window_class,
+            &candidate.window_class,
+            score.class_match,
+        ),
+        (
+            "title",
+            &criteria.window_title,
+            &candidate.window_title,
+            score.title_match,
+        ),
+    ]
+    .into_iter()
+    .map(|(criterion, expected, actual, passed)| CriterionCheck {
+        criterion: criterion.to_string(),
+        expected: expected.clone(),
+        actual: actual.clone(),
+        passed,
+    })
+    .collect()
+}
+
+// Human-readable summary of the detection outcome
+fn verdict(result: &DetectionResult, candidates: &[CandidateDiagnosis]) -> String {
+    if let (Some(window), Some(profile)) = (&result.matched_window, &result.matched_profile) {
+        return format!(
+            "Attached to '{}' ({}, class {}) using profile {}",
+            window.window_title, window.process_name, window.window_class, profile
+        );
+    }
+
+    // Closest window owned by a target process (candidates are sorted by score)
+    let near_miss = candidates.iter().find(|c| {
+        c.checks
+            .iter()
+            .any(|check| check.criterion == "process" && check.passed)
+    });
+
+    let Some(near_miss) = near_miss else {
+        let processes: Vec<&str> = result
+            .profiles_evaluated
+            .iter()
+            .map(|p| p.process_name.as_str())
+            .collect();
+        return format!(
+            "Process not found: no window belongs to {} ({} windows checked)",
+            processes.join(" or "),
+            result.candidates_evaluated.len()
+        );
+    };
+
+    let mut problems: Vec<String> = near_miss
+        .checks
+        .iter()
+        .filter(|check| !check.passed)
+        .map(|check| {
+            let actual = if check.actual.is_empty() {
+                "nothing".to_string()
+            } else if check.criterion == "title" {
+                format!("'{}'", check.actual)
+            } else {
+                check.actual.clone()
+            };
+            let expected = if check.criterion == "title" {
+                format!("'{}'", check.expected)
+            } else {
+                check.expected.clone()
+            };
+            format!(
+                "{} mismatch: expected {}, got {}",
+                check.criterion, expected, actual
+            )
+        })
+        .collect();
+    if !near_miss.window.is_top_level {
+        problems.push("window is not a top-level window".to_string());
+    }
+
+    // Every criterion matches but the window was not attached (e.g. it closed or
+    // changed between the detection and this report)
+    if problems.is_empty() {
+        problems.push("window matches every criterion but was not selected".to_string());
+    }
+
+    let verdict = format!("process found, {}", problems.join(", "));
+    if result.profiles_evaluated.len() > 1 {
+        format!("{}: {}", near_miss.profile_name, verdict)
+    } else {
+        verdict
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::WindowRect;
+    use crate::platform::mock_window_system::MockWindowSystem;
+    use crate::platform::window_system::detect_target;
+
+    fn profile(name: &str, process: &str) -> SearchCriteria {
+        SearchCriteria {
+            profile_name: name.to_string(),
+            process_name: process.to_string(),
+            window_class: "CryENGINE".to_string(),
+            window_title: "Star Citizen".to_string(),
+        }
+    }
+
+    fn rect() -> WindowRect {
+        WindowRect {
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 600,
+        }
+    }
+
+    #[test]
+    fn test_diagnose_attached() {
+        let system = MockWindowSystem::new();
+        system.add_window("explorer.exe", "CabinetWClass", "Documents", rect());
+        let game = system.add_game_window("StarCitizen.exe");
+
+        let report = diagnose_detection(&detect_target(
+            &system,
+            &[profile("LIVE", "StarCitizen.exe")],
+        ));
+
+        assert!(report.success);
+        assert_eq!(report.candidates.len(), 2);
+        assert_eq!(report.candidates[0].window.hwnd, game);
+        assert!(report.candidates[0].selected);
+        assert!(report.candidates[0].checks.iter().all(|c| c.passed));
+        assert!(report.verdict.starts_with("Attached to 'Star Citizen'"));
+    }
+
+    #[test]
+    fn test_diagnose_class_mismatch() {
+        let system = MockWindowSystem::new();
+        system.add_window("notepad.exe", "Notepad", "Untitled", rect());
+        system.add_window("StarCitizen.exe", "UnityWndClass", "Star Citizen", rect());
+
+        let report = diagnose_detection(&detect_target(
+            &system,
+            &[profile("LIVE", "StarCitizen.exe")],
+        ));
+
+        assert!(!report.success);
+        assert_eq!(
+            report.verdict,
+            "process found, class mismatch: expected CryENGINE, got UnityWndClass"
+        );
+        let checks = &report.candidates[0].checks;
+        assert!(checks[0].passed && !checks[1].passed && checks[2].passed);
+    }
+
+    #[test]
+    fn test_diagnose_match_not_selected() {
+        let system = MockWindowSystem::new();
+        system.add_game_window("StarCitizen.exe");
+
+        let mut result = detect_target(&system, &[profile("LIVE", "StarCitizen.exe")]);
+        result.success = false;
+        result.matched_window = None;
+        result.matched_profile = None;
+        let report = diagnose_detection(&result);
+
+        assert_eq!(
+            report.verdict,
+            "process found, window matches every criterion but was not selected"
+        );
+    }
+
+    #[test]
+    fn test_diagnose_process_not_found() {
+        let system = MockWindowSystem::new();
+        system.add_window(
+            "RSI Launcher.exe",
+            "Chrome_WidgetWin_1",
+            "RSI Launcher",
+            rect(),
+        );
+
+        let report = diagnose_detection(&detect_target(
+            &system,
+            &[
+                profile("LIVE", "StarCitizen.exe"),
+                profile("PTU", "StarCitizen_PTU.exe"),
+            ],
+        ));
+
+        assert_eq!(
+            report.verdict,
+            "Process not found: no window belongs to StarCitizen.exe or StarCitizen_PTU.exe (1 windows checked)"
+        );
+        assert_eq!(report.criteria.len(), 2);
+        assert_eq!(report.candidates.len(), 1);
+    }
+}
@@ -5,7 +5,7 @@
 // Note: This module is cfg(windows) in lib.rs, no need for #![cfg(windows)] here
 
 use std::ffi::c_void;
-use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicU32, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicU32, Ordering};
 use std::sync::Mutex;
 use std::thread;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -15,35 +15,16 @@ use windows::Win32::Foundation::{GetLastError, LPARAM, LRESULT, WPARAM};
 use windows::Win32::UI::WindowsAndMessaging::{
     CallNextHookEx, DispatchMessageW, GetMessageW, PostThreadMessageW, SetWindowsHookExW,
     TranslateMessage, UnhookWindowsHookEx, HC_ACTION, HHOOK, KBDLLHOOKSTRUCT, MSG, WH_KEYBOARD_LL,
-    WM_KEYDOWN, WM_QUIT, WM_SYSKEYDOWN,
+    WM_KEYDOWN, WM_KEYUP, WM_QUIT, WM_SYSKEYDOWN, WM_SYSKEYUP,
 };
 
-// T006: Virtual key code constants
-/// F3 key - toggle visibility
-pub const VK_F3: u32 = 0x72;
-/// F5 key - toggle mode
-pub const VK_F5: u32 = 0x74;
-
-// T006: Debounce constant
-/// Minimum time between hotkey triggers (milliseconds)
-pub const DEBOUNCE_MS: u64 = 200;
-
-// T005: HotkeyAction enum representing actions for detected hotkeys
-// T006 (045): Extended with ChronometerStartPause and ChronometerReset
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum HotkeyAction {
-    /// F3 pressed - toggle overlay visibility
-    ToggleVisibility,
-    /// F5 pressed - toggle overlay mode (click-through/interactive)
-    ToggleMode,
-    /// Ctrl+T pressed - toggle chronometer start/pause
-    ChronometerStartPause,
-    /// Ctrl+Y pressed - reset chronometer
-    ChronometerReset,
-}
+// T005: Hotkey actions come from the action registry, matching and debouncing from the matcher
+use crate::hotkey::matcher::{self, Dispatch, KeyPress, MatchState};
+use crate::hotkey::registry::{self, HotkeyAction};
+use crate::settings::types::HotkeySettings;
 
 /// Type alias for the event emitter function
-type EventEmitter = Box<dyn Fn(HotkeyAction) + Send + Sync>;
+type EventEmitter = Box<dyn Fn(&HotkeyAction) + Send + Sync>;
 
 // T004: KeyboardHookState struct with atomic fields
 /// Represents the state of the low-level keyboard hook system
@@ -56,14 +37,8 @@ pub struct KeyboardHookState {
     thread_id: AtomicU32,
     /// Whether fallback to global shortcut is active
     use_fallback: AtomicBool,
-    /// Last F3 press timestamp for debouncing
-    last_f3_press: AtomicU64,
-    /// Last F5 press timestamp for debouncing
-    last_f5_press: AtomicU64,
-    /// Last chronometer toggle (Ctrl+T) press timestamp for debouncing
-    last_chrono_toggle: AtomicU64,
-    /// Last chronometer reset (Ctrl+Y) press timestamp for debouncing
-    last_chrono_reset: AtomicU64,
+    /// Debounce, sequence, hold and double-tap state
+    matcher: Mutex<MatchState>,
     /// App handle for event emission (protected by mutex)
     app_handle: Mutex<Option<EventEmitter>>,
 }
@@ -75,16 +50,13 @@ impl Default for KeyboardHookState {
 }
 
 impl KeyboardHookState {
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             is_active: AtomicBool::new(false),
             hook_handle: AtomicPtr::new(std::ptr::null_mut()),
             thread_id: AtomicU32::new(0),
             use_fallback: AtomicBool::new(false),
-            last_f3_press: AtomicU64::new(0),
-            last_f5_press: AtomicU64::new(0),
-            last_chrono_toggle: AtomicU64::new(0),
-            last_chrono_reset: AtomicU64::new(0),
+            matcher: Mutex::new(MatchState::default()),
             app_handle: Mutex::new(None),
         }
     }
@@ -121,41 +93,61 @@ impl KeyboardHookState {
         self.use_fallback.store(value, Ordering::SeqCst);
     }
 
-    pub fn get_last_f3_press(&self) -> u64 {
-        self.last_f3_press.load(Ordering::SeqCst)
-    }
-
-    pub fn set_last_f3_press(&self, timestamp: u64) {
-        self.last_f3_press.store(timestamp, Ordering::SeqCst);
+    /// Last trigger timestamp of an action (0 if never triggered)
+    pub fn get_last_fired(&self, action_id: &str) -> u64 {
+        match self.matcher.lock() {
+            Ok(state) => state.last_fired(action_id),
+            Err(poisoned) => poisoned.into_inner().last_fired(action_id),
+        }
     }
 
-    pub fn get_last_f5_press(&self) -> u64 {
-        self.last_f5_press.load(Ordering::SeqCst)
+    /// Match a key press against the registered actions (single dispatch path)
+    pub fn dispatch_key(
+        &self,
+        press: &KeyPress,
+        hotkeys: &HotkeySettings,
+        now: u64,
+    ) -> Vec<Dispatch> {
+        let mut state = match self.matcher.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        matcher::dispatch(
+            &registry::registered_actions(),
+            hotkeys,
+            press,
+            now,
+            &mut state,
+        )
     }
 
-    pub fn set_last_f5_press(&self, timestamp: u64) {
-        self.last_f5_press.store(timestamp, Ordering::SeqCst);
-    }
-    pub fn get_last_chrono_toggle(&self) -> u64 {
-        self.last_chrono_toggle.load(Ordering::SeqCst)
-    }
-    pub fn set_last_chrono_toggle(&self, timestamp: u64) {
-        self.last_chrono_toggle.store(timestamp, Ordering::SeqCst);
-    }
-    pub fn get_last_chrono_reset(&self) -> u64 {
-        self.last_chrono_reset.load(Ordering::SeqCst)
-    }
-    pub fn set_last_chrono_reset(&self, timestamp: u64) {
-        self.last_chrono_reset.store(timestamp, Ordering::SeqCst);
+    /// Match a key release (completes hold bindings)
+    pub fn release_key(
+        &self,
+        press: &KeyPress,
+        hotkeys: &HotkeySettings,
+        now: u64,
+    ) -> Vec<Dispatch> {
+        let mut state = match self.matcher.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        matcher::dispatch_release(
+            &registry::registered_actions(),
+            hotkeys,
+            press,
+            now,
+            &mut state,
+        )
     }
 
-    pub fn set_event_emitter(&self, emitter: Box<dyn Fn(HotkeyAction) + Send + Sync>) {
+    pub fn set_event_emitter(&self, emitter: EventEmitter) {
         if let Ok(mut guard) = self.app_handle.lock() {
             *guard = Some(emitter);
         }
     }
 
-    pub fn emit_action(&self, action: HotkeyAction) {
+    pub fn emit_action(&self, action: &HotkeyAction) {
         if let Ok(guard) = self.app_handle.lock() {
             if let Some(ref emitter) = *guard {
                 emitter(action);
@@ -202,8 +194,11 @@ unsafe extern "system" fn keyboard_hook_callback(
     if n_code == HC_ACTION as i32 {
         let msg_type = w_param.0 as u32;
 
-        // Only process key down events (not key up)
-        if msg_type == WM_KEYDOWN || msg_type == WM_SYSKEYDOWN {
+        let key_down = msg_type == WM_KEYDOWN || msg_type == WM_SYSKEYDOWN;
+        let key_up = msg_type == WM_KEYUP || msg_type == WM_SYSKEYUP;
+
+        // Key-down triggers most bindings, key-up completes hold bindings
+        if key_down || key_up {
             // Get keyboard event data
             let kb_struct = &*(l_param.0 as *const KBDLLHOOKSTRUCT);
             let vk_code = kb_struct.vkCode;
@@ -212,104 +207,49 @@ unsafe extern "system" fn keyboard_hook_callback(
             // T025 (038): Get configured hotkeys from user settings
             let hotkeys = crate::settings::user::get_hotkey_settings();
             let (ctrl, shift, alt) = check_modifiers();
-
-            // T025 (038): Check for toggle_visibility hotkey with modifiers
-            let vis = &hotkeys.toggle_visibility;
-            if vk_code == vis.key_code && ctrl == vis.ctrl && shift == vis.shift && alt == vis.alt {
-                let last_press = KEYBOARD_HOOK_STATE.get_last_f3_press();
-                if now - last_press >= DEBOUNCE_MS {
-                    KEYBOARD_HOOK_STATE.set_last_f3_press(now);
-                    log::info!(
-                        "{} pressed: toggle visibility requested (low-level hook, timestamp={})",
-                        vis.key,
-                        now
-                    );
-                    KEYBOARD_HOOK_STATE.emit_action(HotkeyAction::ToggleVisibility);
-                } else {
-                    log::debug!(
-                        "{} pressed: debounced ({}ms since last, threshold={}ms)",
-                        vis.key,
-                        now - last_press,
-                        DEBOUNCE_MS
-                    );
-                }
-            }
-
-            // T025 (038): Check for toggle_mode hotkey with modifiers
-            let mode = &hotkeys.toggle_mode;
-            if vk_code == mode.key_code
-                && ctrl == mode.ctrl
-                && shift == mode.shift
-                && alt == mode.alt
-            {
-                let last_press = KEYBOARD_HOOK_STATE.get_last_f5_press();
-                if now - last_press >= DEBOUNCE_MS {
-                    KEYBOARD_HOOK_STATE.set_last_f5_press(now);
-                    log::info!(
-                        "{} pressed: toggle mode requested (low-level hook, timestamp={})",
-                        mode.key,
-                        now
-                    );
-                    KEYBOARD_HOOK_STATE.emit_action(HotkeyAction::ToggleMode);
-                } else {
-                    log::debug!(
-                        "{} pressed: debounced ({}ms since last, threshold={}ms)",
-                        mode.key,
-                        now - last_press,
-                        DEBOUNCE_MS
-                    );
-                }
-            }
-
-            // T008 (045): Check for chronometer_start_pause hotkey with modifiers
-            let chrono_toggle = &hotkeys.chronometer_start_pause;
-            if vk_code == chrono_toggle.key_code
-                && ctrl == chrono_toggle.ctrl
-                && shift == chrono_toggle.shift
-                && alt == chrono_toggle.alt
-            {
-                let last_press = KEYBOARD_HOOK_STATE.get_last_chrono_toggle();
-                if now - last_press >= DEBOUNCE_MS {
-                    KEYBOARD_HOOK_STATE.set_last_chrono_toggle(now);
-                    log::info!(
-                        "Ctrl+{} pressed: chronometer toggle (low-level hook, timestamp={})",
-                        chrono_toggle.key,
-                        now
-                    );
-                    KEYBOARD_HOOK_STATE.emit_action(HotkeyAction::ChronometerStartPause);
-                } else {
-                    log::debug!(
-                        "Ctrl+{} pressed: debounced ({}ms since last, threshold={}ms)",
-                        chrono_toggle.key,
-                        now - last_press,
-                        DEBOUNCE_MS
-                    );
-                }
-            }
-
-            // T009 (045): Check for chronometer_reset hotkey with modifiers
-            let chrono_reset = &hotkeys.chronometer_reset;
-            if vk_code == chrono_reset.key_code
-                && ctrl == chrono_reset.ctrl
-                && shift == chrono_reset.shift
-                && alt == chrono_reset.alt
-            {
-                let last_press = KEYBOARD_HOOK_STATE.get_last_chrono_reset();
-                if now - last_press >= DEBOUNCE_MS {
-                    KEYBOARD_HOOK_STATE.set_last_chrono_reset(now);
-                    log::info!(
-                        "Ctrl+{} pressed: chronometer reset (low-level hook, timestamp={})",
-                        chrono_reset.key,
-                        now
-                    );
-                    KEYBOARD_HOOK_STATE.emit_action(HotkeyAction::ChronometerReset);
-                } else {
-                    log::debug!(
-                        "Ctrl+{} pressed: debounced ({}ms since last, threshold={}ms)",
-                        chrono_reset.key,
-                        now - last_press,
-                        DEBOUNCE_MS
-                    );
+            let press = KeyPress {
+                key_code: vk_code,
+                ctrl,
+                shift,
+                alt,
+            };
+
+            // T025 (038): Dispatch the event to every action bound to it
+            let dispatches = if key_down {
+                KEYBOARD_HOOK_STATE.dispatch_key(&press, &hotkeys, now)
+            } else {
+                KEYBOARD_HOOK_STATE.release_key(&press, &hotkeys, now)
+            };
+            for dispatch in dispatches {
+                match dispatch {
+                    Dispatch::Fire { action, binding } => {
+                        log::info!(
+                            "{} pressed: {} requested (low-level hook, timestamp={})",
+                            binding.label(),
+                            action.description,
+                            now
+                        );
+                        KEYBOARD_HOOK_STATE.emit_action(&action);
+                    }
+                    Dispatch::Debounced {
+                        action,
+                        binding,
+                        elapsed_ms,
+                    } => {
+                        log::debug!(
+                            "{} pressed: debounced ({}ms since last, threshold={}ms)",
+                            binding.label(),
+                            elapsed_ms,
+                            action.debounce_ms
+                        );
+                    }
+                    Dispatch::Pending { action, binding } => {
+                        log::debug!(
+                            "{} started: waiting for the second key ({})",
+                            binding.label(),
+                            action.description
+                        );
+                    }
                 }
             }
         }
@@ -340,15 +280,9 @@ pub fn start_keyboard_hook<R: Runtime>(app_handle: AppHandle<R>) -> bool {
 
     // T016: Store AppHandle in thread-safe global state for event emission
     let app_handle_clone = app_handle.clone();
-    KEYBOARD_HOOK_STATE.set_event_emitter(Box::new(move |action| {
-        let event_name = match action {
-            HotkeyAction::ToggleVisibility => "toggle-visibility",
-            HotkeyAction::ToggleMode => "toggle-mode",
-            HotkeyAction::ChronometerStartPause => "chronometer-start-pause",
-            HotkeyAction::ChronometerReset => "chronometer-reset",
-        };
-        if let Err(e) = app_handle_clone.emit(event_name, ()) {
-            log::error!("Failed to emit {} event: {}", event_name, e);
+    KEYBOARD_HOOK_STATE.set_event_emitter(Box::new(move |action: &HotkeyAction| {
+        if let Err(e) = app_handle_clone.emit(&action.event, ()) {
+            log::error!("Failed to emit {} event: {}", action.event, e);
         }
     }));
 
@@ -495,41 +429,28 @@ mod tests {
 
     #[test]
     fn test_debounce_logic() {
-        // Reset state for test
-        KEYBOARD_HOOK_STATE.set_last_f3_press(0);
-
-        let now = current_time_ms();
+        let state = KeyboardHookState::new();
+        let hotkeys = HotkeySettings::default();
+        let f3 = KeyPress {
+            key_code: 0x72,
+            ctrl: false,
+            shift: false,
+            alt: false,
+        };
 
         // First press should be allowed
-        let last = KEYBOARD_HOOK_STATE.get_last_f3_press();
-        assert!(now - last >= DEBOUNCE_MS);
-        KEYBOARD_HOOK_STATE.set_last_f3_press(now);
+        let now = current_time_ms();
+        let first = state.dispatch_key(&f3, &hotkeys, now);
+        assert!(matches!(first.as_slice(), [Dispatch::Fire { .. }]));
+        assert_eq!(state.get_last_fired(registry::TOGGLE_VISIBILITY), now);
 
         // Immediate second press should be debounced
-        let now2 = current_time_ms();
-        let last2 = KEYBOARD_HOOK_STATE.get_last_f3_press();
-        assert!(now2 - last2 < DEBOUNCE_MS);
-
-        // After waiting, press should be allowed again
-        std::thread::sleep(std::time::Duration::from_millis(DEBOUNCE_MS + 10));
-        let now3 = current_time_ms();
-        let last3 = KEYBOARD_HOOK_STATE.get_last_f3_press();
-        assert!(now3 - last3 >= DEBOUNCE_MS);
-    }
+        let second = state.dispatch_key(&f3, &hotkeys, now + 10);
+        assert!(matches!(second.as_slice(), [Dispatch::Debounced { .. }]));
 
-    #[test]
-    fn test_hotkey_action_enum() {
-        let visibility = HotkeyAction::ToggleVisibility;
-        let mode = HotkeyAction::ToggleMode;
-        let chrono_toggle = HotkeyAction::ChronometerStartPause;
-        let chrono_reset = HotkeyAction::ChronometerReset;
-
-        assert_ne!(visibility, mode);
-        assert_ne!(chrono_toggle, chrono_reset);
-        assert_eq!(visibility, HotkeyAction::ToggleVisibility);
-        assert_eq!(mode, HotkeyAction::ToggleMode);
-        assert_eq!(chrono_toggle, HotkeyAction::ChronometerStartPause);
-        assert_eq!(chrono_reset, HotkeyAction::ChronometerReset);
+        // After the debounce window, press should be allowed again
+        let third = state.dispatch_key(&f3, &hotkeys, now + registry::DEFAULT_DEBOUNCE_MS + 10);
+        assert!(matches!(third.as_slice(), [Dispatch::Fire { .. }]));
     }
 
     #[test]
@@ -541,9 +462,7 @@ mod tests {
         assert!(state.get_hook_handle().is_null());
         assert_eq!(state.get_thread_id(), 0);
         assert!(!state.is_using_fallback());
-        assert_eq!(state.get_last_f3_press(), 0);
-        assert_eq!(state.get_last_f5_press(), 0);
-        assert_eq!(state.get_last_chrono_toggle(), 0);
-        assert_eq!(state.get_last_chrono_reset(), 0);
+        assert_eq!(state.get_last_fired(registry::TOGGLE_VISIBILITY), 0);
+        assert_eq!(state.get_last_fired(registry::CHRONOMETER_RESET), 0);
     }
 }
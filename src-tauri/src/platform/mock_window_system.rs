@@ -0,0 +1,150 @@
+// Scriptable in-memory WindowSystem
+// Tests add, close, retitle and focus windows to drive the detection, process
+// monitor and auto-hide flows without a real window manager.
+
+use std::sync::Mutex;
+
+use super::window_system::WindowSystem;
+use crate::core::types::{WindowCandidate, WindowRect};
+
+#[derive(Debug, Clone)]
+struct MockWindow {
+    candidate: WindowCandidate,
+    rect: WindowRect,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    windows: Vec<MockWindow>,
+    foreground: Option<u64>,
+    next_hwnd: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct MockWindowSystem {
+    state: Mutex<MockState>,
+}
+
+impl MockWindowSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    // Add a top-level window and return its handle
+    pub fn add_window(
+        &self,
+        process_name: &str,
+        window_class: &str,
+        window_title: &str,
+        rect: WindowRect,
+    ) -> u64 {
+        let mut state = self.lock();
+        state.next_hwnd += 1;
+        let hwnd = state.next_hwnd;
+        state.windows.push(MockWindow {
+            candidate: WindowCandidate {
+                hwnd,
+                process_name: process_name.to_string(),
+                window_class: window_class.to_string(),
+                window_title: window_title.to_string(),
+                is_top_level: true,
+            },
+            rect,
+        });
+        hwnd
+    }
+
+    // Add a 1920x1080 "Star Citizen" CryENGINE window owned by `process_name`
+    pub fn add_game_window(&self, process_name: &str) -> u64 {
+        self.add_window(
+            process_name,
+            "CryENGINE",
+            "Star Citizen",
+            WindowRect {
+                x: 0,
+                y: 0,
+                width: 1920,
+                height: 1080,
+            },
+        )
+    }
+
+    // Close a window; it loses focus if it had it
+    pub fn close_window(&self, hwnd: u64) {
+        let mut state = self.lock();
+        state.windows.retain(|w| w.candidate.hwnd != hwnd);
+        if state.foreground == Some(hwnd) {
+            state.foreground = None;
+        }
+    }
+
+    // Give focus to a window (None = desktop / no window)
+    pub fn set_foreground(&self, hwnd: Option<u64>) {
+        self.lock().foreground = hwnd;
+    }
+
+    pub fn set_rect(&self, hwnd: u64, rect: WindowRect) {
+        if let Some(window) = self
+            .lock()
+            .windows
+            .iter_mut()
+            .find(|w| w.candidate.hwnd == hwnd)
+        {
+            window.rect = rect;
+        }
+    }
+
+    pub fn set_title(&self, hwnd: u64, title: &str) {
+        if let Some(window) = self
+            .lock()
+            .windows
+            .iter_mut()
+            .find(|w| w.candidate.hwnd == hwnd)
+        {
+            window.candidate.window_title = title.to_string();
+        }
+    }
+
+    fn with_window<T>(&self, hwnd: u64, f: impl FnOnce(&MockWindow) -> T) -> Option<T> {
+        self.lock()
+            .windows
+            .iter()
+            .find(|w| w.candidate.hwnd == hwnd)
+            .map(f)
+    }
+}
+
+impl WindowSystem for MockWindowSystem {
+    fn enumerate_windows(&self) -> Vec<WindowCandidate> {
+        self.lock()
+            .windows
+            .iter()
+            .filter(|w| !w.candidate.window_title.is_empty())
+            .map(|w| w.candidate.clone())
+            .collect()
+    }
+
+    fn foreground_window(&self) -> Option<u64> {
+        self.lock().foreground
+    }
+
+    fn window_rect(&self, hwnd: u64) -> Option<WindowRect> {
+        self.with_window(hwnd, |w| w.rect)
+    }
+
+    fn is_window_valid(&self, hwnd: u64) -> bool {
+        self.with_window(hwnd, |_| ()).is_some()
+    }
+
+    fn process_name(&self, hwnd: u64) -> Option<String> {
+        self.with_window(hwnd, |w| w.candidate.process_name.clone())
+    }
+
+    fn window_title(&self, hwnd: u64) -> Option<String> {
+        self.with_window(hwnd, |w| w.candidate.window_title.clone())
+    }
+}
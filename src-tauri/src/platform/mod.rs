@@ -1,23 +1,30 @@
 // Platform module - Windows-specific functionality
+// Detection and monitoring go through the WindowSystem trait (window_system.rs),
+// implemented for Win32, for X11 on Linux (Wine/Proton) and by an in-memory mock
+// used in tests
 
 #[cfg(windows)]
 pub mod keyboard_hook;
 #[cfg(windows)]
 pub mod target_window;
 #[cfg(windows)]
+pub mod win32_window_system;
+#[cfg(target_os = "linux")]
+pub mod x11_window_system;
 pub mod process_monitor;
-#[cfg(windows)]
 pub mod focus_monitor;
 
+pub mod detection_diagnostics;
+pub mod mock_window_system;
 pub mod tray;
+pub mod window_match;
+pub mod window_system;
 
 // Re-exports for public API
 #[cfg(windows)]
 pub use keyboard_hook::*;
 #[cfg(windows)]
 pub use target_window::*;
-#[cfg(windows)]
 pub use process_monitor::*;
-#[cfg(windows)]
 pub use focus_monitor::*;
 pub use tray::*;
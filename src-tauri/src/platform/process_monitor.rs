@@ -7,8 +7,10 @@ use std::thread;
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Runtime};
 
+use crate::core::types::SearchCriteria;
 use crate::settings;
-use super::target_window;
+use super::window_match::{get_target_criteria, running_profile};
+use super::window_system::{detect_target, is_window_focused, window_system, WindowSystem};
 
 // T039: ProcessMonitorState struct
 pub struct ProcessMonitorState {
@@ -22,8 +24,10 @@ pub struct ProcessMonitorState {
     last_check_time: Mutex<Instant>,
     /// User manually hid the overlay (don't auto-show until new game session)
     user_manually_hidden: AtomicBool,
-    /// Polling interval in milliseconds
-    polling_interval_ms: u64,
+    /// Polling interval in milliseconds (updated when settings.toml is reloaded)
+    polling_interval_ms: AtomicU64,
+    /// Target detection criteria changed since the last check
+    criteria_changed: AtomicBool,
 }
 
 impl Default for ProcessMonitorState {
@@ -34,7 +38,8 @@ impl Default for ProcessMonitorState {
             target_hwnd: AtomicU64::new(0),
             last_check_time: Mutex::new(Instant::now()),
             user_manually_hidden: AtomicBool::new(false),
-            polling_interval_ms: settings::get_process_monitor_interval(),
+            polling_interval_ms: AtomicU64::new(settings::get_process_monitor_interval()),
+            criteria_changed: AtomicBool::new(false),
         }
     }
 }
@@ -78,6 +83,27 @@ impl ProcessMonitorState {
         self.user_manually_hidden.store(false, Ordering::SeqCst);
     }
 
+    pub fn get_polling_interval(&self) -> u64 {
+        self.polling_interval_ms.load(Ordering::SeqCst)
+    }
+
+    // Apply a new polling interval from reloaded settings (takes effect after the current sleep)
+    pub fn set_polling_interval(&self, interval_ms: u64) {
+        log::info!("Process monitor interval changed to {}ms", interval_ms);
+        self.polling_interval_ms.store(interval_ms, Ordering::SeqCst);
+    }
+
+    // Target detection criteria changed: forget the current target so the
+    // monitor re-detects it with the new criteria on its next check
+    pub fn invalidate_target(&self) {
+        self.set_target_hwnd(0);
+        self.criteria_changed.store(true, Ordering::SeqCst);
+    }
+
+    pub fn take_criteria_changed(&self) -> bool {
+        self.criteria_changed.swap(false, Ordering::SeqCst)
+    }
+
     pub fn update_last_check(&self) {
         if let Ok(mut time) = self.last_check_time.lock() {
             *time = Instant::now();
@@ -90,32 +116,177 @@ lazy_static::lazy_static! {
     pub static ref PROCESS_MONITOR_STATE: ProcessMonitorState = ProcessMonitorState::default();
 }
 
-// T040: Check if target process is running by looking for matching windows
-#[cfg(windows)]
-pub fn is_process_running() -> bool {
-    // Use the existing detection to check if process is running
-    let result = target_window::find_target_window_verified();
-
-    // Process is running if we found any candidates with matching process name
-    let target_process = settings::get_target_process_name().to_lowercase();
-    result
-        .candidates_evaluated
-        .iter()
-        .any(|c| c.process_name.to_lowercase() == target_process)
+// T040: Find the highest-priority target profile whose process is running,
+// by looking for windows owned by its process
+pub fn detect_running_profile(
+    system: &dyn WindowSystem,
+    profiles: &[SearchCriteria],
+) -> Option<SearchCriteria> {
+    running_profile(&system.enumerate_windows(), profiles).cloned()
 }
 
-#[cfg(not(windows))]
+// T040: Check if any target process is running
 pub fn is_process_running() -> bool {
-    false
+    detect_running_profile(&*window_system(), &get_target_criteria()).is_some()
 }
 
 // Event payload for process events
-#[derive(Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct ProcessEventPayload {
     pub process_name: String,
+    /// Target profile the process belongs to (e.g., "LIVE", "PTU")
+    pub profile_name: String,
     pub detected: bool,
 }
 
+impl ProcessEventPayload {
+    fn new(criteria: &SearchCriteria, detected: bool) -> Self {
+        Self {
+            process_name: criteria.process_name.clone(),
+            profile_name: criteria.profile_name.clone(),
+            detected,
+        }
+    }
+}
+
+// A change in which target profile's process is running
+#[derive(Debug, Clone)]
+pub enum ProcessTransition {
+    Terminated(SearchCriteria),
+    Detected(SearchCriteria),
+}
+
+// Tracks the running target profile between checks
+#[derive(Debug, Default)]
+pub struct ProcessTracker {
+    running: Option<SearchCriteria>,
+}
+
+impl ProcessTracker {
+    pub fn running(&self) -> Option<&SearchCriteria> {
+        self.running.as_ref()
+    }
+
+    // Forget the running profile (detection criteria changed)
+    pub fn reset(&mut self) -> Option<ProcessTransition> {
+        self.running.take().map(ProcessTransition::Terminated)
+    }
+
+    // T043, T044: Record the result of a check and return the transitions in order.
+    // A different profile taking over (e.g. LIVE closed and PTU launched between two
+    // checks) ends the previous session before the new one starts.
+    pub fn update(&mut self, detected: Option<SearchCriteria>) -> Vec<ProcessTransition> {
+        let unchanged = match (&self.running, &detected) {
+            (Some(previous), Some(current)) => previous.profile_name == current.profile_name,
+            (None, None) => true,
+            _ => false,
+        };
+
+        let mut transitions = Vec::new();
+        if !unchanged {
+            if let Some(previous) = self.running.take() {
+                transitions.push(ProcessTransition::Terminated(previous));
+            }
+            if let Some(current) = &detected {
+                transitions.push(ProcessTransition::Detected(current.clone()));
+            }
+        }
+        self.running = detected;
+        transitions
+    }
+}
+
+// Events produced by one monitor check, in the order they are emitted
+#[derive(Debug, Clone)]
+pub enum MonitorEvent {
+    ProcessDetected(ProcessEventPayload),
+    ProcessTerminated(ProcessEventPayload),
+    AutoShow,
+}
+
+// T043-T051: Run one check of the process monitor, updating `state` and returning
+// the events to emit. Independent of Tauri so it can be driven by a mock window system.
+pub fn check_processes(
+    system: &dyn WindowSystem,
+    profiles: &[SearchCriteria],
+    state: &ProcessMonitorState,
+    tracker: &mut ProcessTracker,
+) -> Vec<MonitorEvent> {
+    let mut events = Vec::new();
+
+    // Settings were reloaded with new detection criteria: end the session
+    // for the old target so the new one is detected from scratch
+    if state.take_criteria_changed() {
+        if let Some(ProcessTransition::Terminated(previous)) = tracker.reset() {
+            state.set_target_found(false);
+            events.push(MonitorEvent::ProcessTerminated(ProcessEventPayload::new(
+                &previous, false,
+            )));
+        }
+        log::info!("Process monitor now targeting {}", describe_profiles(profiles));
+    }
+
+    let detected = detect_running_profile(system, profiles);
+
+    for transition in tracker.update(detected) {
+        match transition {
+            ProcessTransition::Terminated(previous) => {
+                // Process just stopped
+                log::info!(
+                    "Target process terminated: {} (profile {})",
+                    previous.process_name,
+                    previous.profile_name
+                );
+
+                state.set_target_found(false);
+                state.set_target_hwnd(0);
+
+                // T046: Emit process terminated event
+                events.push(MonitorEvent::ProcessTerminated(ProcessEventPayload::new(
+                    &previous, false,
+                )));
+            }
+            ProcessTransition::Detected(current) => {
+                // Process just started
+                log::info!(
+                    "Target process detected: {} (profile {})",
+                    current.process_name,
+                    current.profile_name
+                );
+
+                // T051: Reset user_manually_hidden for new session
+                state.reset_for_new_session();
+                state.set_target_found(true);
+
+                // T045: Emit process detected event
+                events.push(MonitorEvent::ProcessDetected(ProcessEventPayload::new(
+                    &current, true,
+                )));
+
+                // T049: Auto-show overlay if window is focused and not manually hidden
+                let detection = detect_target(system, profiles);
+                if let Some(matched) = detection.matched_window {
+                    state.set_target_hwnd(matched.hwnd);
+
+                    if is_window_focused(system, matched.hwnd) {
+                        if !state.is_user_manually_hidden() {
+                            log::info!("Target focused on launch - emitting auto-show event");
+                            events.push(MonitorEvent::AutoShow);
+                        } else {
+                            log::debug!(
+                                "Target focused but user manually hidden - not auto-showing"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    state.update_last_check();
+    events
+}
+
 // T041, T042: Start process monitor thread
 pub fn start_process_monitor<R: Runtime>(app_handle: AppHandle<R>) {
     // Don't start if already monitoring
@@ -125,17 +296,16 @@ pub fn start_process_monitor<R: Runtime>(app_handle: AppHandle<R>) {
     }
 
     PROCESS_MONITOR_STATE.set_monitoring(true);
-    let interval = PROCESS_MONITOR_STATE.polling_interval_ms;
-    let target_process = settings::get_target_process_name().to_string();
+    let interval = PROCESS_MONITOR_STATE.get_polling_interval();
 
     log::info!(
-        "Starting process monitor for '{}' with {}ms interval",
-        target_process,
+        "Starting process monitor for {} with {}ms interval",
+        describe_profiles(&get_target_criteria()),
         interval
     );
 
     thread::spawn(move || {
-        let mut was_running = false;
+        let mut tracker = ProcessTracker::default();
 
         loop {
             // Check if we should stop monitoring
@@ -144,71 +314,154 @@ pub fn start_process_monitor<R: Runtime>(app_handle: AppHandle<R>) {
                 break;
             }
 
-            let is_running = is_process_running();
-
-            // T043, T044, T045, T046: Detect state changes
-            if is_running && !was_running {
-                // Process just started
-                log::info!("Target process detected: {}", target_process);
-
-                // T051: Reset user_manually_hidden for new session
-                PROCESS_MONITOR_STATE.reset_for_new_session();
-                PROCESS_MONITOR_STATE.set_target_found(true);
-
-                // T045: Emit process detected event
-                let payload = ProcessEventPayload {
-                    process_name: target_process.clone(),
-                    detected: true,
-                };
-                let _ = app_handle.emit("target-process-detected", payload);
-
-                // T049: Auto-show overlay if window is focused and not manually hidden
-                #[cfg(windows)]
-                {
-                    let detection = target_window::find_target_window_verified();
-                    if detection.success {
-                        if let Some(ref matched) = detection.matched_window {
-                            let hwnd = target_window::u64_to_hwnd(matched.hwnd);
-                            PROCESS_MONITOR_STATE.set_target_hwnd(matched.hwnd);
-
-                            if target_window::is_target_focused(hwnd) {
-                                if !PROCESS_MONITOR_STATE.is_user_manually_hidden() {
-                                    log::info!(
-                                        "Target focused on launch - emitting auto-show event"
-                                    );
-                                    let _ = app_handle.emit("auto-show-overlay", ());
-                                } else {
-                                    log::debug!("Target focused but user manually hidden - not auto-showing");
-                                }
-                            }
-                        }
+            // Re-read the profiles every check so reloaded settings apply
+            let events = check_processes(
+                &*window_system(),
+                &get_target_criteria(),
+                &PROCESS_MONITOR_STATE,
+                &mut tracker,
+            );
+
+            for event in events {
+                match event {
+                    MonitorEvent::ProcessDetected(payload) => {
+                        let _ = app_handle.emit("target-process-detected", payload);
+                    }
+                    MonitorEvent::ProcessTerminated(payload) => {
+                        let _ = app_handle.emit("target-process-terminated", payload);
+                    }
+                    MonitorEvent::AutoShow => {
+                        let _ = app_handle.emit("auto-show-overlay", ());
                     }
                 }
-            } else if !is_running && was_running {
-                // Process just stopped
-                log::info!("Target process terminated");
-
-                PROCESS_MONITOR_STATE.set_target_found(false);
-                PROCESS_MONITOR_STATE.set_target_hwnd(0);
-
-                // T046: Emit process terminated event
-                let payload = ProcessEventPayload {
-                    process_name: target_process.clone(),
-                    detected: false,
-                };
-                let _ = app_handle.emit("target-process-terminated", payload);
             }
 
-            was_running = is_running;
-            PROCESS_MONITOR_STATE.update_last_check();
-
-            // Sleep for polling interval
-            thread::sleep(Duration::from_millis(interval));
+            // Sleep for polling interval (re-read so reloaded settings apply)
+            thread::sleep(Duration::from_millis(PROCESS_MONITOR_STATE.get_polling_interval()));
         }
     });
 }
 
+// Describe the target profiles for logging, e.g. "'StarCitizen.exe' (LIVE)"
+fn describe_profiles(profiles: &[SearchCriteria]) -> String {
+    profiles
+        .iter()
+        .map(|p| format!("'{}' ({})", p.process_name, p.profile_name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 // Stop the process monitor
 pub fn stop_process_monitor() {
     PROCESS_MONITOR_STATE.set_monitoring(false);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock_window_system::MockWindowSystem;
+    use super::*;
+
+    fn profile(name: &str, process: &str) -> SearchCriteria {
+        SearchCriteria {
+            profile_name: name.to_string(),
+            process_name: process.to_string(),
+            window_class: "CryENGINE".to_string(),
+            window_title: "Star Citizen".to_string(),
+        }
+    }
+
+    fn profiles() -> Vec<SearchCriteria> {
+        vec![
+            profile("LIVE", "StarCitizen.exe"),
+            profile("PTU", "StarCitizen_PTU.exe"),
+        ]
+    }
+
+    // Summarize events as "detected:LIVE", "terminated:PTU", "auto-show"
+    fn names(events: &[MonitorEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| match event {
+                MonitorEvent::ProcessDetected(p) => format!("detected:{}", p.profile_name),
+                MonitorEvent::ProcessTerminated(p) => format!("terminated:{}", p.profile_name),
+                MonitorEvent::AutoShow => "auto-show".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_launch_and_exit_flow() {
+        let system = MockWindowSystem::new();
+        let state = ProcessMonitorState::default();
+        let mut tracker = ProcessTracker::default();
+        let profiles = profiles();
+
+        assert!(check_processes(&system, &profiles, &state, &mut tracker).is_empty());
+
+        // Game launches in the background: detected, but not auto-shown
+        let game = system.add_game_window("StarCitizen.exe");
+        let events = check_processes(&system, &profiles, &state, &mut tracker);
+        assert_eq!(names(&events), vec!["detected:LIVE"]);
+        assert!(state.is_target_found());
+        assert_eq!(state.get_target_hwnd(), game);
+
+        // Still running: no new events
+        assert!(check_processes(&system, &profiles, &state, &mut tracker).is_empty());
+
+        system.close_window(game);
+        let events = check_processes(&system, &profiles, &state, &mut tracker);
+        assert_eq!(names(&events), vec!["terminated:LIVE"]);
+        assert!(!state.is_target_found());
+        assert_eq!(state.get_target_hwnd(), 0);
+    }
+
+    #[test]
+    fn test_focused_launch_auto_shows_and_resets_manual_hide() {
+        let system = MockWindowSystem::new();
+        let state = ProcessMonitorState::default();
+        let mut tracker = ProcessTracker::default();
+        state.set_user_manually_hidden(true);
+
+        let game = system.add_game_window("StarCitizen_PTU.exe");
+        system.set_foreground(Some(game));
+
+        let events = check_processes(&system, &profiles(), &state, &mut tracker);
+        assert_eq!(names(&events), vec!["detected:PTU", "auto-show"]);
+        assert!(!state.is_user_manually_hidden());
+    }
+
+    #[test]
+    fn test_profile_switch_between_checks() {
+        let system = MockWindowSystem::new();
+        let state = ProcessMonitorState::default();
+        let mut tracker = ProcessTracker::default();
+        let profiles = profiles();
+
+        let live = system.add_game_window("StarCitizen.exe");
+        check_processes(&system, &profiles, &state, &mut tracker);
+
+        system.close_window(live);
+        system.add_game_window("StarCitizen_PTU.exe");
+        let events = check_processes(&system, &profiles, &state, &mut tracker);
+        assert_eq!(names(&events), vec!["terminated:LIVE", "detected:PTU"]);
+        assert_eq!(
+            tracker.running().map(|p| p.profile_name.as_str()),
+            Some("PTU")
+        );
+    }
+
+    #[test]
+    fn test_criteria_change_restarts_session() {
+        let system = MockWindowSystem::new();
+        let state = ProcessMonitorState::default();
+        let mut tracker = ProcessTracker::default();
+        let profiles = profiles();
+
+        system.add_game_window("StarCitizen.exe");
+        check_processes(&system, &profiles, &state, &mut tracker);
+
+        state.invalidate_target();
+        let events = check_processes(&system, &profiles, &state, &mut tracker);
+        assert_eq!(names(&events), vec!["terminated:LIVE", "detected:LIVE"]);
+    }
+}
@@ -5,14 +5,13 @@
 #[cfg(windows)]
 use std::cell::Cell;
 #[cfg(windows)]
-use std::time::Instant;
-#[cfg(windows)]
 use windows::core::BOOL;
 #[cfg(windows)]
-use windows::Win32::Foundation::{CloseHandle, HWND, LPARAM};
+use windows::Win32::Foundation::{CloseHandle, FILETIME, HWND, LPARAM};
 #[cfg(windows)]
 use windows::Win32::System::Threading::{
-    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    GetProcessTimes, OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+    PROCESS_QUERY_LIMITED_INFORMATION,
 };
 #[cfg(windows)]
 use windows::Win32::UI::WindowsAndMessaging::{
@@ -20,16 +19,13 @@ use windows::Win32::UI::WindowsAndMessaging::{
     GetWindowThreadProcessId, IsWindow, GA_ROOT,
 };
 
-use crate::settings;
-use crate::core::types::{
-    DetectionResult, SearchCriteria, TargetWindowError, WindowCandidate, WindowRect,
-};
-
-/// Get the target window name from runtime settings.
-/// This function provides a dynamic accessor that replaces the compile-time constant.
-pub fn get_target_window_name() -> &'static str {
-    &settings::get_settings().target_window_name
-}
+use crate::core::types::{SearchCriteria, TargetWindowError, WindowCandidate, WindowRect};
+pub use super::window_match::get_target_window_name;
+use super::window_match::{score_candidate, CompiledCriteria, MatchWeights};
+#[cfg(windows)]
+use super::win32_window_system::Win32WindowSystem;
+#[cfg(windows)]
+use super::window_system;
 
 // Thread-local storage for found window handle and search pattern (to avoid Send issues)
 #[cfg(windows)]
@@ -37,8 +33,6 @@ thread_local! {
     static FOUND_HWND: Cell<Option<isize>> = const { Cell::new(None) };
     static SEARCH_PATTERN: Cell<Option<*const String>> = const { Cell::new(None) };
     // T013 (028): Thread-local storage for three-point verification
-    static SEARCH_PROCESS: Cell<Option<*const String>> = const { Cell::new(None) };
-    static SEARCH_CLASS: Cell<Option<*const String>> = const { Cell::new(None) };
     static CANDIDATES: Cell<Option<*mut Vec<WindowCandidate>>> = const { Cell::new(None) };
 }
 
@@ -59,6 +53,13 @@ pub fn get_window_class(hwnd: HWND) -> String {
 // T014 (028): Get process name from HWND
 #[cfg(windows)]
 pub fn get_process_name(hwnd: HWND) -> Option<String> {
+    // Extract just the filename from the full path
+    get_process_path(hwnd).and_then(|path| path.split('\\').next_back().map(|s| s.to_string()))
+}
+
+// Get the full executable path of the process owning a window
+#[cfg(windows)]
+pub fn get_process_path(hwnd: HWND) -> Option<String> {
     unsafe {
         let mut process_id: u32 = 0;
         GetWindowThreadProcessId(hwnd, Some(&mut process_id));
@@ -81,9 +82,7 @@ pub fn get_process_name(hwnd: HWND) -> Option<String> {
             .is_ok()
             {
                 let _ = CloseHandle(handle);
-                let path = String::from_utf16_lossy(&buffer[..size as usize]);
-                // Extract just the filename from the full path
-                return path.split('\\').next_back().map(|s| s.to_string());
+                return Some(String::from_utf16_lossy(&buffer[..size as usize]));
             }
             let _ = CloseHandle(handle);
         }
@@ -91,6 +90,35 @@ pub fn get_process_name(hwnd: HWND) -> Option<String> {
     }
 }
 
+// Get the creation time of the process owning a window
+#[cfg(windows)]
+pub fn get_process_start_time(hwnd: HWND) -> Option<std::time::SystemTime> {
+    // FILETIME counts 100ns intervals since 1601-01-01
+    const UNIX_EPOCH_AS_FILETIME: u64 = 116_444_736_000_000_000;
+
+    unsafe {
+        let mut process_id: u32 = 0;
+        GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+
+        if process_id == 0 {
+            return None;
+        }
+
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+        let mut creation = FILETIME::default();
+        let mut exit = FILETIME::default();
+        let mut kernel = FILETIME::default();
+        let mut user = FILETIME::default();
+        let result = GetProcessTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user);
+        let _ = CloseHandle(handle);
+        result.ok()?;
+
+        let ticks = ((creation.dwHighDateTime as u64) << 32) | creation.dwLowDateTime as u64;
+        let since_epoch = ticks.checked_sub(UNIX_EPOCH_AS_FILETIME)?;
+        Some(std::time::UNIX_EPOCH + std::time::Duration::from_nanos(since_epoch * 100))
+    }
+}
+
 // T015 (028): Check if window is a top-level window
 #[cfg(windows)]
 pub fn is_top_level_window(hwnd: HWND) -> bool {
@@ -102,7 +130,7 @@ pub fn is_top_level_window(hwnd: HWND) -> bool {
 
 // T016 (028): Get window title from HWND
 #[cfg(windows)]
-fn get_window_title(hwnd: HWND) -> String {
+pub fn get_window_title(hwnd: HWND) -> String {
     unsafe {
         let mut title = [0u16; 512];
         let len = GetWindowTextW(hwnd, &mut title);
@@ -127,57 +155,21 @@ pub fn build_window_candidate(hwnd: HWND) -> WindowCandidate {
 }
 
 // T018, T021 (028): Validate a candidate against search criteria (case-insensitive)
+// Plain process/class rules match exactly, plain titles by substring; glob: and regex:
+// rules are supported for every field (see window_match.rs)
 // Note: No #[cfg(windows)] since this is pure logic without Windows API calls
 pub fn validate_candidate(candidate: &WindowCandidate, criteria: &SearchCriteria) -> bool {
-    // Process name check (case-insensitive)
-    let process_match =
-        candidate.process_name.to_lowercase() == criteria.process_name.to_lowercase();
-
-    // Window class check (case-insensitive)
-    let class_match = candidate.window_class.to_lowercase() == criteria.window_class.to_lowercase();
-
-    // Title check (case-insensitive substring match)
-    let title_match = candidate
-        .window_title
-        .to_lowercase()
-        .contains(&criteria.window_title.to_lowercase());
-
-    // Must be top-level window
-    let top_level = candidate.is_top_level;
-
-    process_match && class_match && title_match && top_level
+    let compiled = CompiledCriteria::new(criteria);
+    score_candidate(candidate, &compiled, &MatchWeights::default()).valid
 }
 
-// T017, T019, T022, T029-T033 (028): Find target window using three-point verification
-// Returns DetectionResult with detailed information about the search
+// Enumerate all windows that have a title as detection candidates
 #[cfg(windows)]
-pub fn find_target_window_verified() -> DetectionResult {
-    // T030: Start timing measurement
-    let start_time = Instant::now();
-
-    // Build search criteria from settings
-    let criteria = SearchCriteria {
-        process_name: settings::get_target_process_name().to_string(),
-        window_class: settings::get_target_window_class().to_string(),
-        window_title: get_target_window_name().to_string(),
-    };
-
-    // T033, T004 (030): Log search criteria at TRACE level (per-poll, verbose)
-    log::trace!(
-        "Starting window detection: process={}, class={}, title={}",
-        criteria.process_name,
-        criteria.window_class,
-        criteria.window_title
-    );
-
+pub fn enumerate_titled_windows() -> Vec<WindowCandidate> {
     let mut candidates: Vec<WindowCandidate> = Vec::new();
-    let mut matched_window: Option<WindowCandidate> = None;
 
     // Store candidates pointer for callback access
     CANDIDATES.with(|c| c.set(Some(&mut candidates as *mut Vec<WindowCandidate>)));
-    SEARCH_PROCESS.with(|p| p.set(Some(&criteria.process_name as *const String)));
-    SEARCH_CLASS.with(|c| c.set(Some(&criteria.window_class as *const String)));
-    SEARCH_PATTERN.with(|p| p.set(Some(&criteria.window_title as *const String)));
 
     // Callback for EnumWindows - collects all potential candidates
     unsafe extern "system" fn enum_callback_verified(hwnd: HWND, _lparam: LPARAM) -> BOOL {
@@ -204,65 +196,8 @@ pub fn find_target_window_verified() -> DetectionResult {
 
     // Clear thread-local storage
     CANDIDATES.with(|c| c.set(None));
-    SEARCH_PROCESS.with(|p| p.set(None));
-    SEARCH_CLASS.with(|c| c.set(None));
-    SEARCH_PATTERN.with(|p| p.set(None));
 
-    // T029, T005 (030): Log each candidate at TRACE level (per-poll, verbose)
-    for candidate in &candidates {
-        let valid = validate_candidate(candidate, &criteria);
-        log::trace!(
-            "Window candidate: process={}, class={}, title={}, top_level={}, valid={}",
-            candidate.process_name,
-            candidate.window_class,
-            candidate.window_title,
-            candidate.is_top_level,
-            valid
-        );
-    }
-
-    // T022: Find matching candidates, prioritizing CryENGINE class
-    let mut cryengine_matches: Vec<&WindowCandidate> = Vec::new();
-    let mut other_matches: Vec<&WindowCandidate> = Vec::new();
-
-    for candidate in &candidates {
-        if validate_candidate(candidate, &criteria) {
-            if candidate.window_class.to_lowercase() == "cryengine" {
-                cryengine_matches.push(candidate);
-            } else {
-                other_matches.push(candidate);
-            }
-        }
-    }
-
-    // Prioritize CryENGINE matches over others
-    if let Some(best_match) = cryengine_matches.first().or_else(|| other_matches.first()) {
-        matched_window = Some((*best_match).clone());
-    }
-
-    // T030: Calculate detection time
-    let detection_time_ms = start_time.elapsed().as_millis() as u64;
-
-    // T031, T008 (030): Log detection summary at TRACE level (per-poll, verbose)
-    // State change events (process detected/terminated) are logged at INFO by callers
-    let match_count = cryengine_matches.len() + other_matches.len();
-    log::trace!(
-        "Detection complete: {} candidates evaluated, {} matched, time={}ms",
-        candidates.len(),
-        match_count,
-        detection_time_ms
-    );
-
-    // T032 (030): Detection failure is logged by callers when user takes action (F3)
-    // Removed per-poll warn! to avoid log spam - the F3 handler logs "detection failed"
-
-    DetectionResult {
-        success: matched_window.is_some(),
-        matched_window,
-        candidates_evaluated: candidates,
-        search_criteria: criteria,
-        detection_time_ms,
-    }
+    candidates
 }
 
 // T013-T014: Find target window by pattern matching window titles using runtime settings
@@ -346,41 +281,23 @@ pub fn get_window_rect(hwnd: HWND) -> Result<WindowRect, TargetWindowError> {
     }
 }
 
+// Get the window that currently has keyboard focus
+#[cfg(windows)]
+pub fn get_foreground_hwnd() -> Option<HWND> {
+    let foreground = unsafe { GetForegroundWindow() };
+    (!foreground.0.is_null()).then_some(foreground)
+}
+
 // T034 (028): Get foreground window info for focus logging
 #[cfg(windows)]
 pub fn get_foreground_window_info() -> (String, String) {
-    unsafe {
-        let foreground = GetForegroundWindow();
-        if foreground.0.is_null() {
-            return ("(none)".to_string(), "(none)".to_string());
-        }
-        let process_name = get_process_name(foreground).unwrap_or_else(|| "(unknown)".to_string());
-        let window_title = get_window_title(foreground);
-        (process_name, window_title)
-    }
+    window_system::foreground_window_info(&Win32WindowSystem)
 }
 
 // T017, T035 (028): Check if target window is focused with logging
 #[cfg(windows)]
 pub fn is_target_focused(hwnd: HWND) -> bool {
-    unsafe {
-        let foreground = GetForegroundWindow();
-        let is_focused = hwnd == foreground;
-
-        // T035, T006 (030): Log focus state at TRACE level (per-poll, verbose)
-        if is_focused {
-            log::trace!("Target window is focused (hwnd={})", hwnd.0 as isize);
-        } else {
-            let (fg_process, fg_title) = get_foreground_window_info();
-            log::trace!(
-                "Target window not focused. Foreground: process={}, title={}",
-                fg_process,
-                fg_title
-            );
-        }
-
-        is_focused
-    }
+    window_system::is_window_focused(&Win32WindowSystem, hwnd_to_u64(hwnd))
 }
 
 // T017: Check if a window handle is still valid
@@ -423,6 +340,7 @@ mod tests {
         };
 
         let criteria = SearchCriteria {
+            profile_name: "default".to_string(),
             process_name: "StarCitizen.exe".to_string(),
             window_class: "CryENGINE".to_string(),
             window_title: "Star Citizen".to_string(),
@@ -442,6 +360,7 @@ mod tests {
         };
 
         let criteria = SearchCriteria {
+            profile_name: "default".to_string(),
             process_name: "starcitizen.exe".to_string(),
             window_class: "CRYENGINE".to_string(),
             window_title: "star citizen".to_string(),
@@ -461,6 +380,7 @@ mod tests {
         };
 
         let criteria = SearchCriteria {
+            profile_name: "default".to_string(),
             process_name: "StarCitizen.exe".to_string(),
             window_class: "CryENGINE".to_string(),
             window_title: "Star Citizen".to_string(),
@@ -480,6 +400,7 @@ mod tests {
         };
 
         let criteria = SearchCriteria {
+            profile_name: "default".to_string(),
             process_name: "StarCitizen.exe".to_string(),
             window_class: "CryENGINE".to_string(),
             window_title: "Star Citizen".to_string(),
@@ -499,6 +420,7 @@ mod tests {
         };
 
         let criteria = SearchCriteria {
+            profile_name: "default".to_string(),
             process_name: "StarCitizen.exe".to_string(),
             window_class: "CryENGINE".to_string(),
             window_title: "Star Citizen".to_string(),
@@ -518,6 +440,7 @@ mod tests {
         };
 
         let criteria = SearchCriteria {
+            profile_name: "default".to_string(),
             process_name: "StarCitizen.exe".to_string(),
             window_class: "CryENGINE".to_string(),
             window_title: "Star Citizen".to_string(),
@@ -537,6 +460,7 @@ mod tests {
         };
 
         let criteria = SearchCriteria {
+            profile_name: "default".to_string(),
             process_name: "StarCitizen.exe".to_string(),
             window_class: "CryENGINE".to_string(),
             window_title: "Star Citizen".to_string(),
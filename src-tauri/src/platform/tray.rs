@@ -14,6 +14,7 @@ use tauri::{
 #[cfg(windows)]
 use super::keyboard_hook;
 
+use crate::persistence;
 use crate::settings::window as settings_window;
 
 /// Initialize the system tray icon with menu.
@@ -69,6 +70,15 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
                 let handle = app_handle.clone();
                 std::thread::spawn(move || {
                     std::thread::sleep(std::time::Duration::from_millis(500));
+                    // Write out saves still held by the write-behind queue
+                    let flushed = persistence::queue::flush_all();
+                    log::info!(
+                        "Flushed {} pending writes before exit ({} failed)",
+                        flushed.flushed,
+                        flushed.failed
+                    );
+                    // Close the play session so it is not recovered as a crash
+                    persistence::sessions::end_session_on_exit(&handle);
                     handle.exit(0);
                 });
             }
@@ -0,0 +1,45 @@
+// Win32 implementation of WindowSystem
+// Thin adapter over the Win32 helpers in target_window.rs
+
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use super::target_window;
+use super::window_system::WindowSystem;
+use crate::core::types::{WindowCandidate, WindowRect};
+
+pub struct Win32WindowSystem;
+
+impl WindowSystem for Win32WindowSystem {
+    fn enumerate_windows(&self) -> Vec<WindowCandidate> {
+        target_window::enumerate_titled_windows()
+    }
+
+    fn foreground_window(&self) -> Option<u64> {
+        target_window::get_foreground_hwnd().map(target_window::hwnd_to_u64)
+    }
+
+    fn window_rect(&self, hwnd: u64) -> Option<WindowRect> {
+        target_window::get_window_rect(target_window::u64_to_hwnd(hwnd)).ok()
+    }
+
+    fn is_window_valid(&self, hwnd: u64) -> bool {
+        hwnd != 0 && target_window::is_window_valid(target_window::u64_to_hwnd(hwnd))
+    }
+
+    fn process_name(&self, hwnd: u64) -> Option<String> {
+        target_window::get_process_name(target_window::u64_to_hwnd(hwnd))
+    }
+
+    fn process_path(&self, hwnd: u64) -> Option<PathBuf> {
+        target_window::get_process_path(target_window::u64_to_hwnd(hwnd)).map(PathBuf::from)
+    }
+
+    fn process_start_time(&self, hwnd: u64) -> Option<SystemTime> {
+        target_window::get_process_start_time(target_window::u64_to_hwnd(hwnd))
+    }
+
+    fn window_title(&self, hwnd: u64) -> Option<String> {
+        Some(target_window::get_window_title(target_window::u64_to_hwnd(hwnd)))
+    }
+}
@@ -0,0 +1,390 @@
+// Window matching rules and candidate ranking for target detection
+// Pure logic without Windows API calls, so it is unit-tested on every platform.
+//
+// Each criteria field (process name, window class, window title) is a rule:
+//   "StarCitizen.exe"            plain text (process/class: exact, title: substring)
+//   "glob:StarCitizen*.exe"      glob over the whole value (* and ?)
+//   "regex:^Star Citizen( PTU)?" regular expression (unanchored unless anchored)
+// All rules are case-insensitive. A candidate is valid for a profile when it is a
+// top-level window and all three fields match; valid candidates are ranked by score.
+
+use regex::{Regex, RegexBuilder};
+
+use crate::core::types::{CandidateScore, SearchCriteria, WindowCandidate};
+use crate::settings::{self, TargetProfile};
+
+const GLOB_PREFIX: &str = "glob:";
+const REGEX_PREFIX: &str = "regex:";
+
+// How a plain-text rule compares values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlainMode {
+    // Whole value must be equal (process name, window class)
+    Exact,
+    // Value must contain the text (window title)
+    Contains,
+}
+
+// A compiled matching rule for one criteria field
+#[derive(Debug, Clone)]
+pub enum MatchRule {
+    Plain { text: String, mode: PlainMode },
+    Pattern(Regex),
+}
+
+impl MatchRule {
+    // Parse a rule, using `mode` for plain text. Fails on an invalid regex.
+    pub fn parse(rule: &str, mode: PlainMode) -> Result<Self, String> {
+        if let Some(glob) = rule.strip_prefix(GLOB_PREFIX) {
+            return compile(&glob_to_regex(glob)).map(MatchRule::Pattern);
+        }
+        if let Some(pattern) = rule.strip_prefix(REGEX_PREFIX) {
+            return compile(pattern)
+                .map(MatchRule::Pattern)
+                .map_err(|e| format!("invalid regex '{}': {}", pattern, e));
+        }
+        Ok(MatchRule::Plain {
+            text: rule.to_lowercase(),
+            mode,
+        })
+    }
+
+    // Parse a rule; an invalid rule never matches (and is logged)
+    pub fn parse_or_never(rule: &str, mode: PlainMode) -> Self {
+        Self::parse(rule, mode).unwrap_or_else(|e| {
+            log::warn!("{}; it will never match", e);
+            MatchRule::Pattern(compile("[^\\s\\S]").expect("static regex"))
+        })
+    }
+
+    pub fn matches(&self, value: &str) -> bool {
+        match self {
+            MatchRule::Plain { text, mode } => {
+                let value = value.to_lowercase();
+                match mode {
+                    PlainMode::Exact => value == *text,
+                    PlainMode::Contains => value.contains(text.as_str()),
+                }
+            }
+            MatchRule::Pattern(regex) => regex.is_match(value),
+        }
+    }
+
+    // Whether a plain rule equals the whole value (used as a ranking bonus)
+    pub fn is_exact(&self, value: &str) -> bool {
+        match self {
+            MatchRule::Plain { text, .. } => value.to_lowercase() == *text,
+            MatchRule::Pattern(_) => false,
+        }
+    }
+}
+
+fn compile(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+// Convert a glob (* and ?) into an anchored regex
+pub fn glob_to_regex(glob: &str) -> String {
+    let mut pattern = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    pattern
+}
+
+// Check that a rule parses (used by settings validation)
+pub fn validate_rule(rule: &str) -> Result<(), String> {
+    MatchRule::parse(rule, PlainMode::Exact).map(|_| ())
+}
+
+// Build search criteria for a target profile
+pub fn criteria_for_profile(profile: &TargetProfile) -> SearchCriteria {
+    SearchCriteria {
+        profile_name: profile.name.clone(),
+        process_name: profile.process_name.clone(),
+        window_class: profile.window_class.clone(),
+        window_title: profile.window_title.clone(),
+    }
+}
+
+/// Get the target window name from runtime settings.
+/// This function provides a dynamic accessor that replaces the compile-time constant.
+pub fn get_target_window_name() -> String {
+    settings::get_settings().target_window_name.clone()
+}
+
+// Search criteria for every target profile, in priority order
+pub fn get_target_criteria() -> Vec<SearchCriteria> {
+    settings::get_target_profiles()
+        .iter()
+        .map(criteria_for_profile)
+        .collect()
+}
+
+// Score weights for each matching field
+#[derive(Debug, Clone, Copy)]
+pub struct MatchWeights {
+    pub process: u32,
+    pub class: u32,
+    pub title: u32,
+    // Bonus when a plain title rule equals the whole title (main window over dialogs)
+    pub exact_title: u32,
+}
+
+impl Default for MatchWeights {
+    fn default() -> Self {
+        Self {
+            process: 50,
+            class: 30,
+            title: 20,
+            exact_title: 10,
+        }
+    }
+}
+
+// The compiled rules of one target profile
+#[derive(Debug, Clone)]
+pub struct CompiledCriteria {
+    pub profile_name: String,
+    pub process: MatchRule,
+    pub class: MatchRule,
+    pub title: MatchRule,
+}
+
+impl CompiledCriteria {
+    pub fn new(criteria: &SearchCriteria) -> Self {
+        Self {
+            profile_name: criteria.profile_name.clone(),
+            process: MatchRule::parse_or_never(&criteria.process_name, PlainMode::Exact),
+            class: MatchRule::parse_or_never(&criteria.window_class, PlainMode::Exact),
+            title: MatchRule::parse_or_never(&criteria.window_title, PlainMode::Contains),
+        }
+    }
+}
+
+// Score a candidate against one profile, recording each field's result
+pub fn score_candidate(
+    candidate: &WindowCandidate,
+    criteria: &CompiledCriteria,
+    weights: &MatchWeights,
+) -> CandidateScore {
+    let process_match = criteria.process.matches(&candidate.process_name);
+    let class_match = criteria.class.matches(&candidate.window_class);
+    let title_match = criteria.title.matches(&candidate.window_title);
+    let title_exact = title_match && criteria.title.is_exact(&candidate.window_title);
+
+    let score = [
+        (process_match, weights.process),
+        (class_match, weights.class),
+        (title_match, weights.title),
+        (title_exact, weights.exact_title),
+    ]
+    .iter()
+    .filter(|(matched, _)| *matched)
+    .map(|(_, weight)| weight)
+    .sum();
+
+    CandidateScore {
+        hwnd: candidate.hwnd,
+        profile_name: criteria.profile_name.clone(),
+        process_match,
+        class_match,
+        title_match,
+        title_exact,
+        is_top_level: candidate.is_top_level,
+        score,
+        valid: process_match && class_match && title_match && candidate.is_top_level,
+    }
+}
+
+// Outcome of ranking all candidates against all profiles
+#[derive(Debug, Clone, Default)]
+pub struct RankingResult {
+    // Index of the matched profile and of the chosen candidate
+    pub selected: Option<(usize, usize)>,
+    // Scores of every candidate that matched at least one field, per profile
+    pub scores: Vec<CandidateScore>,
+}
+
+// Rank candidates for each profile in priority order. The highest-priority profile
+// with a valid candidate wins; within it the highest score wins (ties keep
+// enumeration order).
+pub fn rank_candidates(
+    candidates: &[WindowCandidate],
+    profiles: &[SearchCriteria],
+    weights: &MatchWeights,
+) -> RankingResult {
+    let mut result = RankingResult::default();
+
+    for (profile_index, criteria) in profiles.iter().enumerate() {
+        let compiled = CompiledCriteria::new(criteria);
+        let mut best: Option<(usize, u32)> = None;
+
+        for (candidate_index, candidate) in candidates.iter().enumerate() {
+            let score = score_candidate(candidate, &compiled, weights);
+            if score.valid && best.is_none_or(|(_, best_score)| score.score > best_score) {
+                best = Some((candidate_index, score.score));
+            }
+            if score.score > 0 {
+                result.scores.push(score);
+            }
+        }
+
+        if result.selected.is_none() {
+            result.selected = best.map(|(candidate_index, _)| (profile_index, candidate_index));
+        }
+    }
+
+    result
+}
+
+// The highest-priority profile whose process owns any of the candidate windows
+pub fn running_profile<'a>(
+    candidates: &[WindowCandidate],
+    profiles: &'a [SearchCriteria],
+) -> Option<&'a SearchCriteria> {
+    profiles.iter().find(|criteria| {
+        let process = MatchRule::parse_or_never(&criteria.process_name, PlainMode::Exact);
+        candidates.iter().any(|c| process.matches(&c.process_name))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(hwnd: u64, process: &str, class: &str, title: &str) -> WindowCandidate {
+        WindowCandidate {
+            hwnd,
+            process_name: process.to_string(),
+            window_class: class.to_string(),
+            window_title: title.to_string(),
+            is_top_level: true,
+        }
+    }
+
+    fn profile(name: &str, process: &str, class: &str, title: &str) -> SearchCriteria {
+        SearchCriteria {
+            profile_name: name.to_string(),
+            process_name: process.to_string(),
+            window_class: class.to_string(),
+            window_title: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rules() {
+        let glob = MatchRule::parse("glob:StarCitizen*.exe", PlainMode::Exact).unwrap();
+        assert!(glob.matches("starcitizen_ptu.exe"));
+        assert!(glob.matches("StarCitizen.exe"));
+        assert!(!glob.matches("StarCitizen.exe.bak"));
+
+        let regex = MatchRule::parse("regex:^star citizen( ptu)?$", PlainMode::Contains).unwrap();
+        assert!(regex.matches("Star Citizen PTU"));
+        assert!(!regex.matches("Star Citizen - Crash Reporter"));
+
+        let plain = MatchRule::parse("Star Citizen", PlainMode::Contains).unwrap();
+        assert!(plain.matches("STAR CITIZEN - Alpha 4.0"));
+        assert!(plain.is_exact("star citizen"));
+        assert!(!plain.is_exact("Star Citizen - Alpha 4.0"));
+
+        assert!(MatchRule::parse("regex:(unclosed", PlainMode::Exact).is_err());
+        assert!(!MatchRule::parse_or_never("regex:(unclosed", PlainMode::Exact).matches(""));
+        assert_eq!(glob_to_regex("a.b?*"), "^a\\.b..*$");
+    }
+
+    #[test]
+    fn test_score_records_each_field() {
+        let compiled = CompiledCriteria::new(&profile(
+            "LIVE",
+            "StarCitizen.exe",
+            "CryENGINE",
+            "Star Citizen",
+        ));
+        let weights = MatchWeights::default();
+
+        let score = score_candidate(
+            &candidate(1, "StarCitizen.exe", "Qt5QWindowIcon", "Star Citizen"),
+            &compiled,
+            &weights,
+        );
+        assert!(score.process_match && !score.class_match && score.title_match);
+        assert!(score.title_exact);
+        assert!(!score.valid);
+        assert_eq!(
+            score.score,
+            weights.process + weights.title + weights.exact_title
+        );
+    }
+
+    #[test]
+    fn test_rank_prefers_exact_title_and_profile_priority() {
+        let candidates = vec![
+            candidate(
+                1,
+                "StarCitizen.exe",
+                "CryENGINE",
+                "Star Citizen - Crash Reporter",
+            ),
+            candidate(2, "StarCitizen.exe", "CryENGINE", "Star Citizen"),
+            candidate(3, "StarCitizen_PTU.exe", "CryENGINE", "Star Citizen"),
+            candidate(4, "explorer.exe", "CabinetWClass", "Downloads"),
+        ];
+        let profiles = vec![
+            profile("LIVE", "StarCitizen.exe", "CryENGINE", "Star Citizen"),
+            profile("PTU", "StarCitizen_PTU.exe", "CryENGINE", "Star Citizen"),
+        ];
+
+        let ranking = rank_candidates(&candidates, &profiles, &MatchWeights::default());
+        assert_eq!(ranking.selected, Some((0, 1)));
+        // The unrelated window scores 0 for both profiles and is not recorded
+        assert!(ranking.scores.iter().all(|s| s.hwnd != 4));
+
+        let ranking = rank_candidates(&candidates[2..], &profiles, &MatchWeights::default());
+        assert_eq!(ranking.selected, Some((1, 0)));
+    }
+
+    #[test]
+    fn test_rank_rejects_non_top_level() {
+        let mut child = candidate(1, "StarCitizen.exe", "CryENGINE", "Star Citizen");
+        child.is_top_level = false;
+        let profiles = vec![profile(
+            "LIVE",
+            "StarCitizen.exe",
+            "CryENGINE",
+            "Star Citizen",
+        )];
+
+        let ranking = rank_candidates(&[child], &profiles, &MatchWeights::default());
+        assert!(ranking.selected.is_none());
+        assert_eq!(ranking.scores.len(), 1);
+    }
+
+    #[test]
+    fn test_running_profile_with_glob() {
+        let candidates = vec![candidate(1, "StarCitizen_EPTU.exe", "Splash", "Loading")];
+        let profiles = vec![
+            profile("LIVE", "StarCitizen.exe", "CryENGINE", "Star Citizen"),
+            profile(
+                "Test builds",
+                "glob:StarCitizen_*.exe",
+                "CryENGINE",
+                "Star Citizen",
+            ),
+        ];
+
+        assert_eq!(
+            running_profile(&candidates, &profiles).map(|p| p.profile_name.as_str()),
+            Some("Test builds")
+        );
+        assert!(running_profile(&[], &profiles).is_none());
+    }
+}
@@ -0,0 +1,251 @@
+// WindowSystem abstraction over the native window manager
+// Detection, process monitoring and auto-hide talk to this trait instead of calling
+// Win32 directly, so the same flows can be driven by MockWindowSystem in tests on
+// any platform. Window handles are passed around as u64 (see hwnd_to_u64).
+
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Instant, SystemTime};
+
+use super::window_match::{get_target_criteria, rank_candidates, MatchWeights};
+use crate::core::types::{DetectionResult, SearchCriteria, WindowCandidate, WindowRect};
+
+pub trait WindowSystem: Send + Sync {
+    // All windows that have a title, as detection candidates
+    fn enumerate_windows(&self) -> Vec<WindowCandidate>;
+
+    // Handle of the window that currently has keyboard focus
+    fn foreground_window(&self) -> Option<u64>;
+
+    // Position and size of a window (None if the handle is invalid)
+    fn window_rect(&self, hwnd: u64) -> Option<WindowRect>;
+
+    // Whether a window handle still refers to an existing window
+    fn is_window_valid(&self, hwnd: u64) -> bool;
+
+    // Executable name of the process owning a window (e.g., "StarCitizen.exe")
+    fn process_name(&self, hwnd: u64) -> Option<String>;
+
+    // Title of a window
+    fn window_title(&self, hwnd: u64) -> Option<String>;
+
+    // ID of the process owning a window, if the backend exposes it
+    fn process_id(&self, _hwnd: u64) -> Option<u32> {
+        None
+    }
+
+    // Full path of the executable owning a window, if the backend exposes it
+    fn process_path(&self, _hwnd: u64) -> Option<PathBuf> {
+        None
+    }
+
+    // When the process owning a window started, if the backend exposes it
+    fn process_start_time(&self, _hwnd: u64) -> Option<SystemTime> {
+        None
+    }
+}
+
+// The window system used by the monitors (None = native default)
+static WINDOW_SYSTEM: RwLock<Option<Arc<dyn WindowSystem>>> = RwLock::new(None);
+
+// Get the active window system, creating the native one on first use
+pub fn window_system() -> Arc<dyn WindowSystem> {
+    if let Some(system) = WINDOW_SYSTEM.read().ok().and_then(|s| s.clone()) {
+        return system;
+    }
+
+    let mut slot = WINDOW_SYSTEM.write().unwrap_or_else(|p| p.into_inner());
+    slot.get_or_insert_with(native_window_system).clone()
+}
+
+// Replace the active window system (e.g., with a MockWindowSystem)
+pub fn set_window_system(system: Arc<dyn WindowSystem>) {
+    let mut slot = WINDOW_SYSTEM.write().unwrap_or_else(|p| p.into_inner());
+    *slot = Some(system);
+}
+
+#[cfg(windows)]
+fn native_window_system() -> Arc<dyn WindowSystem> {
+    Arc::new(super::win32_window_system::Win32WindowSystem)
+}
+
+// X11 (including XWayland) on Linux; without an X server nothing is ever detected
+#[cfg(target_os = "linux")]
+fn native_window_system() -> Arc<dyn WindowSystem> {
+    match super::x11_window_system::X11WindowSystem::connect(None) {
+        Ok(system) => {
+            log::info!("Using X11 window system for target detection");
+            Arc::new(system)
+        }
+        Err(e) => {
+            log::warn!("Target detection unavailable: {}", e);
+            Arc::new(super::mock_window_system::MockWindowSystem::new())
+        }
+    }
+}
+
+// No native backend on this platform: an empty window list (nothing is ever detected)
+#[cfg(not(any(windows, target_os = "linux")))]
+fn native_window_system() -> Arc<dyn WindowSystem> {
+    Arc::new(super::mock_window_system::MockWindowSystem::new())
+}
+
+// T019 (028): Find the target window for the configured profiles using the active
+// window system
+pub fn find_target_window_verified() -> DetectionResult {
+    detect_target(&*window_system(), &get_target_criteria())
+}
+
+// T017, T019, T022, T029-T033 (028): Find the target window using three-point verification
+// Each target profile is tried in priority order; the first one with a match wins.
+// Returns DetectionResult with detailed information about the search
+pub fn detect_target(system: &dyn WindowSystem, profiles: &[SearchCriteria]) -> DetectionResult {
+    // T030: Start timing measurement
+    let start_time = Instant::now();
+
+    // T033, T004 (030): Log search criteria at TRACE level (per-poll, verbose)
+    for criteria in profiles {
+        log::trace!(
+            "Starting window detection: profile={}, process={}, class={}, title={}",
+            criteria.profile_name,
+            criteria.process_name,
+            criteria.window_class,
+            criteria.window_title
+        );
+    }
+
+    let candidates = system.enumerate_windows();
+
+    // T022: Score every candidate and pick the best window of the highest-priority profile
+    let ranking = rank_candidates(&candidates, profiles, &MatchWeights::default());
+
+    // T029, T005 (030): Log each scored candidate at TRACE level (per-poll, verbose)
+    for score in &ranking.scores {
+        log::trace!(
+            "Window candidate: hwnd={}, profile={}, process={}, class={}, title={}, top_level={}, score={}, valid={}",
+            score.hwnd,
+            score.profile_name,
+            score.process_match,
+            score.class_match,
+            score.title_match,
+            score.is_top_level,
+            score.score,
+            score.valid
+        );
+    }
+
+    let matched_window = ranking
+        .selected
+        .map(|(_, candidate_index)| candidates[candidate_index].clone());
+    let matched_index = ranking.selected.map(|(profile_index, _)| profile_index);
+
+    // T030: Calculate detection time
+    let detection_time_ms = start_time.elapsed().as_millis() as u64;
+
+    // T031, T008 (030): Log detection summary at TRACE level (per-poll, verbose)
+    // State change events (process detected/terminated) are logged at INFO by callers
+    log::trace!(
+        "Detection complete: {} candidates evaluated, {} profiles tried, matched profile={:?}, time={}ms",
+        candidates.len(),
+        profiles.len(),
+        matched_index.map(|i| &profiles[i].profile_name),
+        detection_time_ms
+    );
+
+    // T032 (030): Detection failure is logged by callers when user takes action (F3)
+
+    let search_criteria = profiles[matched_index.unwrap_or(0)].clone();
+    DetectionResult {
+        success: matched_window.is_some(),
+        matched_window,
+        candidates_evaluated: candidates,
+        matched_profile: matched_index.map(|i| profiles[i].profile_name.clone()),
+        search_criteria,
+        profiles_evaluated: profiles.to_vec(),
+        candidate_scores: ranking.scores,
+        detection_time_ms,
+    }
+}
+
+// T034 (028): Get foreground window info (process, title) for focus logging
+pub fn foreground_window_info(system: &dyn WindowSystem) -> (String, String) {
+    let Some(foreground) = system.foreground_window() else {
+        return ("(none)".to_string(), "(none)".to_string());
+    };
+    let process_name = system
+        .process_name(foreground)
+        .unwrap_or_else(|| "(unknown)".to_string());
+    let window_title = system.window_title(foreground).unwrap_or_default();
+    (process_name, window_title)
+}
+
+// T017, T035 (028): Check if a window is focused with logging
+pub fn is_window_focused(system: &dyn WindowSystem, hwnd: u64) -> bool {
+    let is_focused = system.foreground_window() == Some(hwnd);
+
+    // T035, T006 (030): Log focus state at TRACE level (per-poll, verbose)
+    if is_focused {
+        log::trace!("Target window is focused (hwnd={})", hwnd);
+    } else {
+        let (fg_process, fg_title) = foreground_window_info(system);
+        log::trace!(
+            "Target window not focused. Foreground: process={}, title={}",
+            fg_process,
+            fg_title
+        );
+    }
+
+    is_focused
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock_window_system::MockWindowSystem;
+    use super::*;
+
+    fn profile(name: &str, process: &str) -> SearchCriteria {
+        SearchCriteria {
+            profile_name: name.to_string(),
+            process_name: process.to_string(),
+            window_class: "CryENGINE".to_string(),
+            window_title: "Star Citizen".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_detect_target_with_mock() {
+        let system = MockWindowSystem::new();
+        let profiles = vec![
+            profile("LIVE", "StarCitizen.exe"),
+            profile("PTU", "StarCitizen_PTU.exe"),
+        ];
+
+        let result = detect_target(&system, &profiles);
+        assert!(!result.success);
+        assert!(result.matched_profile.is_none());
+        assert_eq!(result.search_criteria.profile_name, "LIVE");
+
+        let hwnd = system.add_game_window("StarCitizen_PTU.exe");
+        let result = detect_target(&system, &profiles);
+        assert!(result.success);
+        assert_eq!(result.matched_profile.as_deref(), Some("PTU"));
+        assert_eq!(result.matched_window.map(|w| w.hwnd), Some(hwnd));
+        assert_eq!(result.search_criteria.profile_name, "PTU");
+    }
+
+    #[test]
+    fn test_focus_queries() {
+        let system = MockWindowSystem::new();
+        let game = system.add_game_window("StarCitizen.exe");
+
+        assert!(!is_window_focused(&system, game));
+        assert_eq!(foreground_window_info(&system).0, "(none)");
+
+        system.set_foreground(Some(game));
+        assert!(is_window_focused(&system, game));
+        assert_eq!(
+            foreground_window_info(&system),
+            ("StarCitizen.exe".to_string(), "Star Citizen".to_string())
+        );
+    }
+}
@@ -0,0 +1,487 @@
+// X11 implementation of WindowSystem (Linux)
+// Lets the overlay attach to Star Citizen running under Wine or Proton. Windows are
+// read through EWMH properties set by the window manager and by Wine:
+// - _NET_CLIENT_LIST: top-level windows (falls back to the root's mapped children
+//   when no window manager is running, e.g. under Xvfb)
+// - _NET_WM_PID: owning process, resolved to an executable name via /proc (Wine's
+//   Windows paths are mapped into the prefix through its dosdevices links)
+// - WM_CLASS: reported as the window class (Wine sets it to the executable name,
+//   not the Win32 class such as "CryENGINE")
+// - _NET_WM_NAME (falls back to WM_NAME): window title
+// - _NET_ACTIVE_WINDOW (falls back to the input focus): foreground window
+// Window handles are X11 window ids widened to u64.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{Atom, AtomEnum, ConnectionExt, GetPropertyReply, MapState, Window};
+use x11rb::rust_connection::RustConnection;
+
+use super::window_system::WindowSystem;
+use crate::core::types::{WindowCandidate, WindowRect};
+
+// Atoms that are not predefined by the core protocol
+struct Atoms {
+    net_client_list: Atom,
+    net_active_window: Atom,
+    net_wm_pid: Atom,
+    net_wm_name: Atom,
+    utf8_string: Atom,
+}
+
+impl Atoms {
+    fn intern(conn: &RustConnection) -> Result<Self, String> {
+        let intern = |name: &str| -> Result<Atom, String> {
+            conn.intern_atom(false, name.as_bytes())
+                .map_err(|e| format!("Failed to intern {}: {}", name, e))?
+                .reply()
+                .map(|reply| reply.atom)
+                .map_err(|e| format!("Failed to intern {}: {}", name, e))
+        };
+
+        Ok(Self {
+            net_client_list: intern("_NET_CLIENT_LIST")?,
+            net_active_window: intern("_NET_ACTIVE_WINDOW")?,
+            net_wm_pid: intern("_NET_WM_PID")?,
+            net_wm_name: intern("_NET_WM_NAME")?,
+            utf8_string: intern("UTF8_STRING")?,
+        })
+    }
+}
+
+pub struct X11WindowSystem {
+    conn: RustConnection,
+    root: Window,
+    atoms: Atoms,
+}
+
+impl X11WindowSystem {
+    // Connect to an X server (None = $DISPLAY)
+    pub fn connect(display: Option<&str>) -> Result<Self, String> {
+        let (conn, screen_num) =
+            x11rb::connect(display).map_err(|e| format!("Failed to connect to X server: {}", e))?;
+        let root = conn
+            .setup()
+            .roots
+            .get(screen_num)
+            .map(|screen| screen.root)
+            .ok_or_else(|| format!("X server has no screen {}", screen_num))?;
+        let atoms = Atoms::intern(&conn)?;
+
+        Ok(Self { conn, root, atoms })
+    }
+
+    fn property(
+        &self,
+        window: Window,
+        property: impl Into<Atom>,
+        type_: impl Into<Atom>,
+    ) -> Option<GetPropertyReply> {
+        self.conn
+            .get_property(false, window, property, type_, 0, u32::MAX)
+            .ok()?
+            .reply()
+            .ok()
+    }
+
+    // First 32-bit value of a property (e.g., a PID or a window id)
+    fn property_u32(
+        &self,
+        window: Window,
+        property: impl Into<Atom>,
+        type_: impl Into<Atom>,
+    ) -> Option<u32> {
+        self.property(window, property, type_)?.value32()?.next()
+    }
+
+    // Top-level windows, in stacking order of the client list
+    fn top_level_windows(&self) -> Vec<Window> {
+        let client_list: Vec<Window> = self
+            .property(self.root, self.atoms.net_client_list, AtomEnum::WINDOW)
+            .and_then(|reply| reply.value32().map(|ids| ids.collect()))
+            .unwrap_or_default();
+        if !client_list.is_empty() {
+            return client_list;
+        }
+
+        // No EWMH window manager: use the mapped children of the root window
+        self.conn
+            .query_tree(self.root)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .map(|tree| {
+                tree.children
+                    .into_iter()
+                    .filter(|&window| self.is_viewable(window))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn is_viewable(&self, window: Window) -> bool {
+        self.conn
+            .get_window_attributes(window)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .is_some_and(|attributes| attributes.map_state == MapState::VIEWABLE)
+    }
+
+    fn pid(&self, window: Window) -> Option<u32> {
+        self.property_u32(window, self.atoms.net_wm_pid, AtomEnum::CARDINAL)
+            .filter(|&pid| pid != 0)
+    }
+
+    fn title(&self, window: Window) -> String {
+        self.property(window, self.atoms.net_wm_name, self.atoms.utf8_string)
+            .filter(|reply| !reply.value.is_empty())
+            .or_else(|| self.property(window, AtomEnum::WM_NAME, AtomEnum::STRING))
+            .map(|reply| String::from_utf8_lossy(&reply.value).into_owned())
+            .unwrap_or_default()
+    }
+
+    fn class(&self, window: Window) -> String {
+        self.property(window, AtomEnum::WM_CLASS, AtomEnum::STRING)
+            .and_then(|reply| parse_wm_class(&reply.value))
+            .unwrap_or_default()
+    }
+}
+
+// X11 window ids are 32-bit; anything larger cannot be a window
+fn to_window(hwnd: u64) -> Option<Window> {
+    Window::try_from(hwnd).ok().filter(|&window| window != 0)
+}
+
+// WM_CLASS holds two NUL-terminated strings: instance name, then class name.
+// The class name is used for matching (the instance if the class is missing).
+fn parse_wm_class(value: &[u8]) -> Option<String> {
+    let mut parts = value
+        .split(|&b| b == 0)
+        .map(|part| String::from_utf8_lossy(part).into_owned());
+    let instance = parts.next().filter(|s| !s.is_empty());
+    let class = parts.next().filter(|s| !s.is_empty());
+    class.or(instance)
+}
+
+// Executable name from /proc/<pid>/cmdline. Under Wine argv[0] is a Windows path
+// (C:\...\StarCitizen.exe), so both separators are stripped.
+fn process_name_from_cmdline(cmdline: &[u8]) -> Option<String> {
+    let argv0 = cmdline.split(|&b| b == 0).next()?;
+    let argv0 = String::from_utf8_lossy(argv0);
+    let name = argv0.rsplit(['/', '\\']).next()?.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+// Executable name of a process (argv[0], falling back to the kernel's comm name)
+fn process_name_for_pid(pid: u32) -> Option<String> {
+    std::fs::read(format!("/proc/{}/cmdline", pid))
+        .ok()
+        .and_then(|cmdline| process_name_from_cmdline(&cmdline))
+        .or_else(|| {
+            std::fs::read_to_string(format!("/proc/{}/comm", pid))
+                .ok()
+                .map(|comm| comm.trim().to_string())
+                .filter(|comm| !comm.is_empty())
+        })
+}
+
+// Map a Windows path such as "C:\\Games\\StarCitizen.exe" into a Wine prefix
+fn wine_unix_path(windows_path: &str, prefix: &Path) -> Option<PathBuf> {
+    let (drive, rest) = windows_path.split_once(':')?;
+    if drive.len() != 1 || !drive.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let mut path = prefix
+        .join("dosdevices")
+        .join(format!("{}:", drive.to_ascii_lowercase()));
+    path.extend(rest.split('\\').filter(|part| !part.is_empty()));
+    Some(path)
+}
+
+// Wine prefix of a process (WINEPREFIX, defaulting to ~/.wine like Wine itself)
+fn wine_prefix_for_pid(pid: u32) -> Option<PathBuf> {
+    let environ = std::fs::read(format!("/proc/{}/environ", pid)).ok()?;
+    let variable = |name: &str| {
+        environ.split(|&b| b == 0).find_map(|entry| {
+            let entry = String::from_utf8_lossy(entry);
+            entry
+                .strip_prefix(name)
+                .and_then(|v| v.strip_prefix('='))
+                .map(PathBuf::from)
+        })
+    };
+    variable("WINEPREFIX").or_else(|| variable("HOME").map(|home| home.join(".wine")))
+}
+
+// Executable path of a process (argv[0]; Windows paths are mapped into the Wine prefix)
+fn process_path_for_pid(pid: u32) -> Option<PathBuf> {
+    let cmdline = std::fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let argv0 = cmdline.split(|&b| b == 0).next()?;
+    let argv0 = String::from_utf8_lossy(argv0);
+    if argv0.starts_with('/') {
+        return Some(PathBuf::from(argv0.as_ref()));
+    }
+    wine_unix_path(&argv0, &wine_prefix_for_pid(pid)?)
+}
+
+// Clock ticks per second of /proc/<pid>/stat times (USER_HZ, 100 on all common kernels)
+const CLOCK_TICKS_PER_SEC: u64 = 100;
+
+// Process start time from /proc/<pid>/stat (field 22, in clock ticks since boot) and the
+// boot time in seconds since the epoch (btime in /proc/stat)
+fn start_time_from_stat(stat: &str, boot_time_secs: u64) -> Option<SystemTime> {
+    // The command name (field 2) may contain spaces, so count fields after its ')'
+    let after_comm = &stat[stat.rfind(')')? + 1..];
+    let start_ticks: u64 = after_comm.split_whitespace().nth(19)?.parse().ok()?;
+    let start_ms = boot_time_secs * 1000 + start_ticks * 1000 / CLOCK_TICKS_PER_SEC;
+    Some(UNIX_EPOCH + Duration::from_millis(start_ms))
+}
+
+// When a process started
+fn process_start_time_for_pid(pid: u32) -> Option<SystemTime> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let boot_time_secs = std::fs::read_to_string("/proc/stat")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("btime ")?.trim().parse().ok())?;
+    start_time_from_stat(&stat, boot_time_secs)
+}
+
+impl WindowSystem for X11WindowSystem {
+    fn enumerate_windows(&self) -> Vec<WindowCandidate> {
+        self.top_level_windows()
+            .into_iter()
+            .filter_map(|window| {
+                let window_title = self.title(window);
+                if window_title.is_empty() {
+                    return None;
+                }
+                Some(WindowCandidate {
+                    hwnd: window as u64,
+                    process_name: self
+                        .pid(window)
+                        .and_then(process_name_for_pid)
+                        .unwrap_or_default(),
+                    window_class: self.class(window),
+                    window_title,
+                    is_top_level: true,
+                })
+            })
+            .collect()
+    }
+
+    fn foreground_window(&self) -> Option<u64> {
+        let active = self
+            .property_u32(self.root, self.atoms.net_active_window, AtomEnum::WINDOW)
+            .filter(|&window| window != 0);
+        if let Some(window) = active {
+            return Some(window as u64);
+        }
+
+        // No EWMH window manager: use the input focus (0 = None, 1 = PointerRoot)
+        let focus = self.conn.get_input_focus().ok()?.reply().ok()?.focus;
+        (focus > 1 && focus != self.root).then_some(focus as u64)
+    }
+
+    fn window_rect(&self, hwnd: u64) -> Option<WindowRect> {
+        let window = to_window(hwnd)?;
+        let geometry = self.conn.get_geometry(window).ok()?.reply().ok()?;
+        // Geometry is relative to the parent (often a WM frame); translate to the root
+        let origin = self
+            .conn
+            .translate_coordinates(window, self.root, 0, 0)
+            .ok()?
+            .reply()
+            .ok()?;
+
+        Some(WindowRect {
+            x: origin.dst_x as i32,
+            y: origin.dst_y as i32,
+            width: geometry.width as u32,
+            height: geometry.height as u32,
+        })
+    }
+
+    fn is_window_valid(&self, hwnd: u64) -> bool {
+        to_window(hwnd).is_some_and(|window| {
+            self.conn
+                .get_window_attributes(window)
+                .ok()
+                .and_then(|cookie| cookie.reply().ok())
+                .is_some()
+        })
+    }
+
+    fn process_name(&self, hwnd: u64) -> Option<String> {
+        self.pid(to_window(hwnd)?).and_then(process_name_for_pid)
+    }
+
+    fn process_id(&self, hwnd: u64) -> Option<u32> {
+        self.pid(to_window(hwnd)?)
+    }
+
+    fn process_path(&self, hwnd: u64) -> Option<PathBuf> {
+        self.pid(to_window(hwnd)?).and_then(process_path_for_pid)
+    }
+
+    fn process_start_time(&self, hwnd: u64) -> Option<SystemTime> {
+        self.pid(to_window(hwnd)?)
+            .and_then(process_start_time_for_pid)
+    }
+
+    fn window_title(&self, hwnd: u64) -> Option<String> {
+        to_window(hwnd).map(|window| self.title(window))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::SearchCriteria;
+    use crate::platform::window_system::{detect_target, is_window_focused};
+    use x11rb::protocol::xproto::{CreateWindowAux, InputFocus, PropMode, WindowClass};
+    use x11rb::wrapper::ConnectionExt as _;
+
+    #[test]
+    fn test_parse_wm_class() {
+        assert_eq!(
+            parse_wm_class(b"starcitizen.exe\0StarCitizen.exe\0").as_deref(),
+            Some("StarCitizen.exe")
+        );
+        assert_eq!(parse_wm_class(b"instance\0\0").as_deref(), Some("instance"));
+        assert_eq!(parse_wm_class(b""), None);
+    }
+
+    #[test]
+    fn test_start_time_from_stat() {
+        let stat =
+            "4242 (StarCitizen.exe) S 1 4242 4242 0 -1 4194560 1 0 0 0 0 0 0 0 20 0 30 0 12345 0 0";
+        assert_eq!(
+            start_time_from_stat(stat, 1_700_000_000),
+            Some(UNIX_EPOCH + Duration::from_millis(1_700_000_000_000 + 123_450))
+        );
+        // Spaces and parentheses in the command name
+        let stat = "7 (a (b) c) S 1 7 7 0 -1 0 0 0 0 0 0 0 0 0 20 0 1 0 200 0 0";
+        assert_eq!(
+            start_time_from_stat(stat, 0),
+            Some(UNIX_EPOCH + Duration::from_secs(2))
+        );
+        assert_eq!(start_time_from_stat("garbage", 0), None);
+    }
+
+    #[test]
+    fn test_process_name_from_cmdline() {
+        assert_eq!(
+            process_name_from_cmdline(
+                b"C:\\Program Files\\Roberts Space Industries\\StarCitizen\\LIVE\\Bin64\\StarCitizen.exe\0-no_login_dialog\0"
+            )
+            .as_deref(),
+            Some("StarCitizen.exe")
+        );
+        assert_eq!(
+            process_name_from_cmdline(b"/usr/bin/firefox\0--new-window\0").as_deref(),
+            Some("firefox")
+        );
+        assert_eq!(process_name_from_cmdline(b"\0"), None);
+    }
+
+    #[test]
+    fn test_wine_unix_path() {
+        let prefix = Path::new("/home/pilot/Games/star-citizen");
+        assert_eq!(
+            wine_unix_path(
+                "C:\\Program Files\\Roberts Space Industries\\StarCitizen\\LIVE\\Bin64\\StarCitizen.exe",
+                prefix
+            ),
+            Some(PathBuf::from(
+                "/home/pilot/Games/star-citizen/dosdevices/c:/Program Files/Roberts Space Industries/StarCitizen/LIVE/Bin64/StarCitizen.exe"
+            ))
+        );
+        assert_eq!(wine_unix_path("StarCitizen.exe", prefix), None);
+        assert_eq!(wine_unix_path("\\\\?\\C:\\x.exe", prefix), None);
+    }
+
+    // Runs against a real X server: `xvfb-run cargo test x11 -- --ignored`
+    #[test]
+    #[ignore = "requires an X server (run under xvfb-run)"]
+    fn test_detect_window_on_x_server() {
+        let system = X11WindowSystem::connect(None).expect("X server");
+        let (conn, screen_num) = x11rb::connect(None).expect("X server");
+        let root = conn.setup().roots[screen_num].root;
+
+        // Create and map a window that looks like the game under Wine
+        let window = conn.generate_id().unwrap();
+        conn.create_window(
+            x11rb::COPY_DEPTH_FROM_PARENT,
+            window,
+            root,
+            10,
+            20,
+            640,
+            480,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            x11rb::COPY_FROM_PARENT,
+            &CreateWindowAux::new(),
+        )
+        .unwrap();
+        conn.change_property8(
+            PropMode::REPLACE,
+            window,
+            AtomEnum::WM_CLASS,
+            AtomEnum::STRING,
+            b"starcitizen.exe\0StarCitizen.exe\0",
+        )
+        .unwrap();
+        conn.change_property8(
+            PropMode::REPLACE,
+            window,
+            system.atoms.net_wm_name,
+            system.atoms.utf8_string,
+            "Star Citizen".as_bytes(),
+        )
+        .unwrap();
+        conn.change_property32(
+            PropMode::REPLACE,
+            window,
+            system.atoms.net_wm_pid,
+            AtomEnum::CARDINAL,
+            &[std::process::id()],
+        )
+        .unwrap();
+        conn.map_window(window).unwrap();
+        conn.set_input_focus(InputFocus::PARENT, window, x11rb::CURRENT_TIME)
+            .unwrap();
+        // Round trip so the server has processed everything before we query it
+        conn.get_input_focus().unwrap().reply().unwrap();
+
+        let hwnd = window as u64;
+        let own_process = process_name_for_pid(std::process::id()).unwrap();
+        let candidate = system
+            .enumerate_windows()
+            .into_iter()
+            .find(|c| c.hwnd == hwnd)
+            .expect("window enumerated");
+        assert_eq!(candidate.window_class, "StarCitizen.exe");
+        assert_eq!(candidate.window_title, "Star Citizen");
+        assert_eq!(candidate.process_name, own_process);
+
+        let profiles = vec![SearchCriteria {
+            profile_name: "LIVE".to_string(),
+            process_name: own_process,
+            window_class: "StarCitizen.exe".to_string(),
+            window_title: "Star Citizen".to_string(),
+        }];
+        let result = detect_target(&system, &profiles);
+        assert_eq!(result.matched_window.map(|w| w.hwnd), Some(hwnd));
+
+        let rect = system.window_rect(hwnd).unwrap();
+        assert_eq!((rect.width, rect.height), (640, 480));
+        assert!(is_window_focused(&system, hwnd));
+        assert_eq!(system.process_id(hwnd), Some(std::process::id()));
+
+        conn.destroy_window(window).unwrap();
+        conn.get_input_focus().unwrap().reply().unwrap();
+        assert!(!system.is_window_valid(hwnd));
+    }
+}
@@ -0,0 +1,172 @@
+// Command-line remote control grammar
+// A second launch such as `raic-overlay --toggle-visibility` is forwarded to the running
+// instance by the single-instance plugin; its arguments are parsed here into commands.
+
+use crate::core::types::OverlayMode;
+use crate::hotkey::registry::{
+    CHRONOMETER_RESET, CHRONOMETER_START_PAUSE, TOGGLE_MODE, TOGGLE_VISIBILITY,
+};
+
+/// Usage text, logged when the arguments cannot be parsed
+pub const USAGE: &str = "\
+Usage: raic-overlay [COMMAND]...
+  --toggle-visibility        Show or hide the overlay (same as the hotkey)
+  --show, --hide             Show or hide the overlay
+  --toggle-mode              Switch between interactive and click-through mode
+  --mode <interactive|passthrough>
+  --profile <name>           Switch layout profile
+  --chrono <start-pause|reset>
+  --action <id>              Trigger any registered hotkey action
+Commands run in the given order. Without arguments the running overlay is focused.";
+
+/// A command for the running instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteCommand {
+    /// Trigger a registered hotkey action by ID (e.g., "toggleVisibility")
+    Action(String),
+    /// Show (true) or hide (false) the overlay
+    SetVisible(bool),
+    /// Switch to interactive (Windowed) or click-through (Fullscreen) mode
+    SetMode(OverlayMode),
+    /// Switch to a layout profile by name
+    SwitchProfile(String),
+}
+
+/// Parse command-line arguments (without the program name) into commands.
+/// Options take their value as the next argument or after '=' (`--mode=interactive`).
+pub fn parse_args<S: AsRef<str>>(args: &[S]) -> Result<Vec<RemoteCommand>, String> {
+    let mut commands = Vec::new();
+    let mut args = args.iter().map(AsRef::as_ref);
+
+    while let Some(arg) = args.next() {
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (arg, None),
+        };
+        let mut value = |what: &str| -> Result<String, String> {
+            inline_value
+                .or_else(|| args.next())
+                .filter(|v| !v.is_empty() && !v.starts_with("--"))
+                .map(str::to_string)
+                .ok_or_else(|| format!("{} requires {}", name, what))
+        };
+
+        let command = match name {
+            "--toggle-visibility" => RemoteCommand::Action(TOGGLE_VISIBILITY.to_string()),
+            "--toggle-mode" => RemoteCommand::Action(TOGGLE_MODE.to_string()),
+            "--show" => RemoteCommand::SetVisible(true),
+            "--hide" => RemoteCommand::SetVisible(false),
+            "--mode" => RemoteCommand::SetMode(parse_mode(&value("a mode")?)?),
+            "--profile" => RemoteCommand::SwitchProfile(value("a profile name")?),
+            "--chrono" => RemoteCommand::Action(parse_chrono(&value("an action")?)?),
+            "--action" => RemoteCommand::Action(value("an action ID")?),
+            _ => return Err(format!("Unknown argument '{}'", arg)),
+        };
+        if inline_value.is_some() && !takes_value(name) {
+            return Err(format!("{} does not take a value", name));
+        }
+        commands.push(command);
+    }
+
+    Ok(commands)
+}
+
+fn takes_value(name: &str) -> bool {
+    matches!(name, "--mode" | "--profile" | "--chrono" | "--action")
+}
+
+fn parse_mode(value: &str) -> Result<OverlayMode, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "interactive" | "windowed" => Ok(OverlayMode::Windowed),
+        "passthrough" | "click-through" | "fullscreen" => Ok(OverlayMode::Fullscreen),
+        _ => Err(format!(
+            "Unknown mode '{}' (expected interactive or passthrough)",
+            value
+        )),
+    }
+}
+
+fn parse_chrono(value: &str) -> Result<String, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "start-pause" | "toggle" => Ok(CHRONOMETER_START_PAUSE.to_string()),
+        "reset" => Ok(CHRONOMETER_RESET.to_string()),
+        _ => Err(format!(
+            "Unknown chronometer action '{}' (expected start-pause or reset)",
+            value
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands_in_order() {
+        let commands = parse_args(&[
+            "--show",
+            "--mode",
+            "interactive",
+            "--profile=mining",
+            "--chrono",
+            "reset",
+            "--toggle-visibility",
+        ])
+        .unwrap();
+        assert_eq!(
+            commands,
+            vec![
+                RemoteCommand::SetVisible(true),
+                RemoteCommand::SetMode(OverlayMode::Windowed),
+                RemoteCommand::SwitchProfile("mining".to_string()),
+                RemoteCommand::Action(CHRONOMETER_RESET.to_string()),
+                RemoteCommand::Action(TOGGLE_VISIBILITY.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_aliases_and_empty() {
+        assert_eq!(parse_args::<&str>(&[]).unwrap(), vec![]);
+        assert_eq!(
+            parse_args(&[
+                "--mode=Passthrough",
+                "--chrono",
+                "toggle",
+                "--action",
+                "openNotes"
+            ])
+            .unwrap(),
+            vec![
+                RemoteCommand::SetMode(OverlayMode::Fullscreen),
+                RemoteCommand::Action(CHRONOMETER_START_PAUSE.to_string()),
+                RemoteCommand::Action("openNotes".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_args(&["--teleport"]).unwrap_err(),
+            "Unknown argument '--teleport'"
+        );
+        assert_eq!(
+            parse_args(&["--profile"]).unwrap_err(),
+            "--profile requires a profile name"
+        );
+        // The next option is not taken as the value
+        assert_eq!(
+            parse_args(&["--mode", "--show"]).unwrap_err(),
+            "--mode requires a mode"
+        );
+        assert!(parse_args(&["--mode", "fancy"])
+            .unwrap_err()
+            .starts_with("Unknown mode 'fancy'"));
+        assert!(parse_args(&["--chrono", "lap"]).is_err());
+        assert_eq!(
+            parse_args(&["--hide=now"]).unwrap_err(),
+            "--hide does not take a value"
+        );
+    }
+}
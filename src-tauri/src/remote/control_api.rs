@@ -0,0 +1,119 @@
+// Local control API - Tauri side of remote/server.rs
+// Starts the server from [control_api] in settings.toml, answers it from OverlayState and
+// the hotkey registry, and forwards backend events to its /events clients.
+
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tauri::{AppHandle, Listener, Manager};
+
+use super::cli::RemoteCommand;
+use super::dispatch;
+use super::server::{ControlBackend, ControlServer};
+use crate::alarms::ALARM_EVENT_NAMES;
+use crate::core::types::OverlayStateResponse;
+use crate::core::OverlayState;
+use crate::gamelog::GAME_EVENT_NAMES;
+use crate::hotkey::registry::{registered_actions, HotkeyAction};
+use crate::settings::runtime::get_control_api_settings;
+use crate::timers::TIMER_EVENT_NAMES;
+
+/// Backend events streamed to /events clients, in addition to the Game.log, timer and
+/// alarm events (GAME_EVENT_NAMES, TIMER_EVENT_NAMES, ALARM_EVENT_NAMES) and the hotkey
+/// action events
+pub const FORWARDED_EVENTS: &[&str] = &[
+    "target-process-detected",
+    "target-process-terminated",
+    "target-window-changed",
+    "mode-changed",
+    "overlay-state-changed",
+    "auto-hide-changed",
+    "target-focus-changed",
+    "hotkeys-updated",
+    "profile-switched",
+    "settings-reloaded",
+];
+
+/// App handle, kept for restarts on settings reload
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// The running server (None while disabled)
+static SERVER: Mutex<Option<ControlServer>> = Mutex::new(None);
+
+struct AppBackend {
+    app: AppHandle,
+}
+
+impl ControlBackend for AppBackend {
+    fn state(&self) -> OverlayStateResponse {
+        self.app.state::<OverlayState>().to_response()
+    }
+
+    fn actions(&self) -> Vec<HotkeyAction> {
+        registered_actions()
+    }
+
+    fn trigger(&self, action: &str) -> Result<(), String> {
+        dispatch::execute(&self.app, &RemoteCommand::Action(action.to_string()))
+    }
+}
+
+/// Start the control API if it is enabled in settings.toml
+pub fn start_control_api(app: &AppHandle) {
+    if APP.set(app.clone()).is_ok() {
+        forward_events(app);
+    }
+    restart_control_api();
+}
+
+/// Stop the running server and start it again with the current settings
+/// (called when [control_api] changes on settings reload)
+pub fn restart_control_api() {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    let mut server = SERVER.lock().unwrap_or_else(|p| p.into_inner());
+    if let Some(previous) = server.take() {
+        previous.shutdown();
+        log::info!("Control API on {} stopped", previous.addr());
+    }
+
+    let settings = get_control_api_settings();
+    if !settings.enabled {
+        log::debug!("Control API disabled");
+        return;
+    }
+    if settings.token.is_empty() {
+        log::error!("Control API is enabled but control_api.token is empty; not starting it");
+        return;
+    }
+
+    let backend = Arc::new(AppBackend { app: app.clone() });
+    match ControlServer::start(settings.port, &settings.token, backend) {
+        Ok(started) => {
+            log::info!("Control API listening on http://{}", started.addr());
+            *server = Some(started);
+        }
+        Err(e) => log::error!("Failed to start control API: {}", e),
+    }
+}
+
+// Listen once for the lifetime of the app; events are dropped while no server runs
+fn forward_events(app: &AppHandle) {
+    let action_events = registered_actions().into_iter().map(|action| action.event);
+    let events = FORWARDED_EVENTS
+        .iter()
+        .chain(GAME_EVENT_NAMES)
+        .chain(TIMER_EVENT_NAMES)
+        .chain(ALARM_EVENT_NAMES)
+        .map(|event| event.to_string())
+        .chain(action_events);
+
+    for event in events {
+        let name = event.clone();
+        app.listen_any(event, move |e| {
+            if let Some(server) = SERVER.lock().ok().as_ref().and_then(|s| s.as_ref()) {
+                server.publish(&name, e.payload());
+            }
+        });
+    }
+}
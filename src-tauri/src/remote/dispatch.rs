@@ -0,0 +1,274 @@
+// Remote command dispatch
+// Actions emit their registered event to the frontend, exactly as a key press does.
+// Visibility and mode changes are applied in the backend with the code behind the F3/F5
+// commands, so each command has taken effect before the next one reads the state; the
+// frontend is kept in sync by events.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::cli::{parse_args, RemoteCommand, USAGE};
+use crate::core::types::{OverlayMode, OverlayStateResponse};
+use crate::core::OverlayState;
+use crate::hotkey::registry::registered_actions;
+
+/// Emitted with the new OverlayStateResponse after the backend changed the overlay
+pub const OVERLAY_STATE_CHANGED_EVENT: &str = "overlay-state-changed";
+
+/// Serializes check-then-toggle sequences so concurrent callers cannot undo each other
+static OVERLAY_LOCK: Mutex<()> = Mutex::new(());
+
+/// Payload of action events emitted on behalf of another component (e.g. the rules engine),
+/// so it can recognise its own events. Hotkeys and the command line send no payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionSource {
+    pub source: String,
+}
+
+/// Visibility and mode changes, implemented on the running app (and mocked in tests)
+pub trait OverlayControl {
+    fn state(&self) -> &OverlayState;
+    /// Show or hide the overlay as the F3 hotkey does
+    fn toggle_visibility(&self) -> Result<(), String>;
+    /// Switch between click-through and interactive as the F5 hotkey does
+    fn toggle_mode(&self) -> Result<(), String>;
+}
+
+/// Overlay toggled in memory as the F3/F5 commands do, the target window always found
+#[cfg(test)]
+#[derive(Default)]
+pub(crate) struct MockOverlay {
+    pub state: OverlayState,
+}
+
+#[cfg(test)]
+impl OverlayControl for MockOverlay {
+    fn state(&self) -> &OverlayState {
+        &self.state
+    }
+
+    fn toggle_visibility(&self) -> Result<(), String> {
+        self.state.toggle_visible();
+        self.state.set_mode(OverlayMode::Fullscreen);
+        Ok(())
+    }
+
+    fn toggle_mode(&self) -> Result<(), String> {
+        self.state.set_mode(match self.state.get_mode() {
+            OverlayMode::Windowed => OverlayMode::Fullscreen,
+            OverlayMode::Fullscreen => OverlayMode::Windowed,
+        });
+        Ok(())
+    }
+}
+
+/// The overlay of the running app
+struct AppOverlay<'a> {
+    app: &'a AppHandle,
+    state: tauri::State<'a, OverlayState>,
+}
+
+impl<'a> AppOverlay<'a> {
+    fn new(app: &'a AppHandle) -> Self {
+        Self {
+            app,
+            state: app.state::<OverlayState>(),
+        }
+    }
+
+    fn window(&self) -> Result<tauri::WebviewWindow, String> {
+        self.app
+            .get_webview_window("main")
+            .ok_or_else(|| "Main window not found".to_string())
+    }
+
+    fn emit_state(&self, response: OverlayStateResponse) -> Result<(), String> {
+        self.app
+            .emit(OVERLAY_STATE_CHANGED_EVENT, response)
+            .map_err(|e| {
+                format!(
+                    "Failed to emit {} event: {}",
+                    OVERLAY_STATE_CHANGED_EVENT, e
+                )
+            })
+    }
+}
+
+impl OverlayControl for AppOverlay<'_> {
+    fn state(&self) -> &OverlayState {
+        self.state.inner()
+    }
+
+    fn toggle_visibility(&self) -> Result<(), String> {
+        let response =
+            crate::commands::overlay::toggle_visibility_internal(&self.window()?, self.state())?;
+        self.emit_state(response)
+    }
+
+    fn toggle_mode(&self) -> Result<(), String> {
+        let response =
+            crate::commands::overlay::toggle_mode_internal(&self.window()?, self.state())?;
+        self.emit_state(response)
+    }
+}
+
+/// Execute a remote command against the running instance
+pub fn execute(app: &AppHandle, command: &RemoteCommand) -> Result<(), String> {
+    execute_from(app, command, None)
+}
+
+/// Execute a command, tagging the action events it emits with `source`
+pub fn execute_from(
+    app: &AppHandle,
+    command: &RemoteCommand,
+    source: Option<&str>,
+) -> Result<(), String> {
+    match command {
+        RemoteCommand::Action(id) => emit_action(app, id, source),
+        RemoteCommand::SetVisible(_) | RemoteCommand::SetMode(_) => {
+            apply(&AppOverlay::new(app), command)
+        }
+        RemoteCommand::SwitchProfile(name) => {
+            let app = app.clone();
+            let name = name.clone();
+            tauri::async_runtime::spawn(async move {
+                match crate::persistence::profiles::switch_profile(app, name.clone()).await {
+                    Ok(result) if result.success => {
+                        log::info!("Remote: switched to profile '{}'", name)
+                    }
+                    Ok(result) => log::warn!(
+                        "Remote: failed to switch to profile '{}': {}",
+                        name,
+                        result.error.unwrap_or_default()
+                    ),
+                    Err(e) => log::warn!("Remote: failed to switch to profile '{}': {}", name, e),
+                }
+            });
+            Ok(())
+        }
+    }
+}
+
+/// Apply a visibility or mode command (other commands are left to `execute`).
+/// The change is complete when this returns.
+pub fn apply(control: &impl OverlayControl, command: &RemoteCommand) -> Result<(), String> {
+    let _guard = OVERLAY_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let state = control.state();
+    match command {
+        RemoteCommand::SetVisible(visible) => {
+            if state.is_visible() == *visible {
+                log::debug!("Remote: overlay already {}", visibility_label(*visible));
+                return Ok(());
+            }
+            control.toggle_visibility()?;
+            // Showing is refused when the target window is missing or not focused
+            if state.is_visible() != *visible {
+                return Err(format!(
+                    "Overlay is still {}",
+                    visibility_label(state.is_visible())
+                ));
+            }
+            Ok(())
+        }
+        RemoteCommand::SetMode(mode) => {
+            // Same rule as the F5 hotkey: mode changes only apply while the overlay is shown
+            if !state.is_visible() {
+                return Err("Overlay is hidden, mode change ignored".to_string());
+            }
+            if state.get_mode() == *mode {
+                log::debug!("Remote: overlay already in {} mode", mode_label(*mode));
+                return Ok(());
+            }
+            control.toggle_mode()
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Handle the arguments of a second launch (argv[0] is the program path).
+/// Returns false when there is nothing to execute, so the caller focuses the window.
+pub fn handle_second_instance(app: &AppHandle, argv: &[String]) -> bool {
+    let args = argv.get(1..).unwrap_or_default();
+    if args.is_empty() {
+        return false;
+    }
+
+    let commands = match parse_args(args) {
+        Ok(commands) => commands,
+        Err(e) => {
+            log::warn!("Remote: {}\n{}", e, USAGE);
+            return true;
+        }
+    };
+
+    for command in &commands {
+        log::info!("Remote: {:?} requested (command line)", command);
+        if let Err(e) = execute(app, command) {
+            log::warn!("Remote: {:?} failed: {}", command, e);
+        }
+    }
+    true
+}
+
+fn emit_action(app: &AppHandle, id: &str, source: Option<&str>) -> Result<(), String> {
+    let action = registered_actions()
+        .into_iter()
+        .find(|action| action.id == id)
+        .ok_or_else(|| format!("Unknown hotkey action '{}'", id))?;
+    let result = match source {
+        Some(source) => app.emit(
+            &action.event,
+            ActionSource {
+                source: source.to_string(),
+            },
+        ),
+        None => app.emit(&action.event, ()),
+    };
+    result.map_err(|e| format!("Failed to emit {} event: {}", action.event, e))
+}
+
+fn visibility_label(visible: bool) -> &'static str {
+    if visible {
+        "visible"
+    } else {
+        "hidden"
+    }
+}
+
+fn mode_label(mode: OverlayMode) -> &'static str {
+    match mode {
+        OverlayMode::Windowed => "interactive",
+        OverlayMode::Fullscreen => "click-through",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply_all(overlay: &MockOverlay, args: &[&str]) -> Result<(), String> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        parse_args(&args)?
+            .iter()
+            .try_for_each(|command| apply(overlay, command))
+    }
+
+    #[test]
+    fn test_commands_apply_in_order() {
+        let overlay = MockOverlay::default();
+        apply_all(&overlay, &["--show", "--mode", "interactive"]).unwrap();
+        assert!(overlay.state.is_visible());
+        assert_eq!(overlay.state.get_mode(), OverlayMode::Windowed);
+
+        // Already shown: the second --show does not toggle it off again
+        apply_all(&overlay, &["--show", "--show"]).unwrap();
+        assert!(overlay.state.is_visible());
+        assert_eq!(overlay.state.get_mode(), OverlayMode::Windowed);
+
+        apply_all(&overlay, &["--hide"]).unwrap();
+        assert!(!overlay.state.is_visible());
+        assert!(apply_all(&overlay, &["--mode", "interactive"]).is_err());
+    }
+}
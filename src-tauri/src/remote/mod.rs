@@ -0,0 +1,13 @@
+// Remote control module - drive the running overlay from outside the app
+// (Stream Deck, AutoHotkey, scripts, companion apps) without additional global key hooks
+
+pub mod cli;
+pub mod control_api;
+pub mod dispatch;
+pub mod server;
+
+// Re-exports for public API
+pub use cli::*;
+pub use control_api::*;
+pub use dispatch::*;
+pub use server::*;
@@ -0,0 +1,623 @@
+// Local control API server - lets companion apps and stream tools read and drive the overlay
+// Plain HTTP on 127.0.0.1 only; every request must carry the token from [control_api].
+//
+//   GET  /state         OverlayStateResponse (visibility, mode, target status)
+//   GET  /actions       Registered hotkey actions
+//   POST /actions/{id}  Trigger an action, same as pressing its hotkey
+//   GET  /events        Server-Sent Events stream (target-process-detected, mode-changed, ...)
+//
+// The token is sent as `Authorization: Bearer <token>`, or as `?token=<token>` for
+// EventSource clients that cannot set headers. The server knows nothing about Tauri:
+// state and actions come from a ControlBackend, events are pushed through publish().
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::core::types::OverlayStateResponse;
+use crate::hotkey::registry::HotkeyAction;
+
+/// Requests larger than this (request line, headers and body) are rejected
+const MAX_REQUEST_BYTES: u64 = 16 * 1024;
+
+/// Time a client has to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Interval of keep-alive comments on idle event streams (also detects closed clients)
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Source of the state and actions exposed by the server
+pub trait ControlBackend: Send + Sync + 'static {
+    /// Current overlay state
+    fn state(&self) -> OverlayStateResponse;
+    /// Actions that can be triggered
+    fn actions(&self) -> Vec<HotkeyAction>;
+    /// Trigger an action by ID (the ID is known to be in `actions()`)
+    fn trigger(&self, action: &str) -> Result<(), String>;
+}
+
+/// Fan-out of events to the connected /events clients
+#[derive(Default)]
+struct EventHub {
+    subscribers: Mutex<Vec<Sender<String>>>,
+}
+
+impl EventHub {
+    fn subscribe(&self) -> Receiver<String> {
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(tx);
+        }
+        rx
+    }
+
+    fn publish(&self, event: &str, payload: &str) {
+        // Each line of the payload needs its own "data:" prefix
+        let data = payload.lines().collect::<Vec<_>>().join("\ndata: ");
+        let frame = format!("event: {}\ndata: {}\n\n", event, data);
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|tx| tx.send(frame.clone()).is_ok());
+        }
+    }
+
+    /// Drop every subscriber, ending their streams
+    fn close(&self) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.clear();
+        }
+    }
+}
+
+/// A running control API server. Stops when shut down or dropped.
+pub struct ControlServer {
+    addr: SocketAddr,
+    events: Arc<EventHub>,
+    stopped: Arc<AtomicBool>,
+    /// Thread owning the TcpListener, joined on shutdown so the port is free again
+    listener_thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ControlServer {
+    /// Bind 127.0.0.1:`port` (0 = any free port) and serve requests on a background thread.
+    pub fn start(port: u16, token: &str, backend: Arc<dyn ControlBackend>) -> Result<Self, String> {
+        if token.is_empty() {
+            return Err("A token is required".to_string());
+        }
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .map_err(|e| format!("Failed to bind 127.0.0.1:{}: {}", port, e))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to read server address: {}", e))?;
+
+        let events = Arc::new(EventHub::default());
+        let stopped = Arc::new(AtomicBool::new(false));
+        let context = Arc::new(Context {
+            token: token.to_string(),
+            backend,
+            events: events.clone(),
+            stopped: stopped.clone(),
+        });
+        let listener_thread = thread::spawn(move || {
+            for stream in listener.incoming() {
+                if context.stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let context = context.clone();
+                thread::spawn(move || handle_connection(stream, &context));
+            }
+            log::debug!("Control API listener on {} stopped", addr);
+        });
+
+        Ok(Self {
+            addr,
+            events,
+            stopped,
+            listener_thread: Mutex::new(Some(listener_thread)),
+        })
+    }
+
+    /// Address the server listens on
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Send an event to every /events client. `payload` is JSON text.
+    pub fn publish(&self, event: &str, payload: &str) {
+        self.events.publish(event, payload);
+    }
+
+    /// Stop accepting connections and end all event streams. The port is released when
+    /// this returns, so a new server can bind it right away.
+    pub fn shutdown(&self) {
+        if self.stopped.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        self.events.close();
+        // Wake the blocking accept so the listener thread sees the flag
+        if let Err(e) = TcpStream::connect(self.addr) {
+            log::warn!(
+                "Failed to stop control API listener on {}: {}",
+                self.addr,
+                e
+            );
+            return;
+        }
+        let listener_thread = self
+            .listener_thread
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .take();
+        if let Some(listener_thread) = listener_thread {
+            let _ = listener_thread.join();
+        }
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Shared by the connection threads
+struct Context {
+    token: String,
+    backend: Arc<dyn ControlBackend>,
+    events: Arc<EventHub>,
+    stopped: Arc<AtomicBool>,
+}
+
+/// A parsed HTTP request (the body is read and discarded)
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    headers: Vec<(String, String)>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Token from the Authorization header or the `token` query parameter
+    fn token(&self) -> Option<String> {
+        if let Some(token) = self
+            .header("Authorization")
+            .and_then(|v| v.strip_prefix("Bearer "))
+        {
+            return Some(token.trim().to_string());
+        }
+        self.query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+            .map(percent_decode)
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    error: &'a str,
+}
+
+#[derive(Serialize)]
+struct TriggeredBody<'a> {
+    action: &'a str,
+}
+
+fn handle_connection(mut stream: TcpStream, context: &Context) {
+    let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+
+    let request = match read_request(&stream) {
+        Ok(request) => request,
+        Err(e) => {
+            log::debug!("Control API: bad request: {}", e);
+            let _ = write_error(&mut stream, 400, &e);
+            return;
+        }
+    };
+
+    if request.method == "OPTIONS" {
+        // CORS preflight for browser-based tools (the token still guards every request)
+        let _ = write_response(&mut stream, 204, None);
+        return;
+    }
+
+    let authorized = request
+        .token()
+        .is_some_and(|token| token_matches(&context.token, &token));
+    if !authorized {
+        log::warn!(
+            "Control API: rejected {} {} (missing or invalid token)",
+            request.method,
+            request.path
+        );
+        let _ = write_error(&mut stream, 401, "Missing or invalid token");
+        return;
+    }
+
+    log::debug!("Control API: {} {}", request.method, request.path);
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/state") => write_json(&mut stream, 200, &context.backend.state()),
+        ("GET", "/actions") => write_json(&mut stream, 200, &context.backend.actions()),
+        ("GET", "/events") => stream_events(&mut stream, context),
+        ("POST", path) if path.starts_with("/actions/") => {
+            let id = percent_decode(&path["/actions/".len()..]);
+            trigger_action(&mut stream, context, &id)
+        }
+        (_, "/state" | "/actions" | "/events") => {
+            write_error(&mut stream, 405, "Method not allowed")
+        }
+        _ => write_error(&mut stream, 404, "Not found"),
+    };
+    if let Err(e) = result {
+        log::debug!("Control API: failed to write response: {}", e);
+    }
+}
+
+fn trigger_action(stream: &mut TcpStream, context: &Context, id: &str) -> std::io::Result<()> {
+    if !context.backend.actions().iter().any(|a| a.id == id) {
+        return write_error(stream, 404, &format!("Unknown action '{}'", id));
+    }
+    match context.backend.trigger(id) {
+        Ok(()) => {
+            log::info!("Control API: {} requested", id);
+            write_json(stream, 202, &TriggeredBody { action: id })
+        }
+        Err(e) => write_error(stream, 500, &e),
+    }
+}
+
+/// Keep the connection open and forward published events until either side closes
+fn stream_events(stream: &mut TcpStream, context: &Context) -> std::io::Result<()> {
+    let events = context.events.subscribe();
+    stream.write_all(
+        b"HTTP/1.1 200 OK\r\n\
+          Content-Type: text/event-stream\r\n\
+          Cache-Control: no-cache\r\n\
+          Access-Control-Allow-Origin: *\r\n\
+          Connection: close\r\n\r\n\
+          : connected\n\n",
+    )?;
+    stream.flush()?;
+
+    loop {
+        let frame = match events.recv_timeout(KEEPALIVE_INTERVAL) {
+            Ok(frame) => frame,
+            Err(RecvTimeoutError::Timeout) => ": keepalive\n\n".to_string(),
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        };
+        if context.stopped.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        stream.write_all(frame.as_bytes())?;
+        stream.flush()?;
+    }
+}
+
+fn read_request(stream: &TcpStream) -> Result<Request, String> {
+    let mut reader = BufReader::new(stream.take(MAX_REQUEST_BYTES));
+    let mut line = String::new();
+
+    reader
+        .read_line(&mut line)
+        .map_err(|e| format!("Failed to read request: {}", e))?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err("Malformed request line".to_string());
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query.to_string(),
+        headers: Vec::new(),
+    };
+
+    loop {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|e| format!("Failed to read headers: {}", e))?;
+        if read == 0 {
+            return Err("Request ended before the headers did".to_string());
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| format!("Malformed header '{}'", header))?;
+        request
+            .headers
+            .push((name.trim().to_string(), value.trim().to_string()));
+    }
+
+    // Bodies are not used by any endpoint, but must be consumed before responding
+    let length: u64 = request
+        .header("Content-Length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    std::io::copy(&mut reader.take(length), &mut std::io::sink())
+        .map_err(|e| format!("Failed to read body: {}", e))?;
+
+    Ok(request)
+}
+
+fn write_response(stream: &mut TcpStream, status: u16, body: Option<&str>) -> std::io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    let body = body.unwrap_or("");
+    let content_type = if body.is_empty() {
+        ""
+    } else {
+        "Content-Type: application/json\r\n"
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\n{}Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Access-Control-Allow-Headers: Authorization\r\n\
+         Access-Control-Allow-Methods: GET, POST\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        reason,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+fn write_json<T: Serialize>(stream: &mut TcpStream, status: u16, value: &T) -> std::io::Result<()> {
+    match serde_json::to_string(value) {
+        Ok(body) => write_response(stream, status, Some(&body)),
+        Err(e) => write_error(stream, 500, &format!("Failed to serialize response: {}", e)),
+    }
+}
+
+fn write_error(stream: &mut TcpStream, status: u16, message: &str) -> std::io::Result<()> {
+    let body = serde_json::to_string(&ErrorBody { error: message }).unwrap_or_default();
+    write_response(stream, status, Some(&body))
+}
+
+/// Compare tokens without an early exit on the first differing byte
+fn token_matches(expected: &str, given: &str) -> bool {
+    !expected.is_empty()
+        && expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Decode %XX escapes (and '+' as space) of a URL component
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'%' => {
+                let hex = value.get(index + 1..index + 3);
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        index += 3;
+                        continue;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            b'+' => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hotkey::registry::{builtin_actions, TOGGLE_VISIBILITY};
+
+    const TOKEN: &str = "0123456789abcdef";
+
+    /// Backend that records triggered actions
+    #[derive(Default)]
+    struct MockBackend {
+        triggered: Mutex<Vec<String>>,
+    }
+
+    impl ControlBackend for MockBackend {
+        fn state(&self) -> OverlayStateResponse {
+            OverlayStateResponse {
+                visible: true,
+                initialized: true,
+                mode: "windowed".to_string(),
+                target_bound: true,
+                target_name: "Star Citizen".to_string(),
+                target_rect: None,
+                auto_hidden: false,
+            }
+        }
+
+        fn actions(&self) -> Vec<HotkeyAction> {
+            builtin_actions()
+        }
+
+        fn trigger(&self, action: &str) -> Result<(), String> {
+            self.triggered.lock().unwrap().push(action.to_string());
+            Ok(())
+        }
+    }
+
+    fn start() -> (ControlServer, Arc<MockBackend>) {
+        let backend = Arc::new(MockBackend::default());
+        let server = ControlServer::start(0, TOKEN, backend.clone()).unwrap();
+        (server, backend)
+    }
+
+    /// Send a request and return (status, body)
+    fn request(
+        server: &ControlServer,
+        method: &str,
+        target: &str,
+        token: Option<&str>,
+    ) -> (u16, String) {
+        let mut stream = TcpStream::connect(server.addr()).unwrap();
+        let auth = token
+            .map(|t| format!("Authorization: Bearer {}\r\n", t))
+            .unwrap_or_default();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\n{}Content-Length: 0\r\n\r\n",
+            method, target, auth
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body.to_string())
+            .unwrap_or_default();
+        (status, body)
+    }
+
+    #[test]
+    fn test_token_required() {
+        let (server, _) = start();
+        assert_eq!(request(&server, "GET", "/state", None).0, 401);
+        assert_eq!(request(&server, "GET", "/state", Some("wrong")).0, 401);
+        assert_eq!(
+            request(&server, "GET", &format!("/state?token={}", TOKEN), None).0,
+            200
+        );
+        assert!(ControlServer::start(0, "", Arc::new(MockBackend::default())).is_err());
+    }
+
+    #[test]
+    fn test_state_and_actions() {
+        let (server, backend) = start();
+
+        let (status, body) = request(&server, "GET", "/state", Some(TOKEN));
+        assert_eq!(status, 200);
+        let state: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(state["targetName"], "Star Citizen");
+        assert_eq!(state["mode"], "windowed");
+
+        let (status, body) = request(&server, "GET", "/actions", Some(TOKEN));
+        assert_eq!(status, 200);
+        let actions: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(actions[0]["id"], TOGGLE_VISIBILITY);
+
+        let target = format!("/actions/{}", TOGGLE_VISIBILITY);
+        assert_eq!(request(&server, "POST", &target, Some(TOKEN)).0, 202);
+        assert_eq!(
+            request(&server, "POST", "/actions/selfDestruct", Some(TOKEN)).0,
+            404
+        );
+        assert_eq!(request(&server, "GET", &target, Some(TOKEN)).0, 404);
+        assert_eq!(request(&server, "POST", "/state", Some(TOKEN)).0, 405);
+        assert_eq!(*backend.triggered.lock().unwrap(), vec![TOGGLE_VISIBILITY]);
+    }
+
+    #[test]
+    fn test_event_stream() {
+        let (server, _) = start();
+
+        let mut stream = TcpStream::connect(server.addr()).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        write!(
+            stream,
+            "GET /events?token={} HTTP/1.1\r\nHost: localhost\r\n\r\n",
+            TOKEN
+        )
+        .unwrap();
+        let mut reader = BufReader::new(stream);
+
+        // Read up to the "connected" comment, so the subscription is in place
+        let mut line = String::new();
+        while line != ": connected\n" {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+        }
+
+        server.publish(
+            "mode-changed",
+            r#"{"previousMode":"fullscreen","currentMode":"windowed"}"#,
+        );
+        let mut frame = Vec::new();
+        for _ in 0..3 {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+            frame.push(line.clone());
+        }
+        assert_eq!(
+            frame,
+            vec![
+                "\n",
+                "event: mode-changed\n",
+                "data: {\"previousMode\":\"fullscreen\",\"currentMode\":\"windowed\"}\n"
+            ]
+        );
+
+        // Shutting down ends the stream
+        server.shutdown();
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "\n");
+    }
+
+    #[test]
+    fn test_restart_on_same_port() {
+        let (mut server, backend) = start();
+        let port = server.addr().port();
+
+        // A reload with a new token restarts the server right away on the same port
+        for _ in 0..20 {
+            server.shutdown();
+            server = ControlServer::start(port, TOKEN, backend.clone()).unwrap();
+            assert_eq!(request(&server, "GET", "/state", Some(TOKEN)).0, 200);
+        }
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert!(token_matches("secret", "secret"));
+        assert!(!token_matches("secret", "secreT"));
+        assert!(!token_matches("secret", "secret2"));
+        assert!(!token_matches("", ""));
+        assert_eq!(percent_decode("a%2Bb+c%zz"), "a+b c%zz");
+    }
+}
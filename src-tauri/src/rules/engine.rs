@@ -0,0 +1,322 @@
+// Rule engine - parses rules.toml and decides which rules an event starts
+// Pure functions: the service supplies the event and the overlay state, and runs the
+// actions of the matching rules.
+
+use std::collections::HashSet;
+
+use super::types::*;
+
+/// Parse rules.toml. Invalid rules are left out and reported with the parse errors;
+/// hotkey triggers must name one of `known_actions`.
+pub fn load_rules(contents: &str, known_actions: &[String]) -> RulesInfo {
+    let file: RulesFile = match toml::from_str(contents) {
+        Ok(file) => file,
+        Err(e) => {
+            return RulesInfo {
+                errors: vec![format!("Failed to parse rules.toml: {}", e)],
+                ..Default::default()
+            }
+        }
+    };
+
+    let mut info = RulesInfo::default();
+    let mut names = HashSet::new();
+    for (index, rule) in file.rules.into_iter().enumerate() {
+        let problems = validate_rule(&rule, known_actions);
+        let label = if rule.name.trim().is_empty() {
+            format!("Rule #{}", index + 1)
+        } else {
+            format!("Rule '{}'", rule.name)
+        };
+
+        if !problems.is_empty() {
+            info.errors
+                .push(format!("{}: {}", label, problems.join("; ")));
+        } else if !names.insert(rule.name.to_lowercase()) {
+            info.errors
+                .push(format!("{}: another rule has the same name", label));
+        } else {
+            info.rules.push(rule);
+        }
+    }
+    info
+}
+
+fn validate_rule(rule: &Rule, known_actions: &[String]) -> Vec<String> {
+    let mut problems = Vec::new();
+    if rule.name.trim().is_empty() {
+        problems.push("name is empty".to_string());
+    }
+    if rule.then.is_empty() {
+        problems.push("no actions".to_string());
+    }
+
+    match &rule.when {
+        RuleTrigger::Hotkey { action } if !known_actions.contains(action) => {
+            problems.push(format!("unknown hotkey action '{}'", action))
+        }
+        RuleTrigger::TimeOfDay { at } if parse_time_of_day(at).is_none() => {
+            problems.push(format!("time '{}' is not HH:MM", at))
+        }
+        _ => {}
+    }
+
+    for action in &rule.then {
+        match action {
+            RuleAction::OpenUrl { url }
+                if !(url.starts_with("https://") || url.starts_with("http://")) =>
+            {
+                problems.push(format!("URL '{}' must start with http:// or https://", url))
+            }
+            RuleAction::SwitchProfile { profile } if profile.trim().is_empty() => {
+                problems.push("profile name is empty".to_string())
+            }
+            RuleAction::StartTimer { timer } if timer.trim().is_empty() => {
+                problems.push("timer ID is empty".to_string())
+            }
+            _ => {}
+        }
+    }
+    problems
+}
+
+/// Parse "HH:MM" (24-hour) into (hour, minute)
+pub fn parse_time_of_day(value: &str) -> Option<(u32, u32)> {
+    let (hour, minute) = value.trim().split_once(':')?;
+    if minute.len() != 2 {
+        return None;
+    }
+    let hour: u32 = hour.parse().ok()?;
+    let minute: u32 = minute.parse().ok()?;
+    (hour < 24 && minute < 60).then_some((hour, minute))
+}
+
+// None matches anything; names compare case-insensitively
+fn name_matches(expected: &Option<String>, actual: &str) -> bool {
+    expected
+        .as_deref()
+        .is_none_or(|expected| expected.eq_ignore_ascii_case(actual))
+}
+
+fn trigger_matches(trigger: &RuleTrigger, event: &RuleEvent) -> bool {
+    match (trigger, event) {
+        (RuleTrigger::ProcessDetected { profile }, RuleEvent::ProcessDetected { profile: p })
+        | (
+            RuleTrigger::ProcessTerminated { profile },
+            RuleEvent::ProcessTerminated { profile: p },
+        ) => name_matches(profile, p),
+        (RuleTrigger::FocusGained, RuleEvent::FocusGained)
+        | (RuleTrigger::FocusLost, RuleEvent::FocusLost) => true,
+        (RuleTrigger::Hotkey { action }, RuleEvent::Hotkey { action: a }) => action == a,
+        (RuleTrigger::TimeOfDay { at }, RuleEvent::TimeOfDay { at: now }) => {
+            parse_time_of_day(at).is_some() && parse_time_of_day(at) == parse_time_of_day(now)
+        }
+        (RuleTrigger::Alarm { label }, RuleEvent::Alarm { label: l }) => name_matches(label, l),
+        _ => false,
+    }
+}
+
+fn condition_holds(condition: &Option<RuleCondition>, context: &RuleContext) -> bool {
+    let Some(condition) = condition else {
+        return true;
+    };
+    condition.visible.is_none_or(|v| v == context.visible)
+        && condition.mode.is_none_or(|m| m == context.mode)
+        && condition
+            .process_running
+            .is_none_or(|r| r == context.process_running)
+        && name_matches(&condition.profile, &context.profile)
+}
+
+/// The enabled rules whose trigger matches `event`, in file order, with whether
+/// their condition holds in `context`
+pub fn evaluate(rules: &[Rule], event: &RuleEvent, context: &RuleContext) -> Vec<RuleEvaluation> {
+    rules
+        .iter()
+        .filter(|rule| rule.enabled && trigger_matches(&rule.when, event))
+        .map(|rule| RuleEvaluation {
+            rule: rule.name.clone(),
+            condition_met: condition_holds(&rule.condition, context),
+            actions: rule.then.clone(),
+            descriptions: rule.then.iter().map(ToString::to_string).collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = r#"
+[[rules]]
+name = "PTU layout"
+when = { trigger = "process-detected", profile = "ptu" }
+then = [{ action = "switch-profile", profile = "testing" }, { action = "show" }]
+
+[[rules]]
+name = "Hide when game closes"
+when = { trigger = "process-terminated" }
+if = { visible = true }
+then = [{ action = "hide" }]
+
+[[rules]]
+name = "Refinery"
+when = { trigger = "alarm", label = "Refinery" }
+if = { mode = "click-through", profile = "mining" }
+then = [{ action = "open-url", url = "https://example.com/refinery" }]
+
+[[rules]]
+name = "Evening"
+enabled = false
+when = { trigger = "time-of-day", at = "20:00" }
+then = [{ action = "start-timer", timer = "chronometer" }]
+"#;
+
+    fn known_actions() -> Vec<String> {
+        vec![
+            "toggleVisibility".to_string(),
+            "chronometerReset".to_string(),
+        ]
+    }
+
+    fn context() -> RuleContext {
+        RuleContext {
+            visible: true,
+            mode: RuleMode::Interactive,
+            process_running: true,
+            profile: "default".to_string(),
+        }
+    }
+
+    fn matched(info: &RulesInfo, event: &RuleEvent, context: &RuleContext) -> Vec<(String, bool)> {
+        evaluate(&info.rules, event, context)
+            .into_iter()
+            .map(|e| (e.rule, e.condition_met))
+            .collect()
+    }
+
+    #[test]
+    fn test_load_rules() {
+        let info = load_rules(RULES, &known_actions());
+        assert!(info.errors.is_empty(), "{:?}", info.errors);
+        assert_eq!(info.rules.len(), 4);
+        assert_eq!(
+            info.rules[0].then,
+            vec![
+                RuleAction::SwitchProfile {
+                    profile: "testing".to_string()
+                },
+                RuleAction::Show
+            ]
+        );
+        assert!(!info.rules[3].enabled);
+
+        let info = load_rules("[[rules]]\nname = 1", &known_actions());
+        assert!(info.rules.is_empty());
+        assert_eq!(info.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_rules_are_skipped() {
+        let contents = r#"
+[[rules]]
+name = "Bad time"
+when = { trigger = "time-of-day", at = "25:00" }
+then = [{ action = "show" }]
+
+[[rules]]
+name = "Bad hotkey"
+when = { trigger = "hotkey", action = "nope" }
+then = [{ action = "open-url", url = "file:///etc/passwd" }]
+
+[[rules]]
+name = "Good"
+when = { trigger = "hotkey", action = "chronometerReset" }
+then = [{ action = "hide" }]
+
+[[rules]]
+name = "good"
+when = { trigger = "focus-lost" }
+then = [{ action = "hide" }]
+
+[[rules]]
+name = "Nothing to do"
+when = { trigger = "focus-gained" }
+then = []
+"#;
+        let info = load_rules(contents, &known_actions());
+        let names: Vec<&str> = info.rules.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["Good"]);
+        assert_eq!(info.errors.len(), 4);
+        assert!(info.errors[0].contains("'25:00' is not HH:MM"));
+        assert!(info.errors[1].contains("unknown hotkey action 'nope'"));
+        assert!(info.errors[1].contains("http://"));
+        assert!(info.errors[2].contains("same name"));
+        assert!(info.errors[3].contains("no actions"));
+    }
+
+    #[test]
+    fn test_evaluate_triggers_and_conditions() {
+        let info = load_rules(RULES, &known_actions());
+        let mut context = context();
+
+        // Profile names compare case-insensitively
+        let detected = RuleEvent::ProcessDetected {
+            profile: "PTU".to_string(),
+        };
+        assert_eq!(
+            matched(&info, &detected, &context),
+            vec![("PTU layout".to_string(), true)]
+        );
+        let live = RuleEvent::ProcessDetected {
+            profile: "LIVE".to_string(),
+        };
+        assert!(matched(&info, &live, &context).is_empty());
+
+        // Any profile; condition depends on visibility
+        let terminated = RuleEvent::ProcessTerminated {
+            profile: "LIVE".to_string(),
+        };
+        assert_eq!(
+            matched(&info, &terminated, &context),
+            vec![("Hide when game closes".to_string(), true)]
+        );
+        context.visible = false;
+        assert_eq!(
+            matched(&info, &terminated, &context),
+            vec![("Hide when game closes".to_string(), false)]
+        );
+
+        let alarm = RuleEvent::Alarm {
+            label: "refinery".to_string(),
+        };
+        assert_eq!(
+            matched(&info, &alarm, &context),
+            vec![("Refinery".to_string(), false)]
+        );
+        context.mode = RuleMode::Passthrough;
+        context.profile = "Mining".to_string();
+        let evaluations = evaluate(&info.rules, &alarm, &context);
+        assert!(evaluations[0].condition_met);
+        assert_eq!(
+            evaluations[0].descriptions,
+            vec!["open https://example.com/refinery"]
+        );
+
+        // Disabled rules never match
+        let evening = RuleEvent::TimeOfDay {
+            at: "20:00".to_string(),
+        };
+        assert!(matched(&info, &evening, &context).is_empty());
+    }
+
+    #[test]
+    fn test_parse_time_of_day() {
+        assert_eq!(parse_time_of_day("08:30"), Some((8, 30)));
+        assert_eq!(parse_time_of_day("8:30"), Some((8, 30)));
+        assert_eq!(parse_time_of_day("23:59"), Some((23, 59)));
+        assert_eq!(parse_time_of_day("24:00"), None);
+        assert_eq!(parse_time_of_day("12:5"), None);
+        assert_eq!(parse_time_of_day("noon"), None);
+    }
+}
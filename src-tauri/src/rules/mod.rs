@@ -0,0 +1,11 @@
+// Rules module - user-defined automation from rules.toml
+// "when <trigger> [if <condition>] then <actions>": the engine matches events against the
+// rules; the service feeds it backend events and runs the actions of matching rules.
+
+pub mod engine;
+pub mod service;
+pub mod types;
+
+// Re-exports for public API
+pub use service::*;
+pub use types::*;
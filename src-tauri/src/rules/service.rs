@@ -0,0 +1,383 @@
+// Rules service - runs the rules in rules.toml (next to settings.toml)
+// Listens for the backend events the triggers refer to, checks the time of day every
+// minute and reloads rules.toml when it changes. Actions go through the same paths as
+// the command-line remote control, the timer service and the browser window.
+// Rules run one at a time and show/hide/mode actions are applied before the next action
+// runs, so each condition and action sees the changes made before it.
+// Rules do not trigger each other: the events rule actions emit are tagged with
+// RULES_SOURCE and ignored as triggers (Tauri delivers them after the handler returns).
+
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use chrono::Timelike;
+use serde::Deserialize;
+use tauri::{AppHandle, Emitter, Listener, Manager};
+
+use super::engine::{evaluate, load_rules};
+use super::types::*;
+use crate::core::types::TargetFocusChangedPayload;
+use crate::core::OverlayState;
+use crate::hotkey::registry::registered_actions;
+use crate::platform::focus_monitor::TARGET_FOCUS_CHANGED_EVENT;
+use crate::remote::cli::RemoteCommand;
+use crate::remote::dispatch::{self, ActionSource};
+
+const RULES_FILE: &str = "rules.toml";
+
+/// Event asking the frontend to open a browser window on a URL
+pub const OPEN_BROWSER_URL_EVENT: &str = "open-browser-url";
+
+/// Source tag of the action events emitted by rules
+pub const RULES_SOURCE: &str = "rules";
+
+/// How often rules.toml and the time of day are checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Loaded rules
+static RULES: RwLock<Option<RulesInfo>> = RwLock::new(None);
+
+/// Payload fields of target-process-detected/terminated used by the triggers
+#[derive(Debug, Deserialize)]
+struct ProcessEvent {
+    profile_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlarmEvent {
+    label: String,
+}
+
+/// Path of rules.toml, next to settings.toml
+pub fn get_rules_path() -> Option<PathBuf> {
+    crate::settings::runtime::get_settings_path()?
+        .parent()
+        .map(|dir| dir.join(RULES_FILE))
+}
+
+fn modified_time(path: &Option<PathBuf>) -> Option<SystemTime> {
+    std::fs::metadata(path.as_ref()?).ok()?.modified().ok()
+}
+
+/// Read rules.toml (a missing file means no rules) and make its rules current
+fn reload() -> RulesInfo {
+    let path = get_rules_path();
+    let contents = path
+        .as_ref()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    let known_actions: Vec<String> = registered_actions().into_iter().map(|a| a.id).collect();
+
+    let mut info = load_rules(&contents, &known_actions);
+    info.path = path.map(|p| p.display().to_string());
+    for error in &info.errors {
+        log::warn!("rules.toml: {}", error);
+    }
+    log::info!("Loaded {} rule(s)", info.rules.len());
+
+    *RULES.write().unwrap_or_else(|p| p.into_inner()) = Some(info.clone());
+    info
+}
+
+/// Load rules.toml and start evaluating the rules (call once at startup)
+pub fn start_rules_engine(app: &AppHandle) {
+    reload();
+
+    listen(app, "target-process-detected", |payload| {
+        let event: ProcessEvent = serde_json::from_str(payload).ok()?;
+        Some(RuleEvent::ProcessDetected {
+            profile: event.profile_name,
+        })
+    });
+    listen(app, "target-process-terminated", |payload| {
+        let event: ProcessEvent = serde_json::from_str(payload).ok()?;
+        Some(RuleEvent::ProcessTerminated {
+            profile: event.profile_name,
+        })
+    });
+    listen(app, TARGET_FOCUS_CHANGED_EVENT, |payload| {
+        let event: TargetFocusChangedPayload = serde_json::from_str(payload).ok()?;
+        Some(if event.focused {
+            RuleEvent::FocusGained
+        } else {
+            RuleEvent::FocusLost
+        })
+    });
+    listen(app, crate::alarms::ALARM_FIRED_EVENT, |payload| {
+        let event: AlarmEvent = serde_json::from_str(payload).ok()?;
+        Some(RuleEvent::Alarm { label: event.label })
+    });
+    for action in registered_actions() {
+        let id = action.id.clone();
+        listen(app, &action.event, move |_| {
+            Some(RuleEvent::Hotkey { action: id.clone() })
+        });
+    }
+
+    // Time-of-day triggers and changes to rules.toml
+    let app = app.clone();
+    thread::spawn(move || {
+        let path = get_rules_path();
+        let mut last_modified = modified_time(&path);
+        let mut last_minute = None;
+        loop {
+            thread::sleep(CHECK_INTERVAL);
+
+            let modified = modified_time(&path);
+            if modified != last_modified {
+                last_modified = modified;
+                log::info!("rules.toml changed, reloading");
+                reload();
+            }
+
+            let now = chrono::Local::now();
+            let minute = (now.hour(), now.minute());
+            if last_minute.is_some_and(|last| last != minute) {
+                let at = format!("{:02}:{:02}", minute.0, minute.1);
+                handle_event(&app, &RuleEvent::TimeOfDay { at });
+            }
+            last_minute = Some(minute);
+        }
+    });
+}
+
+fn listen(
+    app: &AppHandle,
+    event: &str,
+    to_rule_event: impl Fn(&str) -> Option<RuleEvent> + Send + 'static,
+) {
+    let handle = app.clone();
+    app.listen_any(event.to_string(), move |e| {
+        if let Some(rule_event) = incoming_event(e.payload(), &to_rule_event) {
+            handle_event(&handle, &rule_event);
+        }
+    });
+}
+
+/// The rule event for a backend event, or None when a rule action emitted it
+fn incoming_event(
+    payload: &str,
+    to_rule_event: impl Fn(&str) -> Option<RuleEvent>,
+) -> Option<RuleEvent> {
+    let from_rules = serde_json::from_str::<ActionSource>(payload)
+        .is_ok_and(|action| action.source == RULES_SOURCE);
+    if from_rules {
+        return None;
+    }
+    to_rule_event(payload)
+}
+
+/// Current overlay state for the rule conditions
+fn current_context(app: &AppHandle) -> RuleContext {
+    let state = app.state::<OverlayState>();
+    RuleContext {
+        visible: state.is_visible(),
+        mode: state.get_mode().into(),
+        process_running: crate::commands::is_target_process_running(),
+        profile: crate::persistence::profiles::get_active_profile(app).unwrap_or_default(),
+    }
+}
+
+fn current_rules() -> Vec<Rule> {
+    RULES
+        .read()
+        .ok()
+        .and_then(|rules| rules.as_ref().map(|info| info.rules.clone()))
+        .unwrap_or_default()
+}
+
+fn handle_event(app: &AppHandle, event: &RuleEvent) {
+    run_rules(
+        &current_rules(),
+        event,
+        || current_context(app),
+        |action| run_action(app, action),
+    );
+}
+
+/// Evaluate and run the rules in order. The context is read again for every rule, so
+/// its condition sees what the actions of the rules before it changed.
+fn run_rules(
+    rules: &[Rule],
+    event: &RuleEvent,
+    context: impl Fn() -> RuleContext,
+    run: impl Fn(&RuleAction) -> Result<(), String>,
+) {
+    for rule in rules {
+        for evaluation in evaluate(std::slice::from_ref(rule), event, &context()) {
+            if !evaluation.condition_met {
+                log::debug!("Rule '{}': condition not met", evaluation.rule);
+                continue;
+            }
+            log::info!(
+                "Rule '{}' triggered: {}",
+                evaluation.rule,
+                evaluation.descriptions.join(", ")
+            );
+
+            for action in &evaluation.actions {
+                if let Err(e) = run(action) {
+                    log::warn!("Rule '{}': failed to {}: {}", evaluation.rule, action, e);
+                }
+            }
+        }
+    }
+}
+
+/// The remote command behind a show, hide or set-mode action
+fn overlay_command(action: &RuleAction) -> Option<RemoteCommand> {
+    match action {
+        RuleAction::Show => Some(RemoteCommand::SetVisible(true)),
+        RuleAction::Hide => Some(RemoteCommand::SetVisible(false)),
+        RuleAction::SetMode { mode } => Some(RemoteCommand::SetMode((*mode).into())),
+        _ => None,
+    }
+}
+
+fn run_action(app: &AppHandle, action: &RuleAction) -> Result<(), String> {
+    let execute = |command| dispatch::execute_from(app, &command, Some(RULES_SOURCE));
+    if let Some(command) = overlay_command(action) {
+        // Applied before this returns
+        return execute(command);
+    }
+    match action {
+        RuleAction::Show | RuleAction::Hide | RuleAction::SetMode { .. } => Ok(()),
+        RuleAction::SwitchProfile { profile } => {
+            execute(RemoteCommand::SwitchProfile(profile.clone()))
+        }
+        RuleAction::StartTimer { timer } => crate::timers::start_timer(timer.clone()).map(|_| ()),
+        RuleAction::OpenUrl { url } => app
+            .emit(OPEN_BROWSER_URL_EVENT, OpenUrlPayload { url: url.clone() })
+            .map_err(|e| format!("Failed to emit {} event: {}", OPEN_BROWSER_URL_EVENT, e)),
+    }
+}
+
+// ============================================================================
+// Tauri commands
+// ============================================================================
+
+/// The loaded rules and the problems found in rules.toml
+#[tauri::command]
+pub fn list_rules() -> RulesInfo {
+    RULES
+        .read()
+        .ok()
+        .and_then(|rules| rules.clone())
+        .unwrap_or_default()
+}
+
+/// Re-read rules.toml now
+#[tauri::command]
+pub fn reload_rules() -> RulesInfo {
+    reload()
+}
+
+/// Show which rules an event would trigger and whether their conditions hold, without
+/// running any action. The current overlay state is used unless `context` is given.
+#[tauri::command]
+pub fn dry_run_rules(
+    app: AppHandle,
+    event: RuleEvent,
+    context: Option<RuleContext>,
+) -> DryRunResult {
+    let context = context.unwrap_or_else(|| current_context(&app));
+    let evaluations = evaluate(&current_rules(), &event, &context);
+    DryRunResult {
+        context,
+        evaluations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::engine::{evaluate, load_rules};
+    use super::*;
+    use crate::core::types::OverlayMode;
+    use crate::remote::dispatch::MockOverlay;
+
+    #[test]
+    fn test_rule_actions_do_not_retrigger_rules() {
+        let contents = r#"
+[[rules]]
+name = "Show on toggle"
+when = { trigger = "hotkey", action = "toggleVisibility" }
+if = { visible = false }
+then = [{ action = "show" }]
+"#;
+        let info = load_rules(contents, &["toggleVisibility".to_string()]);
+        let context = RuleContext {
+            visible: false,
+            mode: RuleMode::Interactive,
+            process_running: true,
+            profile: "default".to_string(),
+        };
+        let to_rule_event = |_: &str| {
+            Some(RuleEvent::Hotkey {
+                action: "toggleVisibility".to_string(),
+            })
+        };
+
+        // A key press (no payload) triggers the rule
+        let pressed = incoming_event("null", to_rule_event).unwrap();
+        assert_eq!(evaluate(&info.rules, &pressed, &context).len(), 1);
+
+        // The toggle-visibility event emitted by its "show" action does not
+        let emitted = serde_json::to_string(&ActionSource {
+            source: RULES_SOURCE.to_string(),
+        })
+        .unwrap();
+        assert_eq!(incoming_event(&emitted, to_rule_event), None);
+
+        // Events tagged by other sources still count
+        let other = r#"{"source":"stream-deck"}"#;
+        assert!(incoming_event(other, to_rule_event).is_some());
+    }
+
+    #[test]
+    fn test_actions_apply_before_the_next_rule() {
+        let contents = r#"
+[[rules]]
+name = "Interactive on launch"
+when = { trigger = "process-detected" }
+then = [{ action = "show" }, { action = "set-mode", mode = "interactive" }]
+
+[[rules]]
+name = "Show on launch"
+when = { trigger = "process-detected" }
+then = [{ action = "show" }]
+
+[[rules]]
+name = "Only while hidden"
+when = { trigger = "process-detected" }
+if = { visible = false }
+then = [{ action = "hide" }]
+"#;
+        let info = load_rules(contents, &[]);
+        assert!(info.errors.is_empty());
+        let overlay = MockOverlay::default();
+        let context = || RuleContext {
+            visible: overlay.state.is_visible(),
+            mode: overlay.state.get_mode().into(),
+            process_running: true,
+            profile: "default".to_string(),
+        };
+        let run = |action: &RuleAction| {
+            overlay_command(action).map_or(Ok(()), |command| dispatch::apply(&overlay, &command))
+        };
+
+        run_rules(
+            &info.rules,
+            &RuleEvent::ProcessDetected {
+                profile: "LIVE".to_string(),
+            },
+            context,
+            run,
+        );
+
+        // The second show does not toggle the overlay off, and the third rule sees it shown
+        assert!(overlay.state.is_visible());
+        assert_eq!(overlay.state.get_mode(), OverlayMode::Windowed);
+    }
+}
@@ -0,0 +1,212 @@
+// Rule types - the rules.toml schema, the events rules react to and dry-run results
+//
+// [[rules]]
+// name = "Mining layout on PTU"
+// when = { trigger = "process-detected", profile = "PTU" }
+// if = { visible = true }
+// then = [{ action = "switch-profile", profile = "mining" }, { action = "set-mode", mode = "interactive" }]
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::types::OverlayMode;
+
+/// Overlay mode as written in rules ("click-through" is accepted for passthrough)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RuleMode {
+    Interactive,
+    #[serde(alias = "click-through")]
+    Passthrough,
+}
+
+impl From<OverlayMode> for RuleMode {
+    fn from(mode: OverlayMode) -> Self {
+        match mode {
+            OverlayMode::Windowed => RuleMode::Interactive,
+            OverlayMode::Fullscreen => RuleMode::Passthrough,
+        }
+    }
+}
+
+impl From<RuleMode> for OverlayMode {
+    fn from(mode: RuleMode) -> Self {
+        match mode {
+            RuleMode::Interactive => OverlayMode::Windowed,
+            RuleMode::Passthrough => OverlayMode::Fullscreen,
+        }
+    }
+}
+
+/// What starts a rule (`when`). Optional fields left out match anything.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "trigger", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum RuleTrigger {
+    /// A target profile's process started (profile = target profile name, e.g. "PTU")
+    ProcessDetected {
+        #[serde(default)]
+        profile: Option<String>,
+    },
+    ProcessTerminated {
+        #[serde(default)]
+        profile: Option<String>,
+    },
+    /// The game window gained or lost focus (whether or not the overlay is shown)
+    FocusGained,
+    FocusLost,
+    /// A hotkey action was triggered (action = action ID, e.g. "chronometerReset")
+    Hotkey {
+        action: String,
+    },
+    /// Local time of day, "HH:MM"
+    TimeOfDay {
+        at: String,
+    },
+    /// An alarm fired (label = alarm label)
+    Alarm {
+        #[serde(default)]
+        label: Option<String>,
+    },
+}
+
+/// Extra requirements checked when the trigger matches (`if`); all given fields must hold
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleCondition {
+    /// Overlay shown
+    #[serde(default)]
+    pub visible: Option<bool>,
+    #[serde(default)]
+    pub mode: Option<RuleMode>,
+    /// A target process is running
+    #[serde(default)]
+    pub process_running: Option<bool>,
+    /// Active layout profile
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// What a rule does (`then`), in order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum RuleAction {
+    Show,
+    Hide,
+    SetMode {
+        mode: RuleMode,
+    },
+    SwitchProfile {
+        profile: String,
+    },
+    /// Start (or resume) a backend timer, e.g. "chronometer"
+    StartTimer {
+        timer: String,
+    },
+    /// Open a browser window on the overlay
+    OpenUrl {
+        url: String,
+    },
+}
+
+impl fmt::Display for RuleAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleAction::Show => write!(f, "show overlay"),
+            RuleAction::Hide => write!(f, "hide overlay"),
+            RuleAction::SetMode { mode } => match mode {
+                RuleMode::Interactive => write!(f, "switch to interactive mode"),
+                RuleMode::Passthrough => write!(f, "switch to click-through mode"),
+            },
+            RuleAction::SwitchProfile { profile } => write!(f, "switch to profile '{}'", profile),
+            RuleAction::StartTimer { timer } => write!(f, "start timer '{}'", timer),
+            RuleAction::OpenUrl { url } => write!(f, "open {}", url),
+        }
+    }
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// A rule: when <trigger> [if <condition>] then <actions>
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub name: String,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    pub when: RuleTrigger,
+    #[serde(rename = "if", default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<RuleCondition>,
+    pub then: Vec<RuleAction>,
+}
+
+/// Contents of rules.toml
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RulesFile {
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+/// Something that happened, matched against the rule triggers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "trigger", rename_all = "kebab-case")]
+pub enum RuleEvent {
+    ProcessDetected { profile: String },
+    ProcessTerminated { profile: String },
+    FocusGained,
+    FocusLost,
+    Hotkey { action: String },
+    TimeOfDay { at: String },
+    Alarm { label: String },
+}
+
+/// State the rule conditions are checked against
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleContext {
+    pub visible: bool,
+    pub mode: RuleMode,
+    pub process_running: bool,
+    pub profile: String,
+}
+
+/// A rule whose trigger matched an event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleEvaluation {
+    pub rule: String,
+    /// The `if` condition holds (the actions run only then)
+    pub condition_met: bool,
+    pub actions: Vec<RuleAction>,
+    /// Readable form of the actions, e.g. "switch to profile 'mining'"
+    pub descriptions: Vec<String>,
+}
+
+/// Loaded rules and the problems found in rules.toml
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RulesInfo {
+    pub path: Option<String>,
+    /// Valid rules, in file order
+    pub rules: Vec<Rule>,
+    /// Parse errors and rules skipped as invalid
+    pub errors: Vec<String>,
+}
+
+/// Result of dry_run_rules
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunResult {
+    pub context: RuleContext,
+    pub evaluations: Vec<RuleEvaluation>,
+}
+
+/// Payload of open-browser-url
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenUrlPayload {
+    pub url: String,
+}
@@ -0,0 +1,250 @@
+//! Configuration layers above settings.toml
+//!
+//! Runtime settings are resolved from several layers, highest precedence first:
+//!
+//! 1. Command-line flags (`--target-process-name StarCitizen_PTU.exe`)
+//! 2. `RAIC_*` environment variables (`RAIC_TARGET_PROCESS_NAME=StarCitizen_PTU.exe`)
+//! 3. settings.toml next to the executable
+//! 4. Build-time defaults (.env)
+//!
+//! Each layer is parsed into a `FileSettings` (all fields optional), so the same
+//! merge logic applies to every layer. Values that fail to parse are reported
+//! and skipped, letting a lower layer provide the setting instead.
+
+use super::runtime::FileSettings;
+use super::validation::KNOWN_KEYS;
+
+/// Prefix of environment variables that override settings.toml
+pub const ENV_PREFIX: &str = "RAIC_";
+
+/// Prefix of command-line flags that override settings.toml
+const CLI_PREFIX: &str = "--";
+
+/// Apply a raw string value to the setting `key` of a layer.
+/// Returns an error message if the key is known but the value is invalid,
+/// and `Ok(false)` if the key is not a setting.
+fn set_value(layer: &mut FileSettings, key: &str, value: &str) -> Result<bool, String> {
+    match key {
+        "target_window_name" => layer.target_window_name = Some(value.to_string()),
+        "target_process_name" => layer.target_process_name = Some(value.to_string()),
+        "target_window_class" => layer.target_window_class = Some(value.to_string()),
+        "log_level" => layer.log_level = Some(value.to_string()),
+        "game_log_path" => layer.game_log_path = Some(value.to_string()),
+        "debug_border" => {
+            layer.debug_border =
+                Some(parse_bool(value).ok_or_else(|| {
+                    format!("debug_border must be true or false, got '{}'", value)
+                })?)
+        }
+        "process_monitor_interval_ms" => {
+            layer.process_monitor_interval_ms = Some(value.trim().parse().map_err(|_| {
+                format!(
+                    "process_monitor_interval_ms must be a positive integer, got '{}'",
+                    value
+                )
+            })?)
+        }
+        "target_profiles" | "control_api" => {
+            return Err(format!("{} can only be set in settings.toml", key))
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Build the environment layer from `RAIC_*` variables.
+///
+/// `RAIC_TARGET_PROCESS_NAME` sets `target_process_name`, and so on. Variables
+/// that do not name a setting are ignored. Returns the layer and any warnings.
+pub fn env_layer(vars: impl IntoIterator<Item = (String, String)>) -> (FileSettings, Vec<String>) {
+    let mut layer = FileSettings::default();
+    let mut warnings = Vec::new();
+
+    for (name, value) in vars {
+        let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        if value.trim().is_empty() {
+            continue;
+        }
+        if let Err(e) = set_value(&mut layer, &key.to_lowercase(), &value) {
+            warnings.push(format!("{}: {}", name, e));
+        }
+    }
+
+    (layer, warnings)
+}
+
+/// Build the command-line layer from process arguments.
+///
+/// Accepts `--key value` and `--key=value`, with keys in kebab-case
+/// (`--process-monitor-interval-ms 500`). A bare `--debug-border` means true.
+/// Arguments that do not name a setting are left for other consumers.
+pub fn cli_layer(args: &[String]) -> (FileSettings, Vec<String>) {
+    let mut layer = FileSettings::default();
+    let mut warnings = Vec::new();
+
+    let mut index = 0;
+    while index < args.len() {
+        let arg = &args[index];
+        index += 1;
+
+        let Some(flag) = arg.strip_prefix(CLI_PREFIX) else {
+            continue;
+        };
+        let (name, inline_value) = match flag.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (flag, None),
+        };
+        let key = name.replace('-', "_");
+
+        let value = match inline_value {
+            Some(value) => value,
+            None if key == "debug_border" => {
+                // Bare flag, or followed by an explicit boolean
+                match args.get(index).and_then(|v| parse_bool(v)) {
+                    Some(_) => {
+                        index += 1;
+                        args[index - 1].clone()
+                    }
+                    None => "true".to_string(),
+                }
+            }
+            None => match args.get(index) {
+                Some(next) if !next.starts_with(CLI_PREFIX) && is_setting(&key) => {
+                    index += 1;
+                    next.clone()
+                }
+                _ => {
+                    if is_setting(&key) {
+                        warnings.push(format!("{}: missing value", arg));
+                    }
+                    continue;
+                }
+            },
+        };
+
+        if let Err(e) = set_value(&mut layer, &key, &value) {
+            warnings.push(format!("--{}: {}", name, e));
+        }
+    }
+
+    (layer, warnings)
+}
+
+fn is_setting(key: &str) -> bool {
+    KNOWN_KEYS.contains(&key)
+}
+
+/// Read the environment layer from the current process environment.
+pub fn load_env_layer() -> FileSettings {
+    let (layer, warnings) = env_layer(std::env::vars());
+    for warning in warnings {
+        log::warn!("Ignoring environment override {}", warning);
+    }
+    layer
+}
+
+/// Read the command-line layer from the current process arguments.
+pub fn load_cli_layer() -> FileSettings {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (layer, warnings) = cli_layer(&args);
+    for warning in warnings {
+        log::warn!("Ignoring command-line override {}", warning);
+    }
+    layer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_env_layer() {
+        let (layer, warnings) = env_layer(vars(&[
+            ("RAIC_TARGET_PROCESS_NAME", "StarCitizen_PTU.exe"),
+            ("RAIC_DEBUG_BORDER", "1"),
+            ("RAIC_PROCESS_MONITOR_INTERVAL_MS", "500"),
+            ("RAIC_UNRELATED", "x"),
+            ("PATH", "/usr/bin"),
+        ]));
+
+        assert!(warnings.is_empty());
+        assert_eq!(
+            layer.target_process_name.as_deref(),
+            Some("StarCitizen_PTU.exe")
+        );
+        assert_eq!(layer.debug_border, Some(true));
+        assert_eq!(layer.process_monitor_interval_ms, Some(500));
+        assert!(layer.target_window_name.is_none());
+    }
+
+    #[test]
+    fn test_env_layer_invalid_values_are_reported() {
+        let (layer, warnings) = env_layer(vars(&[
+            ("RAIC_DEBUG_BORDER", "maybe"),
+            ("RAIC_PROCESS_MONITOR_INTERVAL_MS", "-5"),
+            ("RAIC_TARGET_WINDOW_NAME", "  "),
+        ]));
+
+        assert_eq!(warnings.len(), 2);
+        assert!(layer.debug_border.is_none());
+        assert!(layer.process_monitor_interval_ms.is_none());
+        assert!(layer.target_window_name.is_none());
+    }
+
+    #[test]
+    fn test_cli_layer_forms() {
+        let (layer, warnings) = cli_layer(&args(&[
+            "--target-process-name",
+            "StarCitizen_PTU.exe",
+            "--log-level=DEBUG",
+            "--debug-border",
+            "--process-monitor-interval-ms",
+            "250",
+        ]));
+
+        assert!(warnings.is_empty());
+        assert_eq!(
+            layer.target_process_name.as_deref(),
+            Some("StarCitizen_PTU.exe")
+        );
+        assert_eq!(layer.log_level.as_deref(), Some("DEBUG"));
+        assert_eq!(layer.debug_border, Some(true));
+        assert_eq!(layer.process_monitor_interval_ms, Some(250));
+    }
+
+    #[test]
+    fn test_cli_layer_ignores_other_arguments() {
+        let (layer, warnings) = cli_layer(&args(&[
+            "--toggle-visibility",
+            "--debug-border",
+            "false",
+            "positional",
+            "--target-window-name",
+        ]));
+
+        assert_eq!(layer.debug_border, Some(false));
+        assert!(layer.target_window_name.is_none());
+        assert_eq!(warnings.len(), 1);
+    }
+}
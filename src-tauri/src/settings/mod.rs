@@ -1,8 +1,11 @@
 // Settings module - runtime configuration and user preferences
 
+pub mod layers;
+pub mod reload;
 pub mod runtime;
 pub mod types;
 pub mod user;
+pub mod validation;
 pub mod window;
 
 // Re-exports for public API
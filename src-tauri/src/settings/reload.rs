@@ -0,0 +1,202 @@
+//! Hot-reload of settings.toml
+//!
+//! A background thread polls settings.toml for changes. When the file changes it
+//! is re-parsed and, if valid, swapped in atomically via `replace_settings`.
+//! Affected subsystems are then notified and the frontend receives a
+//! `settings-reloaded` event listing the keys that changed. A file that fails
+//! to parse is reported and the current settings are kept. Environment and
+//! command-line overrides are re-applied on top of the reloaded file.
+
+use super::runtime::{self, RuntimeSettings};
+use super::validation;
+use serde::Serialize;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// How often settings.toml is checked for changes
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Payload of the `settings-reloaded` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsReloadedPayload {
+    /// settings.toml keys whose effective value changed
+    pub changed_keys: Vec<String>,
+}
+
+/// List the settings whose effective value differs between `old` and `new`.
+pub fn changed_keys(old: &RuntimeSettings, new: &RuntimeSettings) -> Vec<String> {
+    let mut changed = Vec::new();
+    if old.target_window_name != new.target_window_name {
+        changed.push("target_window_name");
+    }
+    if old.debug_border != new.debug_border {
+        changed.push("debug_border");
+    }
+    if old.log_level != new.log_level {
+        changed.push("log_level");
+    }
+    if old.target_process_name != new.target_process_name {
+        changed.push("target_process_name");
+    }
+    if old.target_window_class != new.target_window_class {
+        changed.push("target_window_class");
+    }
+    if old.process_monitor_interval_ms != new.process_monitor_interval_ms {
+        changed.push("process_monitor_interval_ms");
+    }
+    if old.target_profiles != new.target_profiles {
+        changed.push("target_profiles");
+    }
+    if old.game_log_path != new.game_log_path {
+        changed.push("game_log_path");
+    }
+    if old.control_api != new.control_api {
+        changed.push("control_api");
+    }
+    changed.into_iter().map(String::from).collect()
+}
+
+/// Whether any of the target detection criteria changed
+pub fn criteria_changed(changed: &[String]) -> bool {
+    changed.iter().any(|k| {
+        matches!(
+            k.as_str(),
+            "target_window_name"
+                | "target_process_name"
+                | "target_window_class"
+                | "target_profiles"
+        )
+    })
+}
+
+/// Modification time of the settings file (None if it does not exist)
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Re-read settings.toml and apply it if it parses.
+/// Returns the changed keys, or an error if the file could not be loaded.
+pub fn reload_settings<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<String>, String> {
+    validation::log_validation_report(&validation::validate_settings_file());
+
+    let file_settings = runtime::try_load_file_settings()?;
+    let new_settings = runtime::resolve_settings(file_settings);
+
+    let old_settings = runtime::get_settings();
+    let changed = changed_keys(&old_settings, &new_settings);
+    if changed.is_empty() {
+        log::debug!("settings.toml changed but no effective value changed");
+        return Ok(changed);
+    }
+
+    runtime::replace_settings(new_settings);
+    let settings = runtime::get_settings();
+    log::info!("settings.toml reloaded, changed: {}", changed.join(", "));
+    runtime::log_settings_sources(&settings);
+
+    // Notify affected subsystems
+    if changed.iter().any(|k| k == "log_level") {
+        crate::logging::apply_log_level(settings.log_level);
+    }
+
+    #[cfg(any(windows, target_os = "linux"))]
+    {
+        use crate::platform::process_monitor::PROCESS_MONITOR_STATE;
+
+        if changed.iter().any(|k| k == "process_monitor_interval_ms") {
+            PROCESS_MONITOR_STATE.set_polling_interval(settings.process_monitor_interval_ms);
+        }
+        if criteria_changed(&changed) {
+            PROCESS_MONITOR_STATE.invalidate_target();
+        }
+    }
+
+    if changed.iter().any(|k| k == "control_api") {
+        crate::remote::restart_control_api();
+    }
+
+    let payload = SettingsReloadedPayload {
+        changed_keys: changed.clone(),
+    };
+    if let Err(e) = app.emit("settings-reloaded", payload) {
+        log::error!("Failed to emit settings-reloaded event: {}", e);
+    }
+
+    Ok(changed)
+}
+
+/// Start the background thread that watches settings.toml for changes.
+pub fn start_settings_watcher<R: Runtime>(app: AppHandle<R>) {
+    let Some(path) = runtime::get_settings_path() else {
+        log::debug!("Could not determine settings.toml path, not watching for changes");
+        return;
+    };
+
+    thread::spawn(move || {
+        let mut last_modified = modified_time(&path);
+        log::debug!("Watching {:?} for changes", path);
+
+        loop {
+            thread::sleep(WATCH_INTERVAL);
+
+            let modified = modified_time(&path);
+            if modified == last_modified {
+                continue;
+            }
+            last_modified = modified;
+
+            if let Err(e) = reload_settings(&app) {
+                log::warn!("{}; keeping current settings", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::runtime::FileSettings;
+
+    #[test]
+    fn test_changed_keys_none() {
+        let a = RuntimeSettings::from_file_settings(FileSettings::default());
+        let b = RuntimeSettings::from_file_settings(FileSettings::default());
+        assert!(changed_keys(&a, &b).is_empty());
+    }
+
+    #[test]
+    fn test_changed_keys_lists_effective_changes() {
+        let old = RuntimeSettings::from_file_settings(FileSettings::default());
+        let new = RuntimeSettings::from_file_settings(FileSettings {
+            target_process_name: Some("StarCitizen_PTU.exe".to_string()),
+            process_monitor_interval_ms: Some(250),
+            ..Default::default()
+        });
+
+        let changed = changed_keys(&old, &new);
+        // The implicit default profile follows target_process_name
+        assert_eq!(
+            changed,
+            vec![
+                "target_process_name",
+                "process_monitor_interval_ms",
+                "target_profiles"
+            ]
+        );
+        assert!(criteria_changed(&changed));
+    }
+
+    #[test]
+    fn test_invalid_value_is_not_a_change() {
+        // A 0 ms interval falls back to the default, so nothing effectively changes
+        let old = RuntimeSettings::from_file_settings(FileSettings::default());
+        let new = RuntimeSettings::from_file_settings(FileSettings {
+            process_monitor_interval_ms: Some(0),
+            ..Default::default()
+        });
+        assert!(changed_keys(&old, &new).is_empty());
+    }
+}
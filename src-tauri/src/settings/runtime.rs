@@ -2,14 +2,18 @@
 //
 // This module provides runtime configuration from a settings.toml file located next to
 // the executable. All settings have graceful fallback to compile-time defaults.
+// RAIC_* environment variables and command-line flags override settings.toml
+// (precedence: command line > environment > settings.toml > default, see layers.rs).
+// The cached settings can be swapped at runtime when settings.toml changes (see reload.rs).
 
+use super::layers;
 use log::LevelFilter;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use std::sync::OnceLock;
+use std::sync::{Arc, RwLock};
 
-/// Global settings cache - initialized once at startup
-static SETTINGS: OnceLock<RuntimeSettings> = OnceLock::new();
+/// Global settings cache - initialized on first access, replaced on hot-reload
+static SETTINGS: RwLock<Option<Arc<RuntimeSettings>>> = RwLock::new(None);
 
 // ============================================================================
 // T004: Settings Types
@@ -37,6 +41,47 @@ pub struct FileSettings {
 
     // T010 (028): Polling interval for process monitoring in milliseconds (default: 1000)
     pub process_monitor_interval_ms: Option<u64>,
+
+    /// Target profiles ([[target_profiles]]) tried in order, e.g. LIVE, PTU, EPTU
+    pub target_profiles: Option<Vec<FileTargetProfile>>,
+
+    /// Path of the game's Game.log (default: located from the running game)
+    pub game_log_path: Option<String>,
+
+    /// Local control API ([control_api]) for companion apps and stream tools
+    pub control_api: Option<FileControlApi>,
+}
+
+/// A target profile as written in settings.toml.
+/// Omitted window_class/window_title fall back to target_window_class/target_window_name.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct FileTargetProfile {
+    /// Profile name reported on detection (e.g., "PTU")
+    pub name: Option<String>,
+
+    /// Executable name (e.g., "StarCitizen_PTU.exe")
+    pub process_name: Option<String>,
+
+    /// Window class (e.g., "CryENGINE")
+    pub window_class: Option<String>,
+
+    /// Window title pattern (e.g., "Star Citizen")
+    pub window_title: Option<String>,
+}
+
+/// The [control_api] table as written in settings.toml.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct FileControlApi {
+    /// Start the server (default: false)
+    pub enabled: Option<bool>,
+
+    /// Port on 127.0.0.1 (default: DEFAULT_CONTROL_API_PORT)
+    pub port: Option<u16>,
+
+    /// Token clients must send; the server does not start without one
+    pub token: Option<String>,
 }
 
 // T007-T008 (043): Removed DEFAULT_PROCESS_NAME and DEFAULT_WINDOW_CLASS constants
@@ -45,6 +90,51 @@ pub struct FileSettings {
 // T010 (028): Polling interval for process monitoring in milliseconds
 pub const DEFAULT_PROCESS_MONITOR_INTERVAL_MS: u64 = 1000;
 
+/// Port of the local control API when [control_api] does not set one
+pub const DEFAULT_CONTROL_API_PORT: u16 = 17345;
+
+/// Name of the implicit profile built from the target_* settings
+pub const DEFAULT_TARGET_PROFILE: &str = "default";
+
+/// A resolved target profile: one process/class/title triple to detect
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetProfile {
+    pub name: String,
+    pub process_name: String,
+    pub window_class: String,
+    pub window_title: String,
+}
+
+/// Resolved local control API settings (see remote/server.rs)
+#[derive(Clone, PartialEq)]
+pub struct ControlApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+}
+
+impl Default for ControlApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_CONTROL_API_PORT,
+            token: String::new(),
+        }
+    }
+}
+
+// Keep the token out of logs
+impl std::fmt::Debug for ControlApiSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ControlApiSettings")
+            .field("enabled", &self.enabled)
+            .field("port", &self.port)
+            .field("token", &if self.token.is_empty() { "" } else { "***" })
+            .finish()
+    }
+}
+
 /// Fully resolved runtime settings.
 /// All fields are guaranteed to have valid values (either from file or defaults).
 #[derive(Debug, Clone)]
@@ -67,6 +157,15 @@ pub struct RuntimeSettings {
     // T010 (028): Polling interval for process monitoring
     pub process_monitor_interval_ms: u64,
 
+    /// Target profiles in priority order (never empty)
+    pub target_profiles: Vec<TargetProfile>,
+
+    /// Path of Game.log, empty to locate it from the running game
+    pub game_log_path: String,
+
+    /// Local control API (disabled by default)
+    pub control_api: ControlApiSettings,
+
     /// Source of each setting for logging
     pub sources: SettingsSources,
 }
@@ -80,11 +179,19 @@ pub struct SettingsSources {
     pub target_process_name: SettingSource,
     pub target_window_class: SettingSource,
     pub process_monitor_interval_ms: SettingSource,
+    pub target_profiles: SettingSource,
+    pub game_log_path: SettingSource,
+    pub control_api: SettingSource,
 }
 
-/// Indicates the origin of a setting value
+/// Indicates the origin of a setting value.
+/// Layers are listed from highest to lowest precedence.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SettingSource {
+    /// Value from a command-line flag (e.g. `--target-process-name`)
+    Cli,
+    /// Value from a `RAIC_*` environment variable
+    Env,
     /// Value from settings.toml file
     File,
     /// Value from compile-time default (.env)
@@ -94,6 +201,8 @@ pub enum SettingSource {
 impl std::fmt::Display for SettingSource {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            SettingSource::Cli => write!(f, "command line"),
+            SettingSource::Env => write!(f, "environment"),
             SettingSource::File => write!(f, "settings.toml"),
             SettingSource::Default => write!(f, "default"),
         }
@@ -134,118 +243,188 @@ pub fn get_settings_path() -> Option<PathBuf> {
 /// Load and parse settings.toml file.
 /// Returns FileSettings with all None values if file doesn't exist or can't be parsed.
 pub fn load_file_settings() -> FileSettings {
+    match try_load_file_settings() {
+        Ok(settings) => settings,
+        Err(e) => {
+            log::warn!("{}", e);
+            log::warn!("Using default settings");
+            FileSettings::default()
+        }
+    }
+}
+
+/// Load and parse settings.toml file, reporting read and parse errors.
+/// A missing file is not an error and yields FileSettings with all None values.
+pub fn try_load_file_settings() -> Result<FileSettings, String> {
     let Some(path) = get_settings_path() else {
         log::debug!("Could not determine settings.toml path");
-        return FileSettings::default();
+        return Ok(FileSettings::default());
     };
 
     // Check if file exists
     if !path.exists() {
         log::debug!("settings.toml not found at {:?}", path);
-        return FileSettings::default();
+        return Ok(FileSettings::default());
     }
 
     // Read file contents
-    let contents = match std::fs::read_to_string(&path) {
-        Ok(c) => c,
-        Err(e) => {
-            log::warn!("Failed to read settings.toml: {}", e);
-            return FileSettings::default();
-        }
-    };
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read settings.toml: {}", e))?;
 
     // Parse TOML
-    match toml::from_str(&contents) {
-        Ok(settings) => {
-            log::debug!("Successfully parsed settings.toml");
-            settings
-        }
-        Err(e) => {
-            log::warn!("Failed to parse settings.toml: {}", e);
-            log::warn!("Using default settings");
-            FileSettings::default()
-        }
-    }
+    let settings =
+        toml::from_str(&contents).map_err(|e| format!("Failed to parse settings.toml: {}", e))?;
+    log::debug!("Successfully parsed settings.toml");
+    Ok(settings)
 }
 
 // ============================================================================
 // T007: RuntimeSettings from FileSettings
 // ============================================================================
 
+/// Pick the first layer (highest precedence first) that provides a valid value.
+/// `value` returns None for missing or invalid values, so lower layers can take over.
+fn pick<T>(
+    layers: &[(SettingSource, &FileSettings)],
+    value: impl Fn(SettingSource, &FileSettings) -> Option<T>,
+) -> Option<(T, SettingSource)> {
+    layers
+        .iter()
+        .find_map(|(source, layer)| value(*source, layer).map(|v| (v, *source)))
+}
+
+/// Non-empty string value of a layer
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value.as_ref().filter(|v| !v.is_empty()).cloned()
+}
+
+/// Resolve configured target profiles, skipping unnamed, process-less and
+/// duplicate entries. Omitted class/title fields are taken from `fallback`.
+pub fn resolve_target_profiles(
+    profiles: &[FileTargetProfile],
+    fallback: &TargetProfile,
+) -> Vec<TargetProfile> {
+    let mut resolved: Vec<TargetProfile> = Vec::new();
+
+    for (index, profile) in profiles.iter().enumerate() {
+        let Some(name) = non_empty(&profile.name) else {
+            log::warn!("Target profile #{} has no name, ignoring it", index + 1);
+            continue;
+        };
+        let Some(process_name) = non_empty(&profile.process_name) else {
+            log::warn!("Target profile '{}' has no process_name, ignoring it", name);
+            continue;
+        };
+        if resolved.iter().any(|p| p.name.eq_ignore_ascii_case(&name)) {
+            log::warn!("Duplicate target profile '{}', ignoring it", name);
+            continue;
+        }
+
+        resolved.push(TargetProfile {
+            name,
+            process_name,
+            window_class: non_empty(&profile.window_class)
+                .unwrap_or_else(|| fallback.window_class.clone()),
+            window_title: non_empty(&profile.window_title)
+                .unwrap_or_else(|| fallback.window_title.clone()),
+        });
+    }
+
+    resolved
+}
+
 impl RuntimeSettings {
     /// Create RuntimeSettings by merging FileSettings with compile-time defaults.
     pub fn from_file_settings(file: FileSettings) -> Self {
-        let mut sources = SettingsSources {
-            target_window_name: SettingSource::Default,
-            debug_border: SettingSource::Default,
-            log_level: SettingSource::Default,
-            target_process_name: SettingSource::Default,
-            target_window_class: SettingSource::Default,
-            process_monitor_interval_ms: SettingSource::Default,
-        };
+        Self::from_layers(&[(SettingSource::File, &file)])
+    }
+
+    /// Create RuntimeSettings by merging configuration layers over compile-time defaults.
+    /// `layers` is ordered from highest to lowest precedence (see layers.rs).
+    pub fn from_layers(layers: &[(SettingSource, &FileSettings)]) -> Self {
+        let default_log_level =
+            || parse_log_level(env!("RAIC_LOG_LEVEL")).unwrap_or(LevelFilter::Warn);
 
         // target_window_name
-        let target_window_name = match file.target_window_name {
-            Some(ref name) if !name.is_empty() => {
-                sources.target_window_name = SettingSource::File;
-                name.clone()
-            }
-            _ => env!("TARGET_WINDOW_NAME").to_string(),
-        };
+        let (target_window_name, target_window_name_source) =
+            pick(layers, |_, l| non_empty(&l.target_window_name)).unwrap_or_else(|| {
+                (
+                    env!("TARGET_WINDOW_NAME").to_string(),
+                    SettingSource::Default,
+                )
+            });
 
         // debug_border
-        let debug_border = match file.debug_border {
-            Some(value) => {
-                sources.debug_border = SettingSource::File;
-                value
-            }
-            None => env!("VITE_DEBUG_BORDER") == "true",
-        };
+        let (debug_border, debug_border_source) = pick(layers, |_, l| l.debug_border)
+            .unwrap_or_else(|| (env!("VITE_DEBUG_BORDER") == "true", SettingSource::Default));
 
         // log_level
-        let log_level = match file.log_level {
-            Some(ref level) => match parse_log_level(level) {
-                Some(parsed) => {
-                    sources.log_level = SettingSource::File;
-                    parsed
-                }
-                None => {
-                    log::warn!(
-                        "Invalid log_level '{}' in settings.toml, using default",
-                        level
-                    );
-                    parse_log_level(env!("RAIC_LOG_LEVEL")).unwrap_or(LevelFilter::Warn)
-                }
-            },
-            None => parse_log_level(env!("RAIC_LOG_LEVEL")).unwrap_or(LevelFilter::Warn),
-        };
+        let (log_level, log_level_source) = pick(layers, |source, l| {
+            let level = l.log_level.as_ref()?;
+            let parsed = parse_log_level(level);
+            if parsed.is_none() {
+                log::warn!("Invalid log_level '{}' in {}, ignoring", level, source);
+            }
+            parsed
+        })
+        .unwrap_or_else(|| (default_log_level(), SettingSource::Default));
 
         // T009 (043): target_process_name - now uses env!() macro for build-time default
-        let target_process_name = match file.target_process_name {
-            Some(ref name) if !name.is_empty() => {
-                sources.target_process_name = SettingSource::File;
-                name.clone()
-            }
-            _ => env!("TARGET_PROCESS_NAME").to_string(),
-        };
+        let (target_process_name, target_process_name_source) =
+            pick(layers, |_, l| non_empty(&l.target_process_name)).unwrap_or_else(|| {
+                (
+                    env!("TARGET_PROCESS_NAME").to_string(),
+                    SettingSource::Default,
+                )
+            });
 
         // T010 (043): target_window_class - now uses env!() macro for build-time default
-        let target_window_class = match file.target_window_class {
-            Some(ref class) if !class.is_empty() => {
-                sources.target_window_class = SettingSource::File;
-                class.clone()
-            }
-            _ => env!("TARGET_WINDOW_CLASS").to_string(),
-        };
+        let (target_window_class, target_window_class_source) =
+            pick(layers, |_, l| non_empty(&l.target_window_class)).unwrap_or_else(|| {
+                (
+                    env!("TARGET_WINDOW_CLASS").to_string(),
+                    SettingSource::Default,
+                )
+            });
 
         // T010 (028): process_monitor_interval_ms
-        let process_monitor_interval_ms = match file.process_monitor_interval_ms {
-            Some(interval) if interval > 0 => {
-                sources.process_monitor_interval_ms = SettingSource::File;
-                interval
-            }
-            _ => DEFAULT_PROCESS_MONITOR_INTERVAL_MS,
+        let (process_monitor_interval_ms, process_monitor_interval_ms_source) =
+            pick(layers, |_, l| {
+                l.process_monitor_interval_ms
+                    .filter(|interval| *interval > 0)
+            })
+            .unwrap_or((DEFAULT_PROCESS_MONITOR_INTERVAL_MS, SettingSource::Default));
+
+        // target_profiles - the target_* settings form the implicit default profile
+        let default_profile = TargetProfile {
+            name: DEFAULT_TARGET_PROFILE.to_string(),
+            process_name: target_process_name.clone(),
+            window_class: target_window_class.clone(),
+            window_title: target_window_name.clone(),
         };
+        let (target_profiles, target_profiles_source) = pick(layers, |_, l| {
+            let profiles = l.target_profiles.as_ref()?;
+            Some(resolve_target_profiles(profiles, &default_profile)).filter(|p| !p.is_empty())
+        })
+        .unwrap_or_else(|| (vec![default_profile.clone()], SettingSource::Default));
+
+        // game_log_path - empty means locate Game.log next to the running game
+        let (game_log_path, game_log_path_source) = pick(layers, |_, l| {
+            non_empty(&l.game_log_path).map(|path| path.trim().to_string())
+        })
+        .unwrap_or_else(|| (String::new(), SettingSource::Default));
+
+        // control_api - the table is taken as a whole from one layer
+        let (control_api, control_api_source) = pick(layers, |_, l| {
+            let api = l.control_api.as_ref()?;
+            let defaults = ControlApiSettings::default();
+            Some(ControlApiSettings {
+                enabled: api.enabled.unwrap_or(defaults.enabled),
+                port: api.port.filter(|port| *port > 0).unwrap_or(defaults.port),
+                token: api.token.clone().unwrap_or_default().trim().to_string(),
+            })
+        })
+        .unwrap_or_else(|| (ControlApiSettings::default(), SettingSource::Default));
 
         Self {
             target_window_name,
@@ -254,24 +433,65 @@ impl RuntimeSettings {
             target_process_name,
             target_window_class,
             process_monitor_interval_ms,
-            sources,
+            target_profiles,
+            game_log_path,
+            control_api,
+            sources: SettingsSources {
+                target_window_name: target_window_name_source,
+                debug_border: debug_border_source,
+                log_level: log_level_source,
+                target_process_name: target_process_name_source,
+                target_window_class: target_window_class_source,
+                process_monitor_interval_ms: process_monitor_interval_ms_source,
+                target_profiles: target_profiles_source,
+                game_log_path: game_log_path_source,
+                control_api: control_api_source,
+            },
         }
     }
 }
 
+/// Resolve runtime settings from settings.toml plus the environment and
+/// command-line layers of the current process.
+pub fn resolve_settings(file: FileSettings) -> RuntimeSettings {
+    let cli = layers::load_cli_layer();
+    let env = layers::load_env_layer();
+    RuntimeSettings::from_layers(&[
+        (SettingSource::Cli, &cli),
+        (SettingSource::Env, &env),
+        (SettingSource::File, &file),
+    ])
+}
+
 // ============================================================================
-// T008: Get settings with OnceLock caching
+// T008: Get settings with RwLock caching
 // ============================================================================
 
 /// Get the runtime settings (cached after first call).
 /// This function initializes settings on first call and returns the cached value on subsequent calls.
-pub fn get_settings() -> &'static RuntimeSettings {
-    SETTINGS.get_or_init(|| {
-        let file_settings = load_file_settings();
-        let settings = RuntimeSettings::from_file_settings(file_settings);
-        log_settings_sources(&settings);
-        settings
-    })
+/// The returned snapshot stays consistent even if settings are reloaded while it is held.
+pub fn get_settings() -> Arc<RuntimeSettings> {
+    if let Some(settings) = SETTINGS.read().ok().and_then(|s| s.clone()) {
+        return settings;
+    }
+
+    let mut cache = SETTINGS.write().unwrap_or_else(|p| p.into_inner());
+    if let Some(settings) = cache.as_ref() {
+        return settings.clone();
+    }
+
+    let file_settings = load_file_settings();
+    let settings = Arc::new(resolve_settings(file_settings));
+    log_settings_sources(&settings);
+    *cache = Some(settings.clone());
+    settings
+}
+
+/// Atomically replace the cached runtime settings.
+/// Returns the previous settings, if any were loaded.
+pub fn replace_settings(settings: RuntimeSettings) -> Option<Arc<RuntimeSettings>> {
+    let mut cache = SETTINGS.write().unwrap_or_else(|p| p.into_inner());
+    cache.replace(Arc::new(settings))
 }
 
 /// Initialize settings without returning a reference.
@@ -317,6 +537,34 @@ pub fn log_settings_sources(settings: &RuntimeSettings) {
         settings.process_monitor_interval_ms,
         settings.sources.process_monitor_interval_ms
     );
+    log::info!(
+        "  target_profiles: {} (from {})",
+        settings
+            .target_profiles
+            .iter()
+            .map(|p| format!("{} [{}]", p.name, p.process_name))
+            .collect::<Vec<_>>()
+            .join(", "),
+        settings.sources.target_profiles
+    );
+    log::info!(
+        "  game_log_path: {} (from {})",
+        if settings.game_log_path.is_empty() {
+            "located from the running game".to_string()
+        } else {
+            format!("'{}'", settings.game_log_path)
+        },
+        settings.sources.game_log_path
+    );
+    log::info!(
+        "  control_api: {} (from {})",
+        if settings.control_api.enabled {
+            format!("enabled on port {}", settings.control_api.port)
+        } else {
+            "disabled".to_string()
+        },
+        settings.sources.control_api
+    );
 }
 
 // ============================================================================
@@ -324,13 +572,13 @@ pub fn log_settings_sources(settings: &RuntimeSettings) {
 // ============================================================================
 
 /// Get the target process name (e.g., "StarCitizen.exe")
-pub fn get_target_process_name() -> &'static str {
-    &get_settings().target_process_name
+pub fn get_target_process_name() -> String {
+    get_settings().target_process_name.clone()
 }
 
 /// Get the target window class (e.g., "CryENGINE")
-pub fn get_target_window_class() -> &'static str {
-    &get_settings().target_window_class
+pub fn get_target_window_class() -> String {
+    get_settings().target_window_class.clone()
 }
 
 /// Get the process monitor polling interval in milliseconds
@@ -338,6 +586,22 @@ pub fn get_process_monitor_interval() -> u64 {
     get_settings().process_monitor_interval_ms
 }
 
+/// Get the target profiles in priority order
+pub fn get_target_profiles() -> Vec<TargetProfile> {
+    get_settings().target_profiles.clone()
+}
+
+/// Get the configured Game.log path (None = locate it from the running game)
+pub fn get_game_log_path() -> Option<PathBuf> {
+    let settings = get_settings();
+    (!settings.game_log_path.is_empty()).then(|| PathBuf::from(&settings.game_log_path))
+}
+
+/// Get the local control API settings
+pub fn get_control_api_settings() -> ControlApiSettings {
+    get_settings().control_api.clone()
+}
+
 // ============================================================================
 // T022-T023: Tauri commands for frontend settings access
 // ============================================================================
@@ -364,6 +628,14 @@ pub struct SettingsSourcesResponse {
     pub target_window_name: String,
     pub debug_border: String,
     pub log_level: String,
+    pub target_process_name: String,
+    pub target_window_class: String,
+    pub process_monitor_interval_ms: String,
+    pub target_profiles: String,
+    pub game_log_path: String,
+    pub control_api: String,
+    /// Configuration layers from highest to lowest precedence
+    pub precedence: Vec<String>,
     pub settings_path: Option<String>,
 }
 
@@ -375,6 +647,21 @@ pub fn get_settings_sources() -> SettingsSourcesResponse {
         target_window_name: settings.sources.target_window_name.to_string(),
         debug_border: settings.sources.debug_border.to_string(),
         log_level: settings.sources.log_level.to_string(),
+        target_process_name: settings.sources.target_process_name.to_string(),
+        target_window_class: settings.sources.target_window_class.to_string(),
+        process_monitor_interval_ms: settings.sources.process_monitor_interval_ms.to_string(),
+        target_profiles: settings.sources.target_profiles.to_string(),
+        game_log_path: settings.sources.game_log_path.to_string(),
+        control_api: settings.sources.control_api.to_string(),
+        precedence: [
+            SettingSource::Cli,
+            SettingSource::Env,
+            SettingSource::File,
+            SettingSource::Default,
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect(),
         settings_path: get_settings_path().map(|p| p.to_string_lossy().to_string()),
     }
 }
@@ -474,6 +761,8 @@ log_level = "INFO"
     fn test_setting_source_display() {
         assert_eq!(format!("{}", SettingSource::File), "settings.toml");
         assert_eq!(format!("{}", SettingSource::Default), "default");
+        assert_eq!(format!("{}", SettingSource::Env), "environment");
+        assert_eq!(format!("{}", SettingSource::Cli), "command line");
     }
 
     #[test]
@@ -485,6 +774,9 @@ log_level = "INFO"
             target_process_name: None,
             target_window_class: None,
             process_monitor_interval_ms: None,
+            target_profiles: None,
+            game_log_path: None,
+            control_api: None,
         };
         let runtime = RuntimeSettings::from_file_settings(file);
 
@@ -641,6 +933,9 @@ another_unknown = 42
             target_process_name: Some("Test.exe".to_string()),
             target_window_class: Some("TestClass".to_string()),
             process_monitor_interval_ms: Some(500),
+            target_profiles: None,
+            game_log_path: None,
+            control_api: None,
         };
 
         // Measure time to create RuntimeSettings
@@ -721,4 +1016,152 @@ another_unknown = 42
             SettingSource::Default
         );
     }
+
+    // ========================================================================
+    // Layered configuration: command line > environment > settings.toml > default
+    // ========================================================================
+
+    #[test]
+    fn test_layers_precedence_per_key() {
+        let cli = FileSettings {
+            target_process_name: Some("StarCitizen_PTU.exe".to_string()),
+            ..Default::default()
+        };
+        let env = FileSettings {
+            target_process_name: Some("StarCitizen_EPTU.exe".to_string()),
+            log_level: Some("DEBUG".to_string()),
+            ..Default::default()
+        };
+        let file = FileSettings {
+            log_level: Some("ERROR".to_string()),
+            debug_border: Some(true),
+            ..Default::default()
+        };
+        let runtime = RuntimeSettings::from_layers(&[
+            (SettingSource::Cli, &cli),
+            (SettingSource::Env, &env),
+            (SettingSource::File, &file),
+        ]);
+
+        assert_eq!(runtime.target_process_name, "StarCitizen_PTU.exe");
+        assert_eq!(runtime.sources.target_process_name, SettingSource::Cli);
+        assert_eq!(runtime.log_level, LevelFilter::Debug);
+        assert_eq!(runtime.sources.log_level, SettingSource::Env);
+        assert!(runtime.debug_border);
+        assert_eq!(runtime.sources.debug_border, SettingSource::File);
+        assert_eq!(runtime.sources.target_window_class, SettingSource::Default);
+    }
+
+    #[test]
+    fn test_layers_invalid_value_falls_through() {
+        let env = FileSettings {
+            log_level: Some("LOUD".to_string()),
+            process_monitor_interval_ms: Some(0),
+            ..Default::default()
+        };
+        let file = FileSettings {
+            log_level: Some("INFO".to_string()),
+            ..Default::default()
+        };
+        let runtime = RuntimeSettings::from_layers(&[
+            (SettingSource::Env, &env),
+            (SettingSource::File, &file),
+        ]);
+
+        assert_eq!(runtime.log_level, LevelFilter::Info);
+        assert_eq!(runtime.sources.log_level, SettingSource::File);
+        assert_eq!(
+            runtime.sources.process_monitor_interval_ms,
+            SettingSource::Default
+        );
+    }
+
+    // ========================================================================
+    // Target profiles
+    // ========================================================================
+
+    #[test]
+    fn test_default_target_profile_from_target_settings() {
+        let file = FileSettings {
+            target_process_name: Some("Custom.exe".to_string()),
+            ..Default::default()
+        };
+        let runtime = RuntimeSettings::from_file_settings(file);
+
+        assert_eq!(runtime.target_profiles.len(), 1);
+        assert_eq!(runtime.target_profiles[0].name, DEFAULT_TARGET_PROFILE);
+        assert_eq!(runtime.target_profiles[0].process_name, "Custom.exe");
+        assert_eq!(runtime.sources.target_profiles, SettingSource::Default);
+    }
+
+    #[test]
+    fn test_target_profiles_parse_and_resolve() {
+        let toml = r#"
+target_window_class = "CryENGINE"
+
+[[target_profiles]]
+name = "LIVE"
+process_name = "StarCitizen.exe"
+
+[[target_profiles]]
+name = "PTU"
+process_name = "StarCitizen_PTU.exe"
+window_title = "Star Citizen PTU"
+
+[[target_profiles]]
+name = "live"
+process_name = "Duplicate.exe"
+
+[[target_profiles]]
+name = "Broken"
+"#;
+        let file: FileSettings = toml::from_str(toml).unwrap();
+        let runtime = RuntimeSettings::from_file_settings(file);
+
+        let names: Vec<_> = runtime.target_profiles.iter().map(|p| &p.name).collect();
+        assert_eq!(names, vec!["LIVE", "PTU"]);
+        assert_eq!(runtime.target_profiles[0].window_class, "CryENGINE");
+        assert_eq!(
+            runtime.target_profiles[0].window_title,
+            runtime.target_window_name
+        );
+        assert_eq!(runtime.target_profiles[1].window_title, "Star Citizen PTU");
+        assert_eq!(runtime.sources.target_profiles, SettingSource::File);
+    }
+
+    #[test]
+    fn test_target_profiles_all_invalid_uses_default() {
+        let file = FileSettings {
+            target_profiles: Some(vec![FileTargetProfile {
+                name: Some("NoProcess".to_string()),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+        let runtime = RuntimeSettings::from_file_settings(file);
+
+        assert_eq!(runtime.target_profiles[0].name, DEFAULT_TARGET_PROFILE);
+        assert_eq!(runtime.sources.target_profiles, SettingSource::Default);
+    }
+
+    #[test]
+    fn test_control_api_settings() {
+        let runtime = RuntimeSettings::from_file_settings(FileSettings::default());
+        assert!(!runtime.control_api.enabled);
+        assert_eq!(runtime.control_api.port, DEFAULT_CONTROL_API_PORT);
+
+        let toml = r#"
+[control_api]
+enabled = true
+token = "  0123456789abcdef  "
+"#;
+        let file: FileSettings = toml::from_str(toml).unwrap();
+        let runtime = RuntimeSettings::from_file_settings(file);
+        assert!(runtime.control_api.enabled);
+        assert_eq!(runtime.control_api.port, DEFAULT_CONTROL_API_PORT);
+        assert_eq!(runtime.control_api.token, "0123456789abcdef");
+        assert_eq!(runtime.sources.control_api, SettingSource::File);
+        // The token never shows up in debug output
+        assert!(!format!("{:?}", runtime).contains("0123456789abcdef"));
+    }
 }
@@ -2,6 +2,9 @@
 //! @feature 038-settings-panel
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::hotkey::validation::HotkeyConflict;
 
 pub const CURRENT_USER_SETTINGS_VERSION: u32 = 1;
 
@@ -24,31 +27,65 @@ pub struct UserSettings {
     #[serde(default)]
     pub start_minimized: bool,
 
+    /// Name of the active layout profile (owned by the backend, see persistence::profiles)
+    #[serde(default = "default_active_profile")]
+    pub active_profile: String,
+
     /// Last modified timestamp (ISO 8601)
     pub last_modified: String,
 }
 
-/// Hotkey bindings for overlay control
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+fn default_active_profile() -> String {
+    "default".to_string()
+}
+
+/// Hotkey bindings for overlay control, keyed by action ID (e.g., "toggleVisibility").
+/// Serialized as a flat object of bindings; actions are defined in `hotkey::registry`,
+/// and registered actions missing from a saved file get their default binding.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    from = "BTreeMap<String, HotkeyBinding>",
+    into = "BTreeMap<String, HotkeyBinding>"
+)]
 pub struct HotkeySettings {
-    /// Toggle overlay visibility (default: F3)
-    pub toggle_visibility: HotkeyBinding,
+    bindings: BTreeMap<String, HotkeyBinding>,
+}
+
+impl HotkeySettings {
+    /// Binding of an action, if it has one
+    pub fn get(&self, action_id: &str) -> Option<&HotkeyBinding> {
+        self.bindings.get(action_id)
+    }
+
+    /// Bind an action to a key combination
+    pub fn set(&mut self, action_id: &str, binding: HotkeyBinding) {
+        self.bindings.insert(action_id.to_string(), binding);
+    }
 
-    /// Toggle overlay mode - passthrough/interactive (default: F5)
-    pub toggle_mode: HotkeyBinding,
+    /// All bindings as (action ID, binding), ordered by action ID
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &HotkeyBinding)> {
+        self.bindings
+            .iter()
+            .map(|(id, binding)| (id.as_str(), binding))
+    }
+}
 
-    /// Chronometer start/pause (default: Ctrl+T)
-    #[serde(default = "default_chronometer_start_pause")]
-    pub chronometer_start_pause: HotkeyBinding,
+impl From<BTreeMap<String, HotkeyBinding>> for HotkeySettings {
+    fn from(bindings: BTreeMap<String, HotkeyBinding>) -> Self {
+        let mut settings = Self::default();
+        settings.bindings.extend(bindings);
+        settings
+    }
+}
 
-    /// Chronometer reset (default: Ctrl+Y)
-    #[serde(default = "default_chronometer_reset")]
-    pub chronometer_reset: HotkeyBinding,
+impl From<HotkeySettings> for BTreeMap<String, HotkeyBinding> {
+    fn from(settings: HotkeySettings) -> Self {
+        settings.bindings
+    }
 }
 
 /// Single hotkey binding configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct HotkeyBinding {
     /// Primary key name (e.g., "F3", "O")
@@ -65,6 +102,37 @@ pub struct HotkeyBinding {
 
     /// Alt modifier required
     pub alt: bool,
+
+    /// How the key triggers the action (omitted in older files = press)
+    #[serde(default, skip_serializing_if = "HotkeyTrigger::is_press")]
+    pub trigger: HotkeyTrigger,
+
+    /// Second key of a two-step sequence, e.g. "N" in "Ctrl+K, N"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<Box<HotkeyBinding>>,
+
+    /// Sequence, hold or double-tap window in milliseconds (None = trigger default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+/// How a hotkey binding triggers its action
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HotkeyTrigger {
+    /// Fire when the key goes down
+    #[default]
+    Press,
+    /// Fire once the key has been held down for the timeout
+    Hold,
+    /// Fire on the second press within the timeout
+    DoubleTap,
+}
+
+impl HotkeyTrigger {
+    pub fn is_press(&self) -> bool {
+        *self == HotkeyTrigger::Press
+    }
 }
 
 impl Default for UserSettings {
@@ -74,52 +142,83 @@ impl Default for UserSettings {
             hotkeys: HotkeySettings::default(),
             auto_start: false,
             start_minimized: false,
+            active_profile: default_active_profile(),
             last_modified: String::new(),
         }
     }
 }
 
-/// Default chronometer start/pause hotkey (Ctrl+T)
-fn default_chronometer_start_pause() -> HotkeyBinding {
-    HotkeyBinding {
-        key: "T".to_string(),
-        key_code: 0x54, // VK_T
-        ctrl: true,
-        shift: false,
-        alt: false,
+impl HotkeyBinding {
+    /// Binding for a key without modifiers
+    pub fn new(key: &str, key_code: u32) -> Self {
+        Self {
+            key: key.to_string(),
+            key_code,
+            ctrl: false,
+            shift: false,
+            alt: false,
+            trigger: HotkeyTrigger::Press,
+            next: None,
+            timeout_ms: None,
+        }
     }
-}
 
-/// Default chronometer reset hotkey (Ctrl+Y)
-fn default_chronometer_reset() -> HotkeyBinding {
-    HotkeyBinding {
-        key: "Y".to_string(),
-        key_code: 0x59, // VK_Y
-        ctrl: true,
-        shift: false,
-        alt: false,
+    /// Same binding with Ctrl required
+    pub fn with_ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    /// Same binding with a different trigger
+    pub fn with_trigger(mut self, trigger: HotkeyTrigger) -> Self {
+        self.trigger = trigger;
+        self
+    }
+
+    /// Two-step sequence: this key, then `next`
+    pub fn then(mut self, next: HotkeyBinding) -> Self {
+        self.next = Some(Box::new(next));
+        self
+    }
+
+    /// Whether the key and modifiers of this step equal those of `other`
+    /// (trigger and sequence are not compared)
+    pub fn same_keys(&self, other: &HotkeyBinding) -> bool {
+        self.key_code == other.key_code
+            && self.ctrl == other.ctrl
+            && self.shift == other.shift
+            && self.alt == other.alt
+    }
+
+    /// Human-readable form, e.g. "Ctrl+Shift+T", "Ctrl+K, N" or "Double-tap F3"
+    pub fn label(&self) -> String {
+        let mut label = match self.trigger {
+            HotkeyTrigger::Press => String::new(),
+            HotkeyTrigger::Hold => "Hold ".to_string(),
+            HotkeyTrigger::DoubleTap => "Double-tap ".to_string(),
+        };
+        for (held, name) in [
+            (self.ctrl, "Ctrl+"),
+            (self.shift, "Shift+"),
+            (self.alt, "Alt+"),
+        ] {
+            if held {
+                label.push_str(name);
+            }
+        }
+        label.push_str(&self.key);
+        if let Some(next) = &self.next {
+            label.push_str(", ");
+            label.push_str(&next.label());
+        }
+        label
     }
 }
 
 impl Default for HotkeySettings {
     fn default() -> Self {
         Self {
-            toggle_visibility: HotkeyBinding {
-                key: "F3".to_string(),
-                key_code: 0x72, // VK_F3
-                ctrl: false,
-                shift: false,
-                alt: false,
-            },
-            toggle_mode: HotkeyBinding {
-                key: "F5".to_string(),
-                key_code: 0x74, // VK_F5
-                ctrl: false,
-                shift: false,
-                alt: false,
-            },
-            chronometer_start_pause: default_chronometer_start_pause(),
-            chronometer_reset: default_chronometer_reset(),
+            bindings: crate::hotkey::registry::default_bindings(),
         }
     }
 }
@@ -134,6 +233,9 @@ pub struct LoadUserSettingsResult {
     pub settings: Option<UserSettings>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Problems found in the stored hotkeys (bindings with errors were reset to defaults)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hotkey_conflicts: Vec<HotkeyConflict>,
 }
 
 /// Result of saving user settings
@@ -2,6 +2,10 @@
 //! @feature 038-settings-panel
 
 use super::types::*;
+use crate::hotkey::validation::{
+    reset_invalid_bindings, validate_hotkeys, HotkeyConflict, HotkeyValidationReport,
+};
+use crate::settings::validation::DiagnosticSeverity;
 use std::fs::{self, File};
 use std::io::{Read, Write};
 use std::path::PathBuf;
@@ -33,6 +37,7 @@ pub async fn load_user_settings(app: tauri::AppHandle) -> Result<LoadUserSetting
             success: true,
             settings: None,
             error: None,
+            hotkey_conflicts: Vec::new(),
         });
     }
 
@@ -44,6 +49,7 @@ pub async fn load_user_settings(app: tauri::AppHandle) -> Result<LoadUserSetting
                 success: false,
                 settings: None,
                 error: Some(format!("Failed to open settings: {}", e)),
+                hotkey_conflicts: Vec::new(),
             });
         }
     };
@@ -55,10 +61,11 @@ pub async fn load_user_settings(app: tauri::AppHandle) -> Result<LoadUserSetting
             success: false,
             settings: None,
             error: Some(format!("Failed to read settings: {}", e)),
+            hotkey_conflicts: Vec::new(),
         });
     }
 
-    let settings: UserSettings = match serde_json::from_str(&contents) {
+    let mut settings: UserSettings = match serde_json::from_str(&contents) {
         Ok(s) => s,
         Err(e) => {
             log::warn!("Failed to parse user settings: {}", e);
@@ -66,10 +73,13 @@ pub async fn load_user_settings(app: tauri::AppHandle) -> Result<LoadUserSetting
                 success: false,
                 settings: None,
                 error: Some(format!("Failed to parse settings: {}", e)),
+                hotkey_conflicts: Vec::new(),
             });
         }
     };
 
+    let hotkey_conflicts = sanitize_hotkeys(&mut settings);
+
     // Cache settings for keyboard hook access
     if let Ok(mut cache) = USER_SETTINGS.write() {
         *cache = Some(settings.clone());
@@ -80,15 +90,28 @@ pub async fn load_user_settings(app: tauri::AppHandle) -> Result<LoadUserSetting
         success: true,
         settings: Some(settings),
         error: None,
+        hotkey_conflicts,
     })
 }
 
-/// Save user settings to disk (atomic write via temp file)
-#[tauri::command]
-pub async fn save_user_settings(
-    app: tauri::AppHandle,
-    settings: UserSettings,
-) -> Result<SaveUserSettingsResult, String> {
+/// Validate hotkeys read from user-settings.json. Bindings with errors fall back to
+/// their defaults so the keyboard hook never uses them; the file itself is not changed.
+fn sanitize_hotkeys(settings: &mut UserSettings) -> Vec<HotkeyConflict> {
+    let report = reset_invalid_bindings(&mut settings.hotkeys);
+    for conflict in &report.conflicts {
+        log::warn!("Hotkey conflict: {}", conflict.message);
+    }
+    if !report.valid {
+        log::warn!(
+            "Using default bindings for: {}",
+            report.invalid_actions().join(", ")
+        );
+    }
+    report.conflicts
+}
+
+/// Write user settings to disk (atomic write via temp file)
+fn write_user_settings(app: &tauri::AppHandle, settings: &UserSettings) -> Result<(), String> {
     let data_dir = app
         .path()
         .app_data_dir()
@@ -101,7 +124,7 @@ pub async fn save_user_settings(
     let temp_path = path.with_extension("json.tmp");
 
     // Serialize settings
-    let json = serde_json::to_string_pretty(&settings)
+    let json = serde_json::to_string_pretty(settings)
         .map_err(|e| format!("Serialization failed: {}", e))?;
 
     // Write to temp file first (atomic write pattern)
@@ -115,7 +138,36 @@ pub async fn save_user_settings(
         .map_err(|e| format!("Failed to sync: {}", e))?;
 
     // Atomic rename
-    fs::rename(&temp_path, &path).map_err(|e| format!("Failed to rename: {}", e))?;
+    fs::rename(&temp_path, &path).map_err(|e| format!("Failed to rename: {}", e))
+}
+
+/// Save user settings to disk (atomic write via temp file)
+#[tauri::command]
+pub async fn save_user_settings(
+    app: tauri::AppHandle,
+    mut settings: UserSettings,
+) -> Result<SaveUserSettingsResult, String> {
+    // The active profile is owned by the backend (switch_profile); keep the cached value
+    // so a stale frontend copy cannot switch profiles behind its back
+    settings.active_profile = get_active_profile();
+
+    // Same checks as update_hotkeys: never persist bindings the hook would reject
+    let report = validate_hotkeys(&settings.hotkeys);
+    if !report.valid {
+        let errors: Vec<&str> = report
+            .conflicts
+            .iter()
+            .filter(|c| c.severity == DiagnosticSeverity::Error)
+            .map(|c| c.message.as_str())
+            .collect();
+        log::warn!("User settings not saved: {}", errors.join("; "));
+        return Ok(SaveUserSettingsResult {
+            success: false,
+            error: Some(format!("Invalid hotkeys: {}", errors.join("; "))),
+        });
+    }
+
+    write_user_settings(&app, &settings)?;
 
     // Update cache
     if let Ok(mut cache) = USER_SETTINGS.write() {
@@ -150,6 +202,35 @@ pub fn get_start_minimized() -> bool {
         .unwrap_or(false)
 }
 
+/// Get the active layout profile name from cached settings
+/// Returns the default profile if not set or on error
+pub fn get_active_profile() -> String {
+    USER_SETTINGS
+        .read()
+        .ok()
+        .and_then(|s| s.as_ref().map(|s| s.active_profile.clone()))
+        .unwrap_or_else(|| UserSettings::default().active_profile)
+}
+
+/// Record a new active layout profile and persist it to user-settings.json.
+/// The cache is only updated once the file has been written.
+pub fn set_active_profile(app: &tauri::AppHandle, name: &str) -> Result<(), String> {
+    let mut settings = USER_SETTINGS
+        .read()
+        .map_err(|_| "Failed to acquire settings lock".to_string())?
+        .clone()
+        .unwrap_or_default();
+
+    settings.active_profile = name.to_string();
+    settings.last_modified = chrono::Utc::now().to_rfc3339();
+    write_user_settings(app, &settings)?;
+
+    if let Ok(mut cache) = USER_SETTINGS.write() {
+        *cache = Some(settings);
+    }
+    Ok(())
+}
+
 /// Initialize user settings cache on startup
 pub fn init_user_settings(app: &tauri::AppHandle) {
     let path = match get_user_settings_path(app) {
@@ -172,7 +253,8 @@ pub fn init_user_settings(app: &tauri::AppHandle) {
         Ok(mut file) => {
             let mut contents = String::new();
             if file.read_to_string(&mut contents).is_ok() {
-                if let Ok(settings) = serde_json::from_str::<UserSettings>(&contents) {
+                if let Ok(mut settings) = serde_json::from_str::<UserSettings>(&contents) {
+                    sanitize_hotkeys(&mut settings);
                     if let Ok(mut cache) = USER_SETTINGS.write() {
                         *cache = Some(settings);
                     }
@@ -195,18 +277,33 @@ pub fn init_user_settings(app: &tauri::AppHandle) {
 /// T023-T024: Update hotkey bindings at runtime
 /// This updates the cached settings so the keyboard hook uses the new bindings
 /// @feature 042-sync-webview-hotkeys: Now emits hotkeys-updated event for frontend sync
+/// Bindings are validated first; if any conflict is an error nothing is applied and the
+/// report lists the conflicts for the settings UI
 #[tauri::command]
-pub fn update_hotkeys(app: tauri::AppHandle, hotkeys: HotkeySettings) -> Result<(), String> {
+pub fn update_hotkeys(
+    app: tauri::AppHandle,
+    hotkeys: HotkeySettings,
+) -> Result<HotkeyValidationReport, String> {
+    let report = validate_hotkeys(&hotkeys);
+    for conflict in &report.conflicts {
+        log::warn!("Hotkey conflict: {}", conflict.message);
+    }
+    if !report.valid {
+        log::warn!("Hotkey update rejected");
+        return Ok(report);
+    }
+
     if let Ok(mut cache) = USER_SETTINGS.write() {
         if let Some(ref mut settings) = *cache {
             settings.hotkeys = hotkeys.clone();
-            log::info!(
-                "Hotkeys updated: visibility={}/{}, mode={}/{}",
-                settings.hotkeys.toggle_visibility.key,
-                settings.hotkeys.toggle_visibility.key_code,
-                settings.hotkeys.toggle_mode.key,
-                settings.hotkeys.toggle_mode.key_code
-            );
+            let bindings: Vec<String> = settings
+                .hotkeys
+                .iter()
+                .map(|(action, binding)| {
+                    format!("{}={}/{}", action, binding.label(), binding.key_code)
+                })
+                .collect();
+            log::info!("Hotkeys updated: {}", bindings.join(", "));
         } else {
             // No settings cached, create with new hotkeys
             let mut new_settings = UserSettings::default();
@@ -218,11 +315,16 @@ pub fn update_hotkeys(app: tauri::AppHandle, hotkeys: HotkeySettings) -> Result<
         return Err("Failed to acquire settings lock".to_string());
     }
 
+    // Global-shortcut fallback: register the new bindings
+    if let Err(e) = crate::hotkey::refresh_shortcuts(&app) {
+        log::warn!("{}", e);
+    }
+
     // Emit event to frontend for real-time sync
     // @feature 042-sync-webview-hotkeys
     app.emit("hotkeys-updated", &hotkeys)
         .map_err(|e| format!("Failed to emit hotkeys-updated event: {}", e))?;
 
     log::info!("Hotkeys update event emitted to frontend");
-    Ok(())
+    Ok(report)
 }
@@ -0,0 +1,648 @@
+//! Strict validation of settings.toml
+//!
+//! Loading settings stays lenient (invalid values fall back to defaults), but this
+//! pass reports every problem it finds as a structured diagnostic: syntax errors,
+//! unknown keys (with a "did you mean" suggestion), wrong value types and values
+//! that are out of range. The report is logged at startup and on hot-reload, and
+//! is available to the frontend through the `validate_settings` command.
+
+use super::runtime::{get_settings_path, parse_log_level};
+use crate::platform::window_match::validate_rule;
+use serde::Serialize;
+
+/// Every key settings.toml understands
+pub const KNOWN_KEYS: &[&str] = &[
+    "target_window_name",
+    "debug_border",
+    "log_level",
+    "target_process_name",
+    "target_window_class",
+    "process_monitor_interval_ms",
+    "target_profiles",
+    "game_log_path",
+    "control_api",
+];
+
+/// Every key a [[target_profiles]] entry understands
+const PROFILE_KEYS: &[&str] = &["name", "process_name", "window_class", "window_title"];
+
+/// Every key the [control_api] table understands
+const CONTROL_API_KEYS: &[&str] = &["enabled", "port", "token"];
+
+/// Tokens shorter than this are easy to guess for any local process
+const MIN_RECOMMENDED_TOKEN_LEN: usize = 16;
+
+/// Intervals below this poll the window list often enough to cost noticeable CPU
+const MIN_RECOMMENDED_INTERVAL_MS: i64 = 100;
+
+/// Intervals above this make game launch detection feel unresponsive
+const MAX_RECOMMENDED_INTERVAL_MS: i64 = 60_000;
+
+/// Severity of a settings diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    /// The value (or, for syntax and type errors, the whole file) is ignored
+    Error,
+    /// The value is used but probably not what was intended
+    Warning,
+}
+
+/// A single problem found in settings.toml
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsDiagnostic {
+    /// Key the problem relates to (None for syntax errors)
+    pub key: Option<String>,
+    /// 1-based line number in settings.toml, if known
+    pub line: Option<u32>,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    /// Suggested replacement for an unknown key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+/// Result of the validate_settings command
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsValidationReport {
+    pub settings_path: Option<String>,
+    pub file_exists: bool,
+    /// True when there are no error-level diagnostics
+    pub valid: bool,
+    pub diagnostics: Vec<SettingsDiagnostic>,
+}
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+/// Levenshtein edit distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b_chars.len() + 1];
+        for (j, cb) in b_chars.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b_chars.len()]
+}
+
+/// Find the known key closest to `key`, if it is close enough to be a typo.
+pub fn suggest_key(key: &str) -> Option<&'static str> {
+    let key = key.to_lowercase();
+    let max_distance = (key.len() / 3).max(2);
+
+    KNOWN_KEYS
+        .iter()
+        .map(|known| (*known, edit_distance(&key, known)))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(known, _)| known)
+}
+
+/// 1-based line number of a byte offset.
+fn line_of_offset(contents: &str, offset: usize) -> u32 {
+    contents[..offset.min(contents.len())]
+        .bytes()
+        .filter(|b| *b == b'\n')
+        .count() as u32
+        + 1
+}
+
+/// 1-based line on which `key` is assigned (or declared as a table).
+fn find_key_line(contents: &str, key: &str) -> Option<u32> {
+    contents.lines().enumerate().find_map(|(index, line)| {
+        let line = line.trim_start();
+        let rest = line
+            .strip_prefix('[')
+            .map(|l| l.trim_start_matches('['))
+            .unwrap_or(line);
+        let rest = rest
+            .strip_prefix('"')
+            .and_then(|r| r.strip_prefix(key)?.strip_prefix('"'))
+            .or_else(|| rest.strip_prefix(key))?;
+        let rest = rest.trim_start();
+        (rest.starts_with('=') || rest.starts_with(']')).then_some(index as u32 + 1)
+    })
+}
+
+fn type_name(value: &toml::Value) -> &'static str {
+    match value {
+        toml::Value::String(_) => "a string",
+        toml::Value::Integer(_) => "an integer",
+        toml::Value::Float(_) => "a float",
+        toml::Value::Boolean(_) => "a boolean",
+        toml::Value::Datetime(_) => "a datetime",
+        toml::Value::Array(_) => "an array",
+        toml::Value::Table(_) => "a table",
+    }
+}
+
+/// A value of the wrong type makes the whole file fail to load, not just this key.
+fn type_error(key: &str, expected: &str, value: &toml::Value) -> String {
+    format!(
+        "{} must be {}, found {}; settings.toml is ignored until this is fixed",
+        key,
+        expected,
+        type_name(value)
+    )
+}
+
+/// Check the [[target_profiles]] array, returning (severity, message) problems.
+fn check_target_profiles(value: &toml::Value) -> Vec<(DiagnosticSeverity, String)> {
+    use DiagnosticSeverity::{Error, Warning};
+
+    let key = "target_profiles";
+    let Some(profiles) = value.as_array() else {
+        return vec![(Error, type_error(key, "an array of tables", value))];
+    };
+    if profiles.is_empty() {
+        return vec![(
+            Warning,
+            "target_profiles is empty, the target_* settings are used".to_string(),
+        )];
+    }
+
+    let mut problems = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+    for (index, profile) in profiles.iter().enumerate() {
+        let label = format!("target_profiles #{}", index + 1);
+        let Some(table) = profile.as_table() else {
+            problems.push((Error, type_error(&label, "a table", profile)));
+            continue;
+        };
+
+        for (field, field_value) in table {
+            if !PROFILE_KEYS.contains(&field.as_str()) {
+                problems.push((
+                    Warning,
+                    format!("Unknown key '{}' in {} is ignored", field, label),
+                ));
+            } else if let Some(value) = field_value.as_str() {
+                let rule_error = match field.as_str() {
+                    "name" => None,
+                    _ => validate_rule(value).err(),
+                };
+                if let Some(e) = rule_error {
+                    problems.push((
+                        Error,
+                        format!("{}.{} is {}, no window will match", label, field, e),
+                    ));
+                }
+            } else {
+                let field_key = format!("{}.{}", label, field);
+                problems.push((Error, type_error(&field_key, "a string", field_value)));
+            }
+        }
+
+        let field = |name: &str| {
+            table
+                .get(name)
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.trim().is_empty())
+        };
+        match (field("name"), field("process_name")) {
+            (None, _) => problems.push((Error, format!("{} has no name and is ignored", label))),
+            (Some(name), None) => problems.push((
+                Error,
+                format!("{} ('{}') has no process_name and is ignored", label, name),
+            )),
+            (Some(name), Some(_)) => {
+                if names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                    problems.push((
+                        Warning,
+                        format!("Duplicate profile name '{}' in {} is ignored", name, label),
+                    ));
+                } else {
+                    names.push(name.to_string());
+                }
+            }
+        }
+    }
+    problems
+}
+
+/// Check the [control_api] table, returning (severity, message) problems.
+fn check_control_api(value: &toml::Value) -> Vec<(DiagnosticSeverity, String)> {
+    use DiagnosticSeverity::{Error, Warning};
+
+    let Some(table) = value.as_table() else {
+        return vec![(Error, type_error("control_api", "a table", value))];
+    };
+
+    let mut problems = Vec::new();
+    for (field, field_value) in table {
+        let key = format!("control_api.{}", field);
+        match field.as_str() {
+            "enabled" if !field_value.is_bool() => {
+                problems.push((Error, type_error(&key, "true or false", field_value)))
+            }
+            "port" => match field_value.as_integer() {
+                Some(port) if (1..=u16::MAX as i64).contains(&port) => {}
+                Some(_) => problems.push((
+                    Error,
+                    type_error(&key, "a port number (1-65535)", field_value),
+                )),
+                None => problems.push((Error, type_error(&key, "an integer", field_value))),
+            },
+            "token" if !field_value.is_str() => {
+                problems.push((Error, type_error(&key, "a string", field_value)))
+            }
+            _ if !CONTROL_API_KEYS.contains(&field.as_str()) => problems.push((
+                Warning,
+                format!("Unknown key '{}' in control_api is ignored", field),
+            )),
+            _ => {}
+        }
+    }
+
+    let enabled = table.get("enabled").and_then(|v| v.as_bool()) == Some(true);
+    let token = table
+        .get("token")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .trim();
+    if enabled && token.is_empty() {
+        problems.push((
+            Error,
+            "control_api is enabled without a token; the server is not started".to_string(),
+        ));
+    } else if enabled && token.len() < MIN_RECOMMENDED_TOKEN_LEN {
+        problems.push((
+            Warning,
+            format!(
+                "control_api.token is short; at least {} characters are recommended",
+                MIN_RECOMMENDED_TOKEN_LEN
+            ),
+        ));
+    }
+    problems
+}
+
+/// Check the value of a known key, returning (severity, message) problems.
+fn check_value(key: &str, value: &toml::Value) -> Vec<(DiagnosticSeverity, String)> {
+    use DiagnosticSeverity::{Error, Warning};
+
+    let mut problems = Vec::new();
+    match key {
+        "target_window_name" | "target_process_name" | "target_window_class" => match value {
+            toml::Value::String(s) if s.trim().is_empty() => problems.push((
+                Warning,
+                format!("{} is empty, the build default will be used", key),
+            )),
+            toml::Value::String(s) if key == "target_process_name" && !s.contains('.') => {
+                problems.push((
+                    Warning,
+                    format!(
+                        "'{}' has no file extension; process names usually look like 'StarCitizen.exe'",
+                        s
+                    ),
+                ))
+            }
+            toml::Value::String(s) => {
+                if let Err(e) = validate_rule(s) {
+                    problems.push((Error, format!("{} is {}, no window will match", key, e)))
+                }
+            }
+            other => problems.push((Error, type_error(key, "a string", other))),
+        },
+        "game_log_path" => match value {
+            toml::Value::String(s) if s.trim().is_empty() => {}
+            toml::Value::String(s) if !std::path::Path::new(s.trim()).is_file() => problems.push((
+                Warning,
+                format!("'{}' does not exist yet; the game creates it on launch", s),
+            )),
+            toml::Value::String(_) => {}
+            other => problems.push((Error, type_error(key, "a string", other))),
+        },
+        "debug_border" if !value.is_bool() => {
+            problems.push((Error, type_error(key, "true or false", value)))
+        }
+        "log_level" => match value {
+            toml::Value::String(s) if parse_log_level(s).is_none() => problems.push((
+                Error,
+                format!(
+                    "'{}' is not a log level; use TRACE, DEBUG, INFO, WARN or ERROR",
+                    s
+                ),
+            )),
+            toml::Value::String(_) => {}
+            other => problems.push((Error, type_error(key, "a string", other))),
+        },
+        "process_monitor_interval_ms" => match value {
+            toml::Value::Integer(ms) if *ms < 0 => {
+                problems.push((Error, type_error(key, "a positive integer", value)))
+            }
+            toml::Value::Integer(0) => problems.push((
+                Error,
+                format!(
+                    "process_monitor_interval_ms must be greater than 0, the default of {} ms is used",
+                    super::runtime::DEFAULT_PROCESS_MONITOR_INTERVAL_MS
+                ),
+            )),
+            toml::Value::Integer(ms) if *ms < MIN_RECOMMENDED_INTERVAL_MS => problems.push((
+                Warning,
+                format!(
+                    "{} ms is a very short interval and may cost noticeable CPU; at least {} ms is recommended",
+                    ms, MIN_RECOMMENDED_INTERVAL_MS
+                ),
+            )),
+            toml::Value::Integer(ms) if *ms > MAX_RECOMMENDED_INTERVAL_MS => problems.push((
+                Warning,
+                format!(
+                    "{} ms is a very long interval; game launches will be detected late",
+                    ms
+                ),
+            )),
+            toml::Value::Integer(_) => {}
+            other => problems.push((Error, type_error(key, "an integer", other))),
+        },
+        "target_profiles" => problems.extend(check_target_profiles(value)),
+        "control_api" => problems.extend(check_control_api(value)),
+        _ => {}
+    }
+    problems
+}
+
+/// Validate settings.toml contents and return every problem found.
+pub fn validate_settings_str(contents: &str) -> Vec<SettingsDiagnostic> {
+    let table = match contents.parse::<toml::Table>() {
+        Ok(table) => table,
+        Err(e) => {
+            return vec![SettingsDiagnostic {
+                key: None,
+                line: e.span().map(|span| line_of_offset(contents, span.start)),
+                severity: DiagnosticSeverity::Error,
+                message: format!(
+                    "settings.toml is not valid TOML, all settings use defaults: {}",
+                    e.message()
+                ),
+                suggestion: None,
+            }];
+        }
+    };
+
+    let mut diagnostics = Vec::new();
+    for (key, value) in &table {
+        let line = find_key_line(contents, key);
+
+        if !KNOWN_KEYS.contains(&key.as_str()) {
+            let suggestion = suggest_key(key).map(String::from);
+            let message = match &suggestion {
+                Some(s) => format!("Unknown key '{}' is ignored, did you mean '{}'?", key, s),
+                None => format!("Unknown key '{}' is ignored", key),
+            };
+            diagnostics.push(SettingsDiagnostic {
+                key: Some(key.clone()),
+                line,
+                severity: DiagnosticSeverity::Warning,
+                message,
+                suggestion,
+            });
+            continue;
+        }
+
+        for (severity, message) in check_value(key, value) {
+            diagnostics.push(SettingsDiagnostic {
+                key: Some(key.clone()),
+                line,
+                severity,
+                message,
+                suggestion: None,
+            });
+        }
+    }
+
+    diagnostics.sort_by_key(|d| d.line.unwrap_or(0));
+    diagnostics
+}
+
+/// Validate the settings.toml next to the executable.
+pub fn validate_settings_file() -> SettingsValidationReport {
+    let path = get_settings_path();
+    let settings_path = path.as_ref().map(|p| p.to_string_lossy().to_string());
+
+    let Some(path) = path.filter(|p| p.exists()) else {
+        return SettingsValidationReport {
+            settings_path,
+            file_exists: false,
+            valid: true,
+            diagnostics: Vec::new(),
+        };
+    };
+
+    let diagnostics = match std::fs::read_to_string(&path) {
+        Ok(contents) => validate_settings_str(&contents),
+        Err(e) => vec![SettingsDiagnostic {
+            key: None,
+            line: None,
+            severity: DiagnosticSeverity::Error,
+            message: format!("Failed to read settings.toml: {}", e),
+            suggestion: None,
+        }],
+    };
+
+    SettingsValidationReport {
+        settings_path,
+        file_exists: true,
+        valid: !diagnostics
+            .iter()
+            .any(|d| d.severity == DiagnosticSeverity::Error),
+        diagnostics,
+    }
+}
+
+/// Log every diagnostic in a validation report.
+pub fn log_validation_report(report: &SettingsValidationReport) {
+    if report.diagnostics.is_empty() {
+        if report.file_exists {
+            log::info!("settings.toml validated: no problems found");
+        }
+        return;
+    }
+
+    log::warn!("settings.toml has {} problem(s):", report.diagnostics.len());
+    for diagnostic in &report.diagnostics {
+        let location = match (&diagnostic.key, diagnostic.line) {
+            (Some(key), Some(line)) => format!("line {} ({})", line, key),
+            (Some(key), None) => key.clone(),
+            (None, Some(line)) => format!("line {}", line),
+            (None, None) => "settings.toml".to_string(),
+        };
+        match diagnostic.severity {
+            DiagnosticSeverity::Error => log::error!("  {}: {}", location, diagnostic.message),
+            DiagnosticSeverity::Warning => log::warn!("  {}: {}", location, diagnostic.message),
+        }
+    }
+}
+
+/// Validate settings.toml and return a diagnostics report.
+#[tauri::command]
+pub fn validate_settings() -> SettingsValidationReport {
+    validate_settings_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_file_has_no_diagnostics() {
+        let toml = r#"
+target_window_name = "Star Citizen"
+debug_border = false
+log_level = "info"
+target_process_name = "StarCitizen.exe"
+process_monitor_interval_ms = 1000
+"#;
+        assert!(validate_settings_str(toml).is_empty());
+    }
+
+    #[test]
+    fn test_syntax_error_reports_line() {
+        let toml = "log_level = \"INFO\"\ntarget_window_name = \"Missing quote\n";
+        let diagnostics = validate_settings_str(toml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert!(diagnostics[0].key.is_none());
+    }
+
+    #[test]
+    fn test_unknown_key_suggestion() {
+        let diagnostics = validate_settings_str("\n\ntarget_proces_name = \"x.exe\"\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Warning);
+        assert_eq!(diagnostics[0].line, Some(3));
+        assert_eq!(
+            diagnostics[0].suggestion.as_deref(),
+            Some("target_process_name")
+        );
+
+        let diagnostics = validate_settings_str("completely_unrelated = 1");
+        assert!(diagnostics[0].suggestion.is_none());
+    }
+
+    #[test]
+    fn test_wrong_types_and_values() {
+        let toml = r#"
+debug_border = "yes"
+log_level = "VERBOSE"
+process_monitor_interval_ms = 0
+"#;
+        let diagnostics = validate_settings_str(toml);
+        let keys: Vec<_> = diagnostics.iter().map(|d| d.key.as_deref()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                Some("debug_border"),
+                Some("log_level"),
+                Some("process_monitor_interval_ms")
+            ]
+        );
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.severity == DiagnosticSeverity::Error));
+        assert_eq!(diagnostics[2].line, Some(4));
+    }
+
+    #[test]
+    fn test_questionable_values_are_warnings() {
+        let toml = "process_monitor_interval_ms = 10\ntarget_window_name = \"\"\n";
+        let diagnostics = validate_settings_str(toml);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.severity == DiagnosticSeverity::Warning));
+    }
+
+    #[test]
+    fn test_target_profiles() {
+        let toml = r#"
+[[target_profiles]]
+name = "LIVE"
+process_name = "StarCitizen.exe"
+
+[[target_profiles]]
+name = "PTU"
+
+[[target_profiles]]
+name = "live"
+process_name = "StarCitizen.exe"
+priority = 1
+"#;
+        let diagnostics = validate_settings_str(toml);
+        let severities: Vec<_> = diagnostics.iter().map(|d| d.severity).collect();
+        assert_eq!(
+            severities,
+            vec![
+                DiagnosticSeverity::Error,
+                DiagnosticSeverity::Warning,
+                DiagnosticSeverity::Warning
+            ]
+        );
+        assert!(diagnostics[0].message.contains("no process_name"));
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.key.as_deref() == Some("target_profiles") && d.line == Some(2)));
+
+        let diagnostics = validate_settings_str("target_profiles = \"LIVE\"");
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+    }
+
+    #[test]
+    fn test_control_api() {
+        let toml = r#"
+[control_api]
+enabled = true
+port = 70000
+"#;
+        let diagnostics = validate_settings_str(toml);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics[0].message.contains("port number"));
+        assert!(diagnostics[1].message.contains("without a token"));
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.key.as_deref() == Some("control_api") && d.line == Some(2)));
+
+        let diagnostics = validate_settings_str("[control_api]\nenabled = true\ntoken = \"abc\"\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Warning);
+    }
+
+    #[test]
+    fn test_invalid_match_rules() {
+        let toml = r#"
+target_window_class = "regex:(CryENGINE"
+
+[[target_profiles]]
+name = "PTU"
+process_name = "glob:StarCitizen_*.exe"
+window_title = "regex:[unclosed"
+"#;
+        let diagnostics = validate_settings_str(toml);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.severity == DiagnosticSeverity::Error));
+        assert!(diagnostics[1].message.contains("window_title"));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("log_level", "log_level"), 0);
+        assert_eq!(edit_distance("loglevel", "log_level"), 1);
+        assert_eq!(edit_distance("debug_boarder", "debug_border"), 1);
+        assert_eq!(suggest_key("LOG_LEVL"), Some("log_level"));
+    }
+}
@@ -0,0 +1,11 @@
+// Timers module - stopwatches and countdowns owned by the backend
+// The store holds the timers and their operations; the service persists them, pushes
+// changes to the frontend and drives the chronometer from the hotkey actions.
+
+pub mod service;
+pub mod store;
+pub mod types;
+
+// Re-exports for public API
+pub use service::*;
+pub use types::*;
@@ -0,0 +1,204 @@
+// Timer service - owns the timers for the whole app
+// Timers are stored in timers.json in the app data directory after every change, and
+// each change is pushed to the frontend as timer-changed. The chronometer hotkey actions
+// are handled here, so they work while the overlay is hidden or the webview reloads.
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use tauri::{AppHandle, Listener};
+
+use super::store::TimerStore;
+use super::types::*;
+use crate::hotkey::registry::{registered_actions, CHRONOMETER_RESET, CHRONOMETER_START_PAUSE};
+use crate::persistence::app_data::{emit_event, AppDataFile};
+
+const TIMERS_FILE: &str = "timers.json";
+
+/// How often running countdowns are checked for completion
+const TICK_INTERVAL: Duration = Duration::from_millis(250);
+
+struct TimerService {
+    app: AppHandle,
+    store: TimerStore,
+    file: AppDataFile,
+}
+
+/// The timer service (None until start_timer_service runs)
+static SERVICE: Mutex<Option<TimerService>> = Mutex::new(None);
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+impl TimerService {
+    fn save(&self) {
+        self.file.save(self.store.to_json());
+    }
+}
+
+/// Load the timers and start handling the chronometer hotkeys (call once at startup)
+pub fn start_timer_service(app: &AppHandle) {
+    let file = AppDataFile::open(app, TIMERS_FILE);
+    let store = file.load(TimerStore::from_json);
+    log::info!(
+        "Timer service started with {} timer(s)",
+        store.snapshots(now_ms()).len()
+    );
+    *SERVICE.lock().unwrap_or_else(|p| p.into_inner()) = Some(TimerService {
+        app: app.clone(),
+        store,
+        file,
+    });
+
+    listen_for_hotkeys(app);
+
+    thread::spawn(|| loop {
+        thread::sleep(TICK_INTERVAL);
+        expire_countdowns();
+    });
+}
+
+// The hotkey paths (keyboard hook, global shortcuts, command line) emit the action event;
+// the chronometer is driven from here instead of the frontend
+fn listen_for_hotkeys(app: &AppHandle) {
+    for action in registered_actions() {
+        let apply: fn(&mut TimerStore, &str, u64) -> Result<(), String> = match action.id.as_str() {
+            CHRONOMETER_START_PAUSE => TimerStore::toggle,
+            CHRONOMETER_RESET => |store, id, _| store.reset(id),
+            _ => continue,
+        };
+        app.listen_any(action.event.clone(), move |_| {
+            if let Err(e) = update_timer(CHRONOMETER_ID, apply) {
+                log::warn!("Chronometer hotkey failed: {}", e);
+            }
+        });
+    }
+}
+
+fn expire_countdowns() {
+    let (app, finished) = {
+        let mut guard = SERVICE.lock().unwrap_or_else(|p| p.into_inner());
+        let Some(service) = guard.as_mut() else {
+            return;
+        };
+        let now = now_ms();
+        let expired = service.store.expire(now);
+        if expired.is_empty() {
+            return;
+        }
+        service.save();
+        let finished: Vec<TimerSnapshot> = expired
+            .iter()
+            .filter_map(|id| service.store.snapshot(id, now))
+            .collect();
+        (service.app.clone(), finished)
+    };
+
+    for snapshot in finished {
+        log::info!("Countdown '{}' finished", snapshot.id);
+        emit_event(&app, TIMER_CHANGED_EVENT, &snapshot);
+        emit_event(&app, TIMER_FINISHED_EVENT, &snapshot);
+    }
+}
+
+/// Apply an operation to a timer, save, and push the new state to the frontend
+fn update_timer(
+    id: &str,
+    apply: impl FnOnce(&mut TimerStore, &str, u64) -> Result<(), String>,
+) -> Result<TimerSnapshot, String> {
+    let (app, snapshot) = {
+        let mut guard = SERVICE.lock().unwrap_or_else(|p| p.into_inner());
+        let service = guard
+            .as_mut()
+            .ok_or_else(|| "Timer service is not running".to_string())?;
+
+        let now = now_ms();
+        apply(&mut service.store, id, now)?;
+        service.save();
+
+        let snapshot = service
+            .store
+            .snapshot(id, now)
+            .ok_or_else(|| format!("Unknown timer '{}'", id))?;
+        (service.app.clone(), snapshot)
+    };
+
+    emit_event(&app, TIMER_CHANGED_EVENT, &snapshot);
+    Ok(snapshot)
+}
+
+// ============================================================================
+// Tauri commands
+// ============================================================================
+
+/// List all timers, ordered by ID
+#[tauri::command]
+pub fn list_timers() -> Vec<TimerSnapshot> {
+    let guard = SERVICE.lock().unwrap_or_else(|p| p.into_inner());
+    guard
+        .as_ref()
+        .map(|service| service.store.snapshots(now_ms()))
+        .unwrap_or_default()
+}
+
+/// Create a stopped timer (duration_ms is required for countdowns)
+#[tauri::command]
+pub fn create_timer(
+    id: String,
+    kind: TimerKind,
+    duration_ms: Option<u64>,
+) -> Result<TimerSnapshot, String> {
+    update_timer(&id, |store, id, _| {
+        store.create(id, kind, duration_ms.unwrap_or(0))
+    })
+}
+
+#[tauri::command]
+pub fn start_timer(id: String) -> Result<TimerSnapshot, String> {
+    update_timer(&id, TimerStore::start)
+}
+
+#[tauri::command]
+pub fn pause_timer(id: String) -> Result<TimerSnapshot, String> {
+    update_timer(&id, TimerStore::pause)
+}
+
+#[tauri::command]
+pub fn toggle_timer(id: String) -> Result<TimerSnapshot, String> {
+    update_timer(&id, TimerStore::toggle)
+}
+
+#[tauri::command]
+pub fn reset_timer(id: String) -> Result<TimerSnapshot, String> {
+    update_timer(&id, |store, id, _| store.reset(id))
+}
+
+/// Set the time of a stopped stopwatch
+#[tauri::command]
+pub fn set_timer_elapsed(id: String, elapsed_ms: u64) -> Result<TimerSnapshot, String> {
+    update_timer(&id, |store, id, _| store.set_elapsed(id, elapsed_ms))
+}
+
+#[tauri::command]
+pub fn lap_timer(id: String) -> Result<TimerSnapshot, String> {
+    update_timer(&id, TimerStore::lap)
+}
+
+#[tauri::command]
+pub fn delete_timer(id: String) -> Result<(), String> {
+    let app = {
+        let mut guard = SERVICE.lock().unwrap_or_else(|p| p.into_inner());
+        let service = guard
+            .as_mut()
+            .ok_or_else(|| "Timer service is not running".to_string())?;
+        service.store.remove(&id)?;
+        service.save();
+        service.app.clone()
+    };
+
+    log::info!("Timer '{}' deleted", id);
+    emit_event(&app, TIMER_REMOVED_EVENT, TimerRemovedPayload { id });
+    Ok(())
+}
@@ -0,0 +1,321 @@
+// Timer store - the timers and their operations, independent of Tauri
+// Every operation takes the current time (epoch milliseconds), so the service decides
+// what "now" is and the tests do not depend on the clock.
+
+use std::collections::BTreeMap;
+
+use super::types::{Timer, TimerKind, TimerSnapshot, CHRONOMETER_ID};
+
+/// Longest accepted timer ID
+const MAX_ID_LEN: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimerStore {
+    timers: BTreeMap<String, Timer>,
+}
+
+impl Default for TimerStore {
+    fn default() -> Self {
+        let mut timers = BTreeMap::new();
+        timers.insert(
+            CHRONOMETER_ID.to_string(),
+            new_timer(CHRONOMETER_ID, TimerKind::Stopwatch, 0),
+        );
+        Self { timers }
+    }
+}
+
+fn new_timer(id: &str, kind: TimerKind, duration_ms: u64) -> Timer {
+    Timer {
+        id: id.to_string(),
+        kind,
+        duration_ms,
+        accumulated_ms: 0,
+        running_since_ms: None,
+        laps: Vec::new(),
+        finished: false,
+    }
+}
+
+fn elapsed_ms(timer: &Timer, now_ms: u64) -> u64 {
+    let running = timer
+        .running_since_ms
+        .map(|since| now_ms.saturating_sub(since))
+        .unwrap_or(0);
+    let elapsed = timer.accumulated_ms.saturating_add(running);
+    match timer.kind {
+        TimerKind::Stopwatch => elapsed,
+        TimerKind::Countdown => elapsed.min(timer.duration_ms),
+    }
+}
+
+impl TimerStore {
+    /// Rebuild the store from timers.json contents (the chronometer is always present)
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let timers: Vec<Timer> =
+            serde_json::from_str(json).map_err(|e| format!("Invalid timers file: {}", e))?;
+        let mut store = Self::default();
+        for timer in timers {
+            store.timers.insert(timer.id.clone(), timer);
+        }
+        Ok(store)
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        let timers: Vec<&Timer> = self.timers.values().collect();
+        serde_json::to_string_pretty(&timers)
+            .map_err(|e| format!("Failed to serialize timers: {}", e))
+    }
+
+    fn get_mut(&mut self, id: &str) -> Result<&mut Timer, String> {
+        self.timers
+            .get_mut(id)
+            .ok_or_else(|| format!("Unknown timer '{}'", id))
+    }
+
+    pub fn snapshot(&self, id: &str, now_ms: u64) -> Option<TimerSnapshot> {
+        let timer = self.timers.get(id)?;
+        let elapsed = elapsed_ms(timer, now_ms);
+        let countdown = timer.kind == TimerKind::Countdown;
+        Some(TimerSnapshot {
+            id: timer.id.clone(),
+            kind: timer.kind,
+            running: timer.running_since_ms.is_some(),
+            elapsed_ms: elapsed,
+            accumulated_ms: timer.accumulated_ms,
+            running_since_ms: timer.running_since_ms,
+            duration_ms: countdown.then_some(timer.duration_ms),
+            remaining_ms: countdown.then(|| timer.duration_ms - elapsed),
+            laps: timer.laps.clone(),
+            finished: timer.finished,
+        })
+    }
+
+    /// Snapshots of all timers, ordered by ID
+    pub fn snapshots(&self, now_ms: u64) -> Vec<TimerSnapshot> {
+        self.timers
+            .keys()
+            .filter_map(|id| self.snapshot(id, now_ms))
+            .collect()
+    }
+
+    pub fn create(&mut self, id: &str, kind: TimerKind, duration_ms: u64) -> Result<(), String> {
+        if id.trim().is_empty() || id.len() > MAX_ID_LEN {
+            return Err(format!(
+                "Timer ID must be 1 to {} characters long",
+                MAX_ID_LEN
+            ));
+        }
+        if self.timers.contains_key(id) {
+            return Err(format!("Timer '{}' already exists", id));
+        }
+        let duration_ms = match kind {
+            TimerKind::Stopwatch => 0,
+            TimerKind::Countdown if duration_ms == 0 => {
+                return Err("A countdown needs a duration".to_string())
+            }
+            TimerKind::Countdown => duration_ms,
+        };
+        self.timers
+            .insert(id.to_string(), new_timer(id, kind, duration_ms));
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<(), String> {
+        if id == CHRONOMETER_ID {
+            return Err("The chronometer cannot be removed".to_string());
+        }
+        self.timers
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| format!("Unknown timer '{}'", id))
+    }
+
+    /// Start or resume a timer. A finished countdown starts over.
+    pub fn start(&mut self, id: &str, now_ms: u64) -> Result<(), String> {
+        let timer = self.get_mut(id)?;
+        if timer.running_since_ms.is_some() {
+            return Ok(());
+        }
+        if timer.finished {
+            *timer = new_timer(id, timer.kind, timer.duration_ms);
+        }
+        timer.running_since_ms = Some(now_ms);
+        Ok(())
+    }
+
+    pub fn pause(&mut self, id: &str, now_ms: u64) -> Result<(), String> {
+        let timer = self.get_mut(id)?;
+        if timer.running_since_ms.is_some() {
+            timer.accumulated_ms = elapsed_ms(timer, now_ms);
+            timer.running_since_ms = None;
+        }
+        Ok(())
+    }
+
+    pub fn toggle(&mut self, id: &str, now_ms: u64) -> Result<(), String> {
+        let running = self.get_mut(id)?.running_since_ms.is_some();
+        if running {
+            self.pause(id, now_ms)
+        } else {
+            self.start(id, now_ms)
+        }
+    }
+
+    /// Stop the timer and clear its time and laps
+    pub fn reset(&mut self, id: &str) -> Result<(), String> {
+        let timer = self.get_mut(id)?;
+        *timer = new_timer(id, timer.kind, timer.duration_ms);
+        Ok(())
+    }
+
+    /// Set the time of a stopped stopwatch (e.g. carried over from an older version)
+    pub fn set_elapsed(&mut self, id: &str, elapsed_ms: u64) -> Result<(), String> {
+        let timer = self.get_mut(id)?;
+        if timer.kind != TimerKind::Stopwatch {
+            return Err(format!("Timer '{}' is not a stopwatch", id));
+        }
+        if timer.running_since_ms.is_some() {
+            return Err(format!("Timer '{}' is running", id));
+        }
+        timer.accumulated_ms = elapsed_ms;
+        Ok(())
+    }
+
+    /// Record the current elapsed time as a lap (running timers only)
+    pub fn lap(&mut self, id: &str, now_ms: u64) -> Result<(), String> {
+        let timer = self.get_mut(id)?;
+        if timer.running_since_ms.is_none() {
+            return Err(format!("Timer '{}' is not running", id));
+        }
+        let elapsed = elapsed_ms(timer, now_ms);
+        timer.laps.push(elapsed);
+        Ok(())
+    }
+
+    /// Stop the running countdowns that reached zero and return their IDs
+    pub fn expire(&mut self, now_ms: u64) -> Vec<String> {
+        let mut expired = Vec::new();
+        for timer in self.timers.values_mut() {
+            if timer.kind == TimerKind::Countdown
+                && timer.running_since_ms.is_some()
+                && elapsed_ms(timer, now_ms) >= timer.duration_ms
+            {
+                timer.accumulated_ms = timer.duration_ms;
+                timer.running_since_ms = None;
+                timer.finished = true;
+                expired.push(timer.id.clone());
+            }
+        }
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stopwatch_start_pause_lap() {
+        let mut store = TimerStore::default();
+        store.start(CHRONOMETER_ID, 1_000).unwrap();
+        store.lap(CHRONOMETER_ID, 3_500).unwrap();
+        store.pause(CHRONOMETER_ID, 5_000).unwrap();
+
+        // Paused: time does not move
+        let snapshot = store.snapshot(CHRONOMETER_ID, 60_000).unwrap();
+        assert!(!snapshot.running);
+        assert_eq!(snapshot.elapsed_ms, 4_000);
+        assert_eq!(snapshot.laps, vec![2_500]);
+        assert_eq!(snapshot.duration_ms, None);
+
+        // Resumed: counts on from the accumulated time
+        store.toggle(CHRONOMETER_ID, 10_000).unwrap();
+        let snapshot = store.snapshot(CHRONOMETER_ID, 11_000).unwrap();
+        assert!(snapshot.running);
+        assert_eq!(snapshot.elapsed_ms, 5_000);
+        assert_eq!(snapshot.running_since_ms, Some(10_000));
+
+        store.reset(CHRONOMETER_ID).unwrap();
+        let snapshot = store.snapshot(CHRONOMETER_ID, 12_000).unwrap();
+        assert_eq!((snapshot.running, snapshot.elapsed_ms), (false, 0));
+        assert!(snapshot.laps.is_empty());
+        assert!(store.lap(CHRONOMETER_ID, 12_000).is_err());
+
+        // Time carried over from the frontend's old storage
+        store.set_elapsed(CHRONOMETER_ID, 90_000).unwrap();
+        assert_eq!(
+            store.snapshot(CHRONOMETER_ID, 0).unwrap().elapsed_ms,
+            90_000
+        );
+        store.start(CHRONOMETER_ID, 20_000).unwrap();
+        assert!(store.set_elapsed(CHRONOMETER_ID, 0).is_err());
+    }
+
+    #[test]
+    fn test_countdown_expires() {
+        let mut store = TimerStore::default();
+        store
+            .create("beacon", TimerKind::Countdown, 10_000)
+            .unwrap();
+        store.start("beacon", 0).unwrap();
+
+        assert!(store.expire(9_999).is_empty());
+        let snapshot = store.snapshot("beacon", 4_000).unwrap();
+        assert_eq!(snapshot.remaining_ms, Some(6_000));
+
+        assert_eq!(store.expire(12_000), vec!["beacon".to_string()]);
+        let snapshot = store.snapshot("beacon", 20_000).unwrap();
+        assert!(snapshot.finished);
+        assert!(!snapshot.running);
+        assert_eq!(snapshot.remaining_ms, Some(0));
+        // Only reported once
+        assert!(store.expire(30_000).is_empty());
+
+        // Starting a finished countdown starts it over
+        store.start("beacon", 40_000).unwrap();
+        let snapshot = store.snapshot("beacon", 41_000).unwrap();
+        assert!(!snapshot.finished);
+        assert_eq!(snapshot.remaining_ms, Some(9_000));
+    }
+
+    #[test]
+    fn test_create_and_remove() {
+        let mut store = TimerStore::default();
+        assert!(store.create("", TimerKind::Stopwatch, 0).is_err());
+        assert!(store.create("x", TimerKind::Countdown, 0).is_err());
+        assert!(store
+            .create(CHRONOMETER_ID, TimerKind::Stopwatch, 0)
+            .is_err());
+        store.create("mining", TimerKind::Stopwatch, 500).unwrap();
+        // Stopwatches have no duration
+        assert_eq!(store.snapshot("mining", 0).unwrap().duration_ms, None);
+
+        assert!(store.remove(CHRONOMETER_ID).is_err());
+        store.remove("mining").unwrap();
+        assert!(store.remove("mining").is_err());
+        assert!(store.start("mining", 0).is_err());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut store = TimerStore::default();
+        store
+            .create("beacon", TimerKind::Countdown, 60_000)
+            .unwrap();
+        store.start("beacon", 1_000).unwrap();
+
+        let restored = TimerStore::from_json(&store.to_json().unwrap()).unwrap();
+        assert_eq!(restored, store);
+        // Still running after a restart
+        assert_eq!(
+            restored.snapshot("beacon", 31_000).unwrap().remaining_ms,
+            Some(30_000)
+        );
+
+        // Files without the chronometer get it back
+        let restored = TimerStore::from_json("[]").unwrap();
+        assert!(restored.snapshot(CHRONOMETER_ID, 0).is_some());
+        assert!(TimerStore::from_json("{").is_err());
+    }
+}
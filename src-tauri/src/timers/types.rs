@@ -0,0 +1,79 @@
+// Timer types - stored timers and the snapshots sent to the frontend
+
+use serde::{Deserialize, Serialize};
+
+/// ID of the built-in stopwatch driven by the chronometer hotkeys
+pub const CHRONOMETER_ID: &str = "chronometer";
+
+// Events emitted by the timer service
+pub const TIMER_CHANGED_EVENT: &str = "timer-changed";
+pub const TIMER_REMOVED_EVENT: &str = "timer-removed";
+pub const TIMER_FINISHED_EVENT: &str = "timer-finished";
+
+/// Events emitted by the timer service (forwarded by the control API)
+pub const TIMER_EVENT_NAMES: &[&str] = &[
+    TIMER_CHANGED_EVENT,
+    TIMER_REMOVED_EVENT,
+    TIMER_FINISHED_EVENT,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimerKind {
+    /// Counts up from zero
+    Stopwatch,
+    /// Counts down from its duration and stops when it reaches zero
+    Countdown,
+}
+
+/// A timer as stored in timers.json.
+/// Times are Unix epoch milliseconds; a running timer keeps counting while the app is
+/// closed, since it only stores when it was started.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Timer {
+    pub id: String,
+    pub kind: TimerKind,
+    /// Countdown length (0 for stopwatches)
+    #[serde(default)]
+    pub duration_ms: u64,
+    /// Time counted before the current run
+    #[serde(default)]
+    pub accumulated_ms: u64,
+    /// Start of the current run (None while paused)
+    #[serde(default)]
+    pub running_since_ms: Option<u64>,
+    /// Elapsed time at each lap
+    #[serde(default)]
+    pub laps: Vec<u64>,
+    /// Countdown reached zero
+    #[serde(default)]
+    pub finished: bool,
+}
+
+/// Timer state sent to the frontend (timer-changed, timer-finished, commands)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerSnapshot {
+    pub id: String,
+    pub kind: TimerKind,
+    pub running: bool,
+    /// Elapsed time when the snapshot was taken
+    pub elapsed_ms: u64,
+    /// Time counted before the current run; while running, the frontend shows
+    /// accumulated_ms + (now - running_since_ms)
+    pub accumulated_ms: u64,
+    pub running_since_ms: Option<u64>,
+    /// Countdown length and time left (None for stopwatches)
+    pub duration_ms: Option<u64>,
+    pub remaining_ms: Option<u64>,
+    pub laps: Vec<u64>,
+    pub finished: bool,
+}
+
+/// Payload of timer-removed
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerRemovedPayload {
+    pub id: String,
+}
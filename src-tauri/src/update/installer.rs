@@ -54,6 +54,8 @@ pub async fn launch_installer_and_exit(
                 log::info!("Installer launched successfully, exiting application");
                 // Give the installer a moment to start
                 std::thread::sleep(std::time::Duration::from_millis(500));
+                // Write out saves still held by the write-behind queue
+                crate::persistence::queue::flush_all();
                 // Exit the application
                 std::process::exit(0);
             }
@@ -10,11 +10,13 @@
  * @feature 027-widget-container
  */
 
+import { useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { motion, AnimatePresence, useReducedMotion } from "motion/react";
 import { Button } from "@/components/ui/button";
 import { ButtonGroup } from "@/components/ui/button-group";
 import { OverlayMode } from "@/types/overlay";
-import { WindowRect } from "@/types/ipc";
+import { OpenUrlPayload, WindowRect } from "@/types/ipc";
 import { windowEvents } from "@/lib/windowEvents";
 import { widgetEvents } from "@/lib/widgetEvents";
 import { NotesContent } from "@/components/windows/NotesContent";
@@ -78,6 +80,22 @@ export function MainMenu({
     });
   };
 
+  // Browser windows opened by the "open-url" rule action
+  useEffect(() => {
+    const unlisten = listen<OpenUrlPayload>("open-browser-url", (event) => {
+      log.info(`Opening Browser window on ${event.payload.url}`);
+      windowEvents.emit("window:open", {
+        component: BrowserContent,
+        title: "Browser",
+        contentType: "browser",
+        componentProps: { isInteractive: mode === "windowed", initialUrl: event.payload.url },
+      });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [mode, log.info]);
+
   const handleOpenFileViewer = () => {
     windowEvents.emit("window:open", {
       component: FileViewerContent,
@@ -114,6 +132,14 @@ export function MainMenu({
     });
   };
 
+  // Open alarm widget (reminders scheduled by the backend)
+  const handleOpenAlarmWidget = () => {
+    log.info("Opening Alarm widget");
+    widgetEvents.emit("widget:open", {
+      type: "alarm",
+    });
+  };
+
   // Handle scanlines toggle - feature 026-sc-hud-theme
   const handleScanlinesChange = (enabled: boolean) => {
     if (onScanlinesChange) {
@@ -160,6 +186,9 @@ export function MainMenu({
               <Button variant="secondary" onClick={handleOpenTimerWidget}>
                 Session Timer
               </Button>
+              <Button variant="secondary" onClick={handleOpenAlarmWidget}>
+                Alarms
+              </Button>
             </ButtonGroup>
           </div>
 
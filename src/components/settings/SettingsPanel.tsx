@@ -24,6 +24,8 @@ import type {
   UserSettings,
   LoadUserSettingsResult,
   HotkeySettings,
+  HotkeyConflict,
+  HotkeyValidationReport,
 } from "@/types/user-settings";
 import { DEFAULT_USER_SETTINGS } from "@/types/user-settings";
 
@@ -36,6 +38,7 @@ export function SettingsPanel() {
     toggleVisibility?: string;
     toggleMode?: string;
   }>({});
+  const [hotkeyConflicts, setHotkeyConflicts] = useState<HotkeyConflict[]>([]);
 
   // T021: Load settings on mount
   useEffect(() => {
@@ -55,6 +58,7 @@ export function SettingsPanel() {
       if (result.success && result.settings) {
         setSettings(result.settings);
       }
+      setHotkeyConflicts(result.hotkeyConflicts ?? []);
     } catch (e) {
       console.error("Failed to load settings:", e);
     } finally {
@@ -95,10 +99,16 @@ export function SettingsPanel() {
         lastModified: new Date().toISOString(),
       };
 
-      await invoke("save_user_settings", { settings: updatedSettings });
+      // Update hotkeys in the running keyboard hook (rejected if any binding conflicts)
+      const report = await invoke<HotkeyValidationReport>("update_hotkeys", {
+        hotkeys: settings.hotkeys,
+      });
+      setHotkeyConflicts(report.conflicts);
+      if (!report.valid) {
+        return;
+      }
 
-      // Update hotkeys in the running keyboard hook
-      await invoke("update_hotkeys", { hotkeys: settings.hotkeys });
+      await invoke("save_user_settings", { settings: updatedSettings });
 
       setSettings(updatedSettings);
     } catch (e) {
@@ -118,6 +128,7 @@ export function SettingsPanel() {
             [field]: binding,
           },
         }));
+        setHotkeyConflicts([]);
       },
     []
   );
@@ -184,6 +195,19 @@ export function SettingsPanel() {
               onChange={updateHotkey("toggleMode")}
               onValidationError={setHotkeyError("toggleMode")}
             />
+            {/* Conflicts reported by update_hotkeys or found on startup */}
+            {hotkeyConflicts.map((conflict) => (
+              <p
+                key={`${conflict.kind}:${conflict.actions.join(",")}`}
+                className={`text-xs text-right ${
+                  conflict.severity === "error"
+                    ? "text-red-400"
+                    : "text-yellow-400"
+                }`}
+              >
+                {conflict.message}
+              </p>
+            ))}
           </div>
         </section>
 
@@ -0,0 +1,106 @@
+'use client';
+
+/**
+ * AlarmWidgetContent Component
+ *
+ * Lists the upcoming alarms with a countdown to each, and highlights
+ * alarms that just fired (including those caught up after the app was
+ * closed). In interactive mode alarms can be added ("Refinery", "3h 12m")
+ * and deleted.
+ *
+ * Alarms are scheduled by the backend, so they fire while the overlay is
+ * hidden and survive restarts.
+ */
+
+import { useState, type FormEvent } from 'react';
+import { X } from 'lucide-react';
+import { useAlarms, formatCountdown } from '@/hooks/useAlarms';
+import { ALARM_WIDGET_DEFAULTS } from '@/types/widgets';
+
+export interface AlarmWidgetContentProps {
+  isInteractive: boolean;
+  widgetId?: string;
+}
+
+export function AlarmWidgetContent({ isInteractive }: AlarmWidgetContentProps) {
+  const { alarms, fired, now, createAlarm, deleteAlarm } = useAlarms();
+  const [label, setLabel] = useState('');
+  const [duration, setDuration] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  const recentlyFired = fired.filter(
+    (alarm) => now - alarm.firedAtMs < ALARM_WIDGET_DEFAULTS.FIRED_HIGHLIGHT_MS
+  );
+
+  const handleSubmit = async (e: FormEvent) => {
+    e.preventDefault();
+    const result = await createAlarm(label, duration);
+    setError(result);
+    if (result === null) {
+      setLabel('');
+      setDuration('');
+    }
+  };
+
+  return (
+    // pointer-events: none on container for click-through on transparent areas
+    <div className="w-full h-full flex flex-col gap-1 p-2 text-sm pointer-events-none">
+      {recentlyFired.map((alarm) => (
+        <div
+          key={`${alarm.id}-${alarm.dueAtMs}`}
+          className="pointer-events-auto select-none font-orbitron text-amber-300 animate-pulse truncate"
+        >
+          {alarm.label}
+          {alarm.late && ' (missed)'}
+        </div>
+      ))}
+
+      {alarms.slice(0, ALARM_WIDGET_DEFAULTS.MAX_ALARMS).map((alarm) => (
+        <div
+          key={alarm.id}
+          className="pointer-events-auto cursor-move select-none flex items-center gap-2"
+        >
+          <span className="flex-1 truncate text-white/80">{alarm.label}</span>
+          <span className="font-orbitron liquid-glass-text">
+            {formatCountdown(alarm.dueAtMs - now)}
+          </span>
+          {isInteractive && (
+            <button
+              type="button"
+              onClick={() => deleteAlarm(alarm.id)}
+              className="text-white/50 hover:text-white"
+              aria-label={`Delete ${alarm.label}`}
+            >
+              <X className="w-3 h-3" />
+            </button>
+          )}
+        </div>
+      ))}
+
+      {alarms.length === 0 && recentlyFired.length === 0 && (
+        <div className="text-white/50 select-none">No alarms</div>
+      )}
+
+      {isInteractive && (
+        <form onSubmit={handleSubmit} className="pointer-events-auto mt-auto flex gap-1">
+          <input
+            value={label}
+            onChange={(e) => setLabel(e.target.value)}
+            placeholder="Label"
+            className="min-w-0 flex-1 bg-black/40 border border-white/20 rounded px-1 text-white"
+          />
+          <input
+            value={duration}
+            onChange={(e) => setDuration(e.target.value)}
+            placeholder="3h 12m"
+            className="w-16 bg-black/40 border border-white/20 rounded px-1 text-white"
+          />
+          <button type="submit" className="px-2 rounded bg-white/20 hover:bg-white/30 text-white">
+            Add
+          </button>
+        </form>
+      )}
+      {isInteractive && error && <div className="text-red-400 text-xs">{error}</div>}
+    </div>
+  );
+}
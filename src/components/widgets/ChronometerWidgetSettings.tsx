@@ -15,6 +15,8 @@ import { X, Trash2 } from 'lucide-react';
 import type {
   HotkeySettings,
   HotkeyBinding,
+  HotkeyConflict,
+  HotkeyValidationReport,
   LoadUserSettingsResult,
 } from '@/types/user-settings';
 import { DEFAULT_USER_SETTINGS } from '@/types/user-settings';
@@ -47,6 +49,18 @@ const RESERVED_KEY_CODES = [
   0x74, // F5 - toggle mode
 ];
 
+/** Short error label for a backend hotkey conflict */
+function conflictLabel(conflict: HotkeyConflict): string {
+  switch (conflict.kind) {
+    case 'duplicate':
+      return 'Conflict';
+    case 'reserved':
+      return 'Reserved';
+    default:
+      return 'Invalid';
+  }
+}
+
 // ============================================================================
 // Compact Hotkey Input Sub-component
 // ============================================================================
@@ -240,6 +254,23 @@ export function ChronometerWidgetSettings({
         chronometerReset: resetBinding,
       };
 
+      // Apply first: the backend rejects conflicting bindings
+      const report = await invoke<HotkeyValidationReport>('update_hotkeys', {
+        hotkeys: updatedHotkeys,
+      });
+      if (!report.valid) {
+        for (const conflict of report.conflicts) {
+          if (conflict.severity !== 'error') continue;
+          if (conflict.actions.includes('chronometerStartPause')) {
+            setErrors((prev) => ({ ...prev, startPause: conflictLabel(conflict) }));
+          }
+          if (conflict.actions.includes('chronometerReset')) {
+            setErrors((prev) => ({ ...prev, reset: conflictLabel(conflict) }));
+          }
+        }
+        return;
+      }
+
       const result = await invoke<LoadUserSettingsResult>('load_user_settings');
       if (result.success && result.settings) {
         const updatedSettings = {
@@ -250,7 +281,6 @@ export function ChronometerWidgetSettings({
         await invoke('save_user_settings', { settings: updatedSettings });
       }
 
-      await invoke('update_hotkeys', { hotkeys: updatedHotkeys });
       setAllHotkeys(updatedHotkeys);
     } catch (err) {
       console.error('Failed to save chronometer hotkeys:', err);
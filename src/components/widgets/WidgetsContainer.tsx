@@ -17,6 +17,7 @@ import { Widget } from './Widget';
 import { ClockWidgetContent } from './ClockWidgetContent';
 import { SessionTimerWidgetContent } from './SessionTimerWidgetContent';
 import { ChronometerWidgetContent } from './ChronometerWidgetContent';
+import { AlarmWidgetContent } from './AlarmWidgetContent';
 import type { WidgetInstance, WidgetType } from '@/types/widgets';
 
 // ============================================================================
@@ -60,6 +61,13 @@ function renderWidgetContent(
           widgetId={widget.id}
         />
       );
+    case 'alarm':
+      return (
+        <AlarmWidgetContent
+          isInteractive={isInteractive}
+          widgetId={widget.id}
+        />
+      );
     default:
       // Fallback for unknown widget types
       return (
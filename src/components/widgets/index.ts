@@ -13,3 +13,4 @@ export { ClockWidgetContent } from './ClockWidgetContent';
 export { SessionTimerWidgetContent } from './SessionTimerWidgetContent';
 export { ChronometerWidgetContent } from './ChronometerWidgetContent';
 export { ChronometerWidgetSettings } from './ChronometerWidgetSettings';
+export { AlarmWidgetContent } from './AlarmWidgetContent';
@@ -26,6 +26,7 @@ import type {
 } from '@/types/widgets';
 import {
   WIDGET_CONSTANTS,
+  ALARM_WIDGET_DEFAULTS,
   generateWidgetId,
   clampWidgetOpacity,
   clampWidgetDimensions,
@@ -97,6 +98,8 @@ function getDefaultDimensions(type: WidgetType): { width: number; height: number
     case 'chronometer':
       // Chronometer needs space for compact hotkey settings panel
       return { width: 180, height: 180 };
+    case 'alarm':
+      return { width: ALARM_WIDGET_DEFAULTS.WIDTH, height: ALARM_WIDGET_DEFAULTS.HEIGHT };
     default:
       return { width: WIDGET_CONSTANTS.DEFAULT_WIDTH, height: WIDGET_CONSTANTS.DEFAULT_HEIGHT };
   }
@@ -110,6 +113,7 @@ function getDefaultOpacity(type: WidgetType): number {
     case 'clock':
     case 'timer':
     case 'chronometer':
+    case 'alarm':
       return 0.8;
     default:
       return WIDGET_CONSTANTS.DEFAULT_OPACITY;
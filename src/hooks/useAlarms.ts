@@ -0,0 +1,177 @@
+"use client";
+
+/**
+ * useAlarms Hook
+ *
+ * Follows the alarms scheduled by the Rust backend for the alarm widget.
+ * The backend owns the alarms: it stores them, fires them when due (also
+ * catching up on alarms that came due while the app was closed) and
+ * reports every change as alarms-updated.
+ *
+ * Features:
+ * - Upcoming alarms from list_alarms, kept current by alarms-updated events
+ * - Recently fired alarms from alarm-fired events and list_fired_alarms
+ * - Create alarms from a relative duration ("3h 12m") and delete them
+ * - Current time updated every second for countdown display
+ */
+
+import { useState, useEffect, useCallback } from 'react';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
+import { invoke } from '@tauri-apps/api/core';
+import { debug } from '@/lib/logger';
+import type { Alarm, AlarmFiredPayload, AlarmRepeat } from '@/types/ipc';
+import { ALARM_WIDGET_DEFAULTS } from '@/types/widgets';
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/**
+ * Return type for the useAlarms hook.
+ */
+export interface AlarmsResult {
+  /** Upcoming alarms, soonest first */
+  alarms: Alarm[];
+  /** Alarms fired recently, newest first */
+  fired: AlarmFiredPayload[];
+  /** Current time (Date.now()), updated every second */
+  now: number;
+  /** Create an alarm due after `duration` (e.g. "3h 12m"); resolves to an error message or null */
+  createAlarm: (label: string, duration: string, repeat?: AlarmRepeat) => Promise<string | null>;
+  /** Delete an alarm */
+  deleteAlarm: (id: string) => void;
+}
+
+// ============================================================================
+// Utility Functions
+// ============================================================================
+
+const DURATION_UNITS: Record<string, number> = { d: 86400, h: 3600, m: 60, s: 1 };
+
+/**
+ * Parses a duration like "3h 12m", "45m" or "1d 2h" into seconds.
+ * A bare number is read as minutes. Returns null if the text is not a duration.
+ *
+ * @param text - Duration text
+ * @returns Duration in seconds, or null
+ */
+export function parseDuration(text: string): number | null {
+  const trimmed = text.trim().toLowerCase();
+  if (/^\d+$/.test(trimmed)) {
+    return parseInt(trimmed, 10) * 60;
+  }
+
+  const parts = trimmed.match(/\d+\s*[dhms]/g);
+  if (!parts || parts.join('').replace(/\s/g, '') !== trimmed.replace(/\s/g, '')) {
+    return null;
+  }
+  return parts.reduce((total, part) => {
+    const unit = part.slice(-1);
+    return total + parseInt(part, 10) * DURATION_UNITS[unit];
+  }, 0);
+}
+
+/**
+ * Formats the time until an alarm as H:MM:SS, or "Xd HH:MM" beyond a day.
+ *
+ * @param ms - Milliseconds remaining
+ * @returns Formatted countdown
+ */
+export function formatCountdown(ms: number): string {
+  const totalSeconds = Math.max(0, Math.ceil(ms / 1000));
+  const days = Math.floor(totalSeconds / 86400);
+  const hours = Math.floor((totalSeconds % 86400) / 3600);
+  const minutes = Math.floor((totalSeconds % 3600) / 60);
+  const seconds = totalSeconds % 60;
+
+  if (days > 0) {
+    return `${days}d ${hours.toString().padStart(2, '0')}:${minutes.toString().padStart(2, '0')}`;
+  }
+  return `${hours}:${minutes.toString().padStart(2, '0')}:${seconds.toString().padStart(2, '0')}`;
+}
+
+// ============================================================================
+// Hook
+// ============================================================================
+
+/**
+ * Hook that follows the backend alarm scheduler.
+ *
+ * @returns Upcoming and fired alarms with create/delete functions
+ */
+export function useAlarms(): AlarmsResult {
+  const [alarms, setAlarms] = useState<Alarm[]>([]);
+  const [fired, setFired] = useState<AlarmFiredPayload[]>([]);
+  const [now, setNow] = useState(() => Date.now());
+
+  useEffect(() => {
+    let unlistenUpdated: UnlistenFn | null = null;
+    let unlistenFired: UnlistenFn | null = null;
+    let cancelled = false;
+
+    const setup = async () => {
+      try {
+        unlistenUpdated = await listen<Alarm[]>('alarms-updated', (event) => {
+          setAlarms(event.payload);
+        });
+        unlistenFired = await listen<AlarmFiredPayload>('alarm-fired', (event) => {
+          debug(`Alarms: '${event.payload.label}' fired`);
+          setFired((prev) => [event.payload, ...prev].slice(0, ALARM_WIDGET_DEFAULTS.MAX_ALARMS));
+        });
+
+        // Alarms caught up at startup may have fired before this webview loaded
+        const [upcoming, recent] = await Promise.all([
+          invoke<Alarm[]>('list_alarms', {}),
+          invoke<AlarmFiredPayload[]>('list_fired_alarms'),
+        ]);
+        if (!cancelled) {
+          setAlarms(upcoming);
+          setFired(recent.slice(0, ALARM_WIDGET_DEFAULTS.MAX_ALARMS));
+        }
+      } catch (err) {
+        debug(`Alarms: Failed to load alarms - ${err}`);
+      }
+
+      if (cancelled) {
+        unlistenUpdated?.();
+        unlistenFired?.();
+      }
+    };
+    setup();
+
+    const intervalId = setInterval(() => setNow(Date.now()), ALARM_WIDGET_DEFAULTS.UPDATE_INTERVAL_MS);
+
+    return () => {
+      cancelled = true;
+      clearInterval(intervalId);
+      unlistenUpdated?.();
+      unlistenFired?.();
+    };
+  }, []);
+
+  const createAlarm = useCallback(
+    async (label: string, duration: string, repeat?: AlarmRepeat): Promise<string | null> => {
+      const secs = parseDuration(duration);
+      if (secs === null || secs <= 0) {
+        return 'Enter a duration like 3h 12m';
+      }
+      try {
+        await invoke<Alarm>('create_alarm', {
+          label,
+          due: { type: 'in', secs },
+          repeat: repeat ?? null,
+        });
+        return null;
+      } catch (err) {
+        return String(err);
+      }
+    },
+    []
+  );
+
+  const deleteAlarm = useCallback((id: string) => {
+    invoke('delete_alarm', { id }).catch((err) => debug(`Alarms: delete failed - ${err}`));
+  }, []);
+
+  return { alarms, fired, now, createAlarm, deleteAlarm };
+}
@@ -1,14 +1,14 @@
-"use client";
-
 /**
  * useChronometer Hook
  *
- * Provides stopwatch functionality for the chronometer widget.
- * Controlled via hotkeys (Ctrl+T for toggle, Ctrl+Y for reset).
+ * Displays the backend's "chronometer" stopwatch for the chronometer widget.
+ * The timer service in the Rust backend owns the time and handles the hotkeys
+ * (Ctrl+T for toggle, Ctrl+Y for reset), so the chronometer keeps counting
+ * while the overlay is hidden and survives webview reloads and restarts.
  *
  * Features:
- * - Start/pause via toggle function or Tauri event
- * - Reset to 00:00:00 via reset function or Tauri event
+ * - Start/pause and reset via backend timer commands
+ * - Follows timer-changed events from the backend
  * - Shared singleton state across all widget instances
  * - HH:MM:SS formatted time display (caps at 99:59:59)
  * - Event-based synchronization for multiple instances
@@ -16,17 +16,22 @@
  * @feature 045-chronometer-widget
  */
 
-import { useState, useEffect, useRef, useCallback } from 'react';
+import { useState, useEffect } from 'react';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
+import { invoke } from '@tauri-apps/api/core';
 import { debug } from '@/lib/logger';
 import { chronometerEvents } from '@/lib/chronometerEvents';
+import type { TimerSnapshot } from '@/types/ipc';
 
 // ============================================================================
-// Persistence
+// Backend timer
 // ============================================================================
 
-/** localStorage key for chronometer state */
-const PERSISTENCE_KEY = 'chronometer-elapsed-ms';
+/** ID of the backend timer shown by the chronometer widget */
+const CHRONOMETER_TIMER_ID = 'chronometer';
+
+/** localStorage key used before the backend owned the chronometer */
+const LEGACY_PERSISTENCE_KEY = 'chronometer-elapsed-ms';
 
 // ============================================================================
 // Types
@@ -38,12 +43,13 @@ const PERSISTENCE_KEY = 'chronometer-elapsed-ms';
 interface ChronometerState {
   /** Whether the chronometer is currently running */
   isRunning: boolean;
-  /** Timestamp (Date.now()) when chronometer was last started/resumed */
-  lastTickTimestamp: number | null;
-  /** Accumulated elapsed time in milliseconds */
+  /** Time counted before the current run (from the backend) */
+  accumulatedMs: number;
+  /** Epoch milliseconds when the current run started, null while paused */
+  runningSinceMs: number | null;
+  /** Elapsed time in milliseconds (updated locally while running) */
   elapsedMs: number;
 }
-
 /**
  * Return type for the useChronometer hook.
  */
@@ -105,7 +111,8 @@ export function formatChronometerTime(ms: number): string {
  */
 let globalState: ChronometerState = {
   isRunning: false,
-  lastTickTimestamp: null,
+  accumulatedMs: 0,
+  runningSinceMs: null,
   elapsedMs: 0,
 };
 
@@ -122,8 +129,7 @@ let tauriListenersSetup = false;
 let eventEmitterListenersSetup = false;
 
 /** Unlisten functions for cleanup */
-let unlistenToggle: UnlistenFn | null = null;
-let unlistenReset: UnlistenFn | null = null;
+let unlistenTimerChanged: UnlistenFn | null = null;
 let unsubscribeToggle: (() => void) | null = null;
 let unsubscribeReset: (() => void) | null = null;
 
@@ -135,6 +141,14 @@ function setGlobalState(updater: (prev: ChronometerState) => ChronometerState):
   listeners.forEach((listener) => listener());
 }
 
+/**
+ * Elapsed time of the current state at the current time.
+ */
+function currentElapsedMs(state: ChronometerState): number {
+  const running = state.runningSinceMs !== null ? Math.max(0, Date.now() - state.runningSinceMs) : 0;
+  return Math.min(state.accumulatedMs + running, MAX_ELAPSED_MS);
+}
+
 /**
  * Starts the global update interval.
  */
@@ -142,13 +156,10 @@ function startGlobalInterval(): void {
   if (globalIntervalId !== null) return;
 
   globalIntervalId = setInterval(() => {
-    if (globalState.isRunning && globalState.lastTickTimestamp !== null) {
-      const now = Date.now();
-      const delta = now - globalState.lastTickTimestamp;
+    if (globalState.isRunning) {
       setGlobalState((prev) => ({
         ...prev,
-        elapsedMs: Math.min(prev.elapsedMs + delta, MAX_ELAPSED_MS),
-        lastTickTimestamp: now,
+        elapsedMs: currentElapsedMs(prev),
       }));
     }
   }, UPDATE_INTERVAL_MS);
@@ -165,142 +176,87 @@ function stopGlobalInterval(): void {
 }
 
 /**
- * Toggles the chronometer between running and paused states.
+ * Applies a snapshot of the backend chronometer timer.
  */
-function toggle(): void {
-  debug('Chronometer: toggle called');
+function applySnapshot(snapshot: TimerSnapshot): void {
+  setGlobalState(() => {
+    const next: ChronometerState = {
+      isRunning: snapshot.running,
+      accumulatedMs: snapshot.accumulatedMs,
+      runningSinceMs: snapshot.runningSinceMs,
+      elapsedMs: 0,
+    };
+    return { ...next, elapsedMs: currentElapsedMs(next) };
+  });
 
-  if (globalState.isRunning) {
-    // Pause: stop interval, preserve elapsed time
-    setGlobalState((prev) => ({
-      ...prev,
-      isRunning: false,
-      lastTickTimestamp: null,
-    }));
-    stopGlobalInterval();
-    debug('Chronometer: paused');
-  } else {
-    // Start/Resume: begin interval with current timestamp
-    setGlobalState((prev) => ({
-      ...prev,
-      isRunning: true,
-      lastTickTimestamp: Date.now(),
-    }));
+  if (snapshot.running) {
     startGlobalInterval();
-    debug('Chronometer: started');
+  } else {
+    stopGlobalInterval();
   }
 }
 
 /**
- * Persists the current elapsed time to localStorage.
+ * Runs a backend timer command on the chronometer and applies the result.
  */
-function persistElapsedMs(): void {
-  try {
-    if (typeof window !== 'undefined' && window.localStorage) {
-      localStorage.setItem(PERSISTENCE_KEY, globalState.elapsedMs.toString());
-      debug(`Chronometer: persisted elapsedMs=${globalState.elapsedMs}`);
-    }
-  } catch (err) {
-    debug(`Chronometer: failed to persist - ${err}`);
-  }
+function runTimerCommand(command: string): void {
+  invoke<TimerSnapshot>(command, { id: CHRONOMETER_TIMER_ID })
+    .then(applySnapshot)
+    .catch((err) => debug(`Chronometer: ${command} failed - ${err}`));
 }
 
 /**
- * Loads persisted elapsed time from localStorage.
- * Returns 0 if not found or on error.
+ * Toggles the chronometer between running and paused states.
  */
-function loadPersistedElapsedMs(): number {
-  try {
-    if (typeof window !== 'undefined' && window.localStorage) {
-      const stored = localStorage.getItem(PERSISTENCE_KEY);
-      if (stored !== null) {
-        const parsed = parseInt(stored, 10);
-        if (!isNaN(parsed) && parsed >= 0) {
-          debug(`Chronometer: loaded persisted elapsedMs=${parsed}`);
-          return Math.min(parsed, MAX_ELAPSED_MS);
-        }
-      }
-    }
-  } catch (err) {
-    debug(`Chronometer: failed to load persisted state - ${err}`);
-  }
-  return 0;
+function toggle(): void {
+  debug('Chronometer: toggle called');
+  runTimerCommand('toggle_timer');
 }
 
 /**
- * Pauses the chronometer and saves current state.
+ * Pauses the chronometer. The backend keeps its time.
  * Used when widget is closed.
  */
 function pauseAndSave(): void {
   debug('Chronometer: pauseAndSave called');
-
-  // Stop if running
-  if (globalState.isRunning) {
-    setGlobalState((prev) => ({
-      ...prev,
-      isRunning: false,
-      lastTickTimestamp: null,
-    }));
-    stopGlobalInterval();
-  }
-
-  // Persist current elapsed time
-  persistElapsedMs();
-  debug('Chronometer: paused and saved');
+  runTimerCommand('pause_timer');
 }
 
 /**
  * Resets the chronometer to 00:00:00 and stops it.
- * Also clears persisted state.
  */
 function reset(): void {
   debug('Chronometer: reset called');
-
-  setGlobalState(() => ({
-    isRunning: false,
-    lastTickTimestamp: null,
-    elapsedMs: 0,
-  }));
-  stopGlobalInterval();
-
-  // Clear persisted state
-  try {
-    if (typeof window !== 'undefined' && window.localStorage) {
-      localStorage.removeItem(PERSISTENCE_KEY);
-    }
-  } catch (err) {
-    debug(`Chronometer: failed to clear persisted state - ${err}`);
-  }
-
-  debug('Chronometer: reset to 00:00:00');
+  runTimerCommand('reset_timer');
 }
 
-/** Track if persisted state has been loaded */
-let persistedStateLoaded = false;
-
 /**
- * Loads persisted state on first hook mount.
- * Only called once to restore elapsedMs from previous session.
+ * Moves the elapsed time saved in localStorage by older versions into the
+ * backend chronometer. The key is removed once the backend has accepted it,
+ * or when the backend chronometer already has time of its own.
  */
-function loadPersistedState(): void {
-  if (persistedStateLoaded) return;
-  persistedStateLoaded = true;
-
-  const savedElapsedMs = loadPersistedElapsedMs();
-  if (savedElapsedMs > 0) {
-    setGlobalState((prev) => ({
-      ...prev,
-      elapsedMs: savedElapsedMs,
-      // Always start paused after restore
-      isRunning: false,
-      lastTickTimestamp: null,
-    }));
-    debug(`Chronometer: restored elapsedMs=${savedElapsedMs}, paused`);
+async function migrateLegacyElapsed(chronometer: TimerSnapshot): Promise<void> {
+  if (typeof window === 'undefined' || !window.localStorage) return;
+
+  const saved = localStorage.getItem(LEGACY_PERSISTENCE_KEY);
+  if (saved === null) return;
+
+  const elapsedMs = Math.floor(Number(saved));
+  const isFresh = !chronometer.running && chronometer.elapsedMs === 0;
+  if (isFresh && Number.isFinite(elapsedMs) && elapsedMs > 0) {
+    const snapshot = await invoke<TimerSnapshot>('set_timer_elapsed', {
+      id: CHRONOMETER_TIMER_ID,
+      elapsedMs: Math.min(elapsedMs, MAX_ELAPSED_MS),
+    });
+    applySnapshot(snapshot);
+    debug(`Chronometer: migrated saved elapsedMs=${snapshot.elapsedMs} to the backend`);
   }
+  localStorage.removeItem(LEGACY_PERSISTENCE_KEY);
 }
 
 /**
- * Sets up Tauri event listeners for chronometer hotkeys.
+ * Sets up Tauri event listeners for backend timer changes and loads
+ * the current chronometer state.
  * Only called once when the first hook instance mounts.
  */
 async function setupTauriListeners(): Promise<void> {
@@ -308,17 +264,24 @@ async function setupTauriListeners(): Promise<void> {
   tauriListenersSetup = true;
 
   try {
-    // Listen for chronometer start/pause hotkey from backend
-    unlistenToggle = await listen('chronometer-start-pause', () => {
-      debug('Chronometer: received chronometer-start-pause event from backend');
-      toggle();
+    // The hotkeys are handled by the backend timer service, which reports every change
+    unlistenTimerChanged = await listen<TimerSnapshot>('timer-changed', (event) => {
+      if (event.payload.id === CHRONOMETER_TIMER_ID) {
+        applySnapshot(event.payload);
+      }
     });
 
-    // Listen for chronometer reset hotkey from backend
-    unlistenReset = await listen('chronometer-reset', () => {
-      debug('Chronometer: received chronometer-reset event from backend');
-      reset();
-    });
+    const timers = await invoke<TimerSnapshot[]>('list_timers');
+    const chronometer = timers.find((timer) => timer.id === CHRONOMETER_TIMER_ID);
+    if (chronometer) {
+      applySnapshot(chronometer);
+      debug(`Chronometer: loaded backend state elapsedMs=${chronometer.elapsedMs}`);
+
+      // The elapsed time is stored by the backend now; the saved key is kept if this fails
+      await migrateLegacyElapsed(chronometer).catch((err) =>
+        debug(`Chronometer: failed to migrate saved time - ${err}`)
+      );
+    }
 
     debug('Chronometer: Tauri event listeners registered');
   } catch (err) {
@@ -357,10 +320,8 @@ function cleanupAllListeners(): void {
   if (listeners.size > 0) return; // Still have active instances
 
   // Cleanup Tauri listeners
-  unlistenToggle?.();
-  unlistenReset?.();
-  unlistenToggle = null;
-  unlistenReset = null;
+  unlistenTimerChanged?.();
+  unlistenTimerChanged = null;
   tauriListenersSetup = false;
 
   // Cleanup event emitter listeners
@@ -404,20 +365,11 @@ export function useChronometer(): ChronometerResult {
   // Local state that syncs with global state
   const [, forceUpdate] = useState({});
 
-  // Track if this is the first hook instance
-  const isFirstMount = useRef(true);
-
   // Subscribe to global state changes
   useEffect(() => {
     const listener = () => forceUpdate({});
     listeners.add(listener);
 
-    // Load persisted state only once (first hook instance ever)
-    if (isFirstMount.current) {
-      isFirstMount.current = false;
-      loadPersistedState();
-    }
-
     // Always try to setup event listeners - internal guards handle deduplication
     // This fixes React StrictMode remount issue where refs persist but listeners were cleaned up
     setupTauriListeners();
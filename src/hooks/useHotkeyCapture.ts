@@ -45,6 +45,11 @@ const DEBOUNCE_MS = 200;
  * @returns true if the event matches the binding
  */
 function matchesHotkey(event: KeyboardEvent, binding: HotkeyBinding): boolean {
+  // Sequence, hold and double-tap bindings are only evaluated by the backend
+  if (binding.next || (binding.trigger && binding.trigger !== 'press')) {
+    return false;
+  }
+
   // Case-insensitive key comparison for letter keys
   // (event.key is lowercase when ctrl is pressed, but binding.key is uppercase)
   const keyMatches = event.key.toLowerCase() === binding.key.toLowerCase();
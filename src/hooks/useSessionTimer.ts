@@ -12,6 +12,7 @@
  * - Updates elapsed time every 1000ms while session is running
  * - Provides formatted time string (HH:MM:SS with >24h support)
  * - Shared state across all timer widget instances
+ * - Resumes the running session (or shows the last one) from the recorded play history
  *
  * @feature 044-session-timer-widget
  */
@@ -20,6 +21,7 @@ import { useState, useEffect, useRef, useCallback } from 'react';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
 import { invoke } from '@tauri-apps/api/core';
 import { debug } from '@/lib/logger';
+import type { PlaySession } from '@/types/persistence-ipc';
 
 // ============================================================================
 // Types
@@ -30,6 +32,8 @@ import { debug } from '@/lib/logger';
  */
 interface ProcessEventPayload {
   process_name: string;
+  /** Target profile that matched (e.g. "LIVE", "PTU") */
+  profile_name: string;
   detected: boolean;
 }
 
@@ -161,15 +165,24 @@ async function setupEventListeners(): Promise<void> {
     // Check if process is already running (handles case where process
     // was detected before frontend was ready)
     const isAlreadyRunning = await invoke<boolean>('is_target_process_running');
+    // The recorded session history knows when the running (or last) session started
+    const [lastSession] = await invoke<PlaySession[]>('list_play_sessions', { limit: 1 }).catch(
+      () => [] as PlaySession[]
+    );
     if (isAlreadyRunning && !globalState.isRunning) {
       debug('Session timer: Process already running on mount, starting timer');
+      const startedAt =
+        lastSession && lastSession.endedAt === null ? Date.parse(lastSession.startedAt) : NaN;
+      const sessionStartTime = Number.isNaN(startedAt) ? Date.now() : startedAt;
       setGlobalState(() => ({
         isRunning: true,
-        sessionStartTime: Date.now(),
-        elapsedMs: 0,
+        sessionStartTime,
+        elapsedMs: Date.now() - sessionStartTime,
         lastSessionMs: 0,
       }));
       startGlobalInterval();
+    } else if (!isAlreadyRunning && lastSession && lastSession.endedAt !== null) {
+      setGlobalState((prev) => ({ ...prev, lastSessionMs: lastSession.durationSecs * 1000 }));
     }
 
     // Listen for target process detection
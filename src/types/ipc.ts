@@ -72,6 +72,11 @@ export interface AutoHideChangedPayload {
   reason: 'focus_lost' | 'focus_gained' | 'target_closed';
 }
 
+// target-focus-changed: the bound game window gained or lost focus (also while hidden)
+export interface TargetFocusChangedPayload {
+  focused: boolean;
+}
+
 // T008: TargetWindowInfo for get_target_window_info command response
 export interface TargetWindowInfo {
   pattern: string;
@@ -79,3 +84,227 @@ export interface TargetWindowInfo {
   focused: boolean;
   rect: WindowRect | null;
 }
+
+// SettingsReloadedPayload for settings-reloaded event (settings.toml hot-reload)
+export interface SettingsReloadedPayload {
+  changedKeys: string[];
+}
+
+// SettingsDiagnostic for validate_settings command response
+export interface SettingsDiagnostic {
+  key: string | null;
+  line: number | null;
+  severity: 'error' | 'warning';
+  message: string;
+  suggestion?: string;
+}
+
+// SettingsValidationReport for validate_settings command response
+export interface SettingsValidationReport {
+  settingsPath: string | null;
+  fileExists: boolean;
+  valid: boolean;
+  diagnostics: SettingsDiagnostic[];
+}
+
+// WindowCandidate - a window evaluated during target detection
+export interface WindowCandidate {
+  hwnd: number;
+  process_name: string;
+  window_class: string;
+  window_title: string;
+  is_top_level: boolean;
+}
+
+// SearchCriteria - detection rules of one target profile
+export interface SearchCriteria {
+  profile_name: string;
+  process_name: string;
+  window_class: string;
+  window_title: string;
+}
+
+// CriterionCheck - pass/fail of one criterion for a candidate window
+export interface CriterionCheck {
+  criterion: 'process' | 'class' | 'title';
+  expected: string;
+  actual: string;
+  passed: boolean;
+}
+
+// CandidateDiagnosis - a candidate checked against its closest profile
+export interface CandidateDiagnosis {
+  window: WindowCandidate;
+  profile_name: string;
+  checks: CriterionCheck[];
+  score: number;
+  valid: boolean;
+  selected: boolean;
+}
+
+// DetectionDiagnostics for diagnose_target_detection command response
+export interface DetectionDiagnostics {
+  success: boolean;
+  verdict: string;
+  matched_profile: string | null;
+  matched_window: WindowCandidate | null;
+  criteria: SearchCriteria[];
+  candidates: CandidateDiagnosis[];
+  detection_time_ms: number;
+}
+
+// Game.log events (game-session-started, game-location-changed, game-player-death,
+// game-quantum-travel, game-disconnected). Timestamps are the game's, in UTC.
+export interface GameSessionStartedPayload {
+  timestamp: string | null;
+  shard: string | null;
+  server: string | null;
+}
+
+export interface GameLocationChangedPayload {
+  timestamp: string | null;
+  location: string;
+  previousLocation: string | null;
+}
+
+/** The local player died (victim is their handle) */
+export interface GamePlayerDeathPayload {
+  timestamp: string | null;
+  victim: string;
+  killer: string;
+  zone: string;
+  weapon: string;
+  damageType: string;
+}
+
+export type QuantumPhase = 'selected' | 'started' | 'arrived';
+
+export interface GameQuantumTravelPayload {
+  timestamp: string | null;
+  phase: QuantumPhase;
+  destination: string | null;
+}
+
+export interface GameDisconnectedPayload {
+  timestamp: string | null;
+  reason: string | null;
+}
+
+// Backend timers (timer-changed, timer-finished, timer-removed and the *_timer commands)
+
+export type TimerKind = 'stopwatch' | 'countdown';
+
+export interface TimerSnapshot {
+  id: string;
+  kind: TimerKind;
+  running: boolean;
+  /** Elapsed time when the snapshot was taken */
+  elapsedMs: number;
+  /** While running, elapsed = accumulatedMs + (Date.now() - runningSinceMs) */
+  accumulatedMs: number;
+  runningSinceMs: number | null;
+  /** Countdown length and time left (null for stopwatches) */
+  durationMs: number | null;
+  remainingMs: number | null;
+  laps: number[];
+  finished: boolean;
+}
+
+export interface TimerRemovedPayload {
+  id: string;
+}
+
+// Alarms (alarm-fired, alarms-updated and the *_alarm(s) commands). Times are epoch ms.
+
+export type AlarmRepeat =
+  | { every: 'interval'; secs: number }
+  | { every: 'daily' }
+  | { every: 'weekly' };
+
+/** Due time of a new alarm: absolute, or relative to now */
+export type AlarmDue = { type: 'at'; atMs: number } | { type: 'in'; secs: number };
+
+export interface Alarm {
+  id: string;
+  label: string;
+  /** Next time the alarm fires */
+  dueAtMs: number;
+  /** null for one-shot alarms, which are removed once they fire */
+  repeat: AlarmRepeat | null;
+  createdAtMs: number;
+}
+
+export interface AlarmFiredPayload {
+  id: string;
+  label: string;
+  dueAtMs: number;
+  firedAtMs: number;
+  /** Fired after its due time, e.g. when the app was closed */
+  late: boolean;
+  /** Further occurrences that passed while the app was closed */
+  missed: number;
+  nextDueAtMs: number | null;
+}
+
+// Rules engine (rules.toml next to settings.toml; list_rules, reload_rules, dry_run_rules)
+
+export type RuleMode = 'interactive' | 'passthrough';
+
+/** Something a rule can react to */
+export type RuleEvent =
+  | { trigger: 'process-detected'; profile: string }
+  | { trigger: 'process-terminated'; profile: string }
+  | { trigger: 'focus-gained' }
+  | { trigger: 'focus-lost' }
+  | { trigger: 'hotkey'; action: string }
+  | { trigger: 'time-of-day'; at: string }
+  | { trigger: 'alarm'; label: string };
+
+export type RuleAction =
+  | { action: 'show' }
+  | { action: 'hide' }
+  | { action: 'set-mode'; mode: RuleMode }
+  | { action: 'switch-profile'; profile: string }
+  | { action: 'start-timer'; timer: string }
+  | { action: 'open-url'; url: string };
+
+export interface Rule {
+  name: string;
+  enabled: boolean;
+  when: Record<string, unknown> & { trigger: string };
+  if?: { visible?: boolean; mode?: RuleMode; process_running?: boolean; profile?: string };
+  then: RuleAction[];
+}
+
+/** State the rule conditions are checked against */
+export interface RuleContext {
+  visible: boolean;
+  mode: RuleMode;
+  processRunning: boolean;
+  profile: string;
+}
+
+export interface RuleEvaluation {
+  rule: string;
+  /** The actions run only when the condition holds */
+  conditionMet: boolean;
+  actions: RuleAction[];
+  descriptions: string[];
+}
+
+export interface RulesInfo {
+  path: string | null;
+  rules: Rule[];
+  /** Parse errors and rules skipped as invalid */
+  errors: string[];
+}
+
+export interface DryRunResult {
+  context: RuleContext;
+  evaluations: RuleEvaluation[];
+}
+
+/** Payload of open-browser-url */
+export interface OpenUrlPayload {
+  url: string;
+}
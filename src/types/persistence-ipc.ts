@@ -20,9 +20,34 @@ export interface LoadStateResult {
   success: boolean;
   state: PersistedState | null;
   windowContents: WindowContentFile[];
+  /** Schema migrations applied by the backend while loading */
+  migrations: MigrationRecord[];
+  /** Snapshot restored because state.json was corrupt */
+  recoveredFromSnapshot?: string;
   error?: string;
 }
 
+/**
+ * A single schema migration step applied to a persisted file.
+ */
+export interface MigrationRecord {
+  file: string;
+  fromVersion: number;
+  toVersion: number;
+  description: string;
+}
+
+/**
+ * A snapshot in the state backup ring (see list_state_snapshots).
+ */
+export interface SnapshotInfo {
+  id: string;
+  createdAt: string;
+  windowCount: number;
+  totalBytes: number;
+  valid: boolean;
+}
+
 /**
  * Result of saving state or window content to disk.
  */
@@ -40,6 +65,127 @@ export interface DeleteResult {
   error?: string;
 }
 
+/**
+ * A named layout profile (see list_profiles).
+ */
+export interface ProfileInfo {
+  name: string;
+  active: boolean;
+  isDefault: boolean;
+  lastModified?: string;
+}
+
+/**
+ * Result of a profile create/clone/rename/delete/switch operation.
+ */
+export interface ProfileResult {
+  success: boolean;
+  error?: string;
+}
+
+/**
+ * Payload of the `profile-switched` event.
+ */
+export interface ProfileSwitchedPayload {
+  previous: string;
+  current: string;
+}
+
+/**
+ * Result of exporting the active profile to a workspace archive.
+ */
+export interface ExportWorkspaceResult {
+  success: boolean;
+  windowCount: number;
+  error?: string;
+}
+
+/**
+ * Result of importing a workspace archive into the active profile.
+ * Reload via load_state after a successful import.
+ */
+export interface ImportWorkspaceResult {
+  success: boolean;
+  windowCount: number;
+  /** Window/widget IDs changed to avoid clashing with existing ones */
+  remappedIds: number;
+  error?: string;
+}
+
+/**
+ * Result of flush_persistence (write-behind queue flush).
+ */
+export interface FlushResult {
+  success: boolean;
+  /** Files written by this flush */
+  flushed: number;
+  /** Files that failed to write in this flush */
+  failed: number;
+  /** Writes still queued after this flush */
+  pending: number;
+  /** Saves dropped since startup because a newer save replaced them */
+  dropped: number;
+  totalWritten: number;
+  totalFailed: number;
+  error?: string;
+}
+
+/**
+ * A stored content revision of a window (see list_window_history).
+ */
+export interface RevisionInfo {
+  id: string;
+  createdAt: string;
+  sizeBytes: number;
+}
+
+/**
+ * Result of restoring a window content revision.
+ * Apply `content` to the open window so its next save keeps the restore.
+ */
+export interface RestoreRevisionResult {
+  success: boolean;
+  content: WindowContentFile | null;
+  error?: string;
+}
+
+/**
+ * A recorded game session (see list_play_sessions).
+ * `endedAt` is null while the session is running; `recovered` sessions were
+ * closed at the last heartbeat after a crash.
+ */
+export interface PlaySession {
+  id: string;
+  profileName: string;
+  processName: string;
+  startedAt: string;
+  endedAt: string | null;
+  durationSecs: number;
+  recovered: boolean;
+}
+
+/**
+ * Play time of one day, or of one week starting on Monday.
+ */
+export interface PlayTimeTotal {
+  /** Local date (YYYY-MM-DD) the period starts on */
+  periodStart: string;
+  sessions: number;
+  totalSecs: number;
+}
+
+/**
+ * Result of get_play_session_stats. Days and weeks are oldest first and
+ * include periods without sessions.
+ */
+export interface PlaySessionStats {
+  totalSessions: number;
+  totalSecs: number;
+  longestSession: PlaySession | null;
+  days: PlayTimeTotal[];
+  weeks: PlayTimeTotal[];
+}
+
 // ============================================================================
 // Re-exports for convenience
 // ============================================================================
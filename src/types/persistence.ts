@@ -14,7 +14,7 @@
 
 import type { WidgetType as WidgetTypeFromWidgets } from './widgets';
 
-export const CURRENT_STATE_VERSION = 1;
+export const CURRENT_STATE_VERSION = 2;
 
 // ============================================================================
 // Persisted State (state.json)
@@ -71,6 +71,8 @@ export interface WindowFlags {
 // ============================================================================
 
 export interface WindowContentFile {
+  /** Schema version (stamped by the backend on save) */
+  version?: number;
   windowId: string;
   type: WindowType;
   content: NotesContent | DrawContent | BrowserPersistedContent | FileViewerPersistedContent;
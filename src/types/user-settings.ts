@@ -21,14 +21,21 @@ export interface UserSettings {
    */
   startMinimized: boolean;
 
+  /** Active layout profile name (managed by the backend via switch_profile) */
+  activeProfile?: string;
+
   /** Last modified timestamp (ISO 8601) */
   lastModified: string;
 }
 
 /**
- * Hotkey bindings for overlay control
+ * Hotkey bindings for overlay control, keyed by action ID
+ * (see list_hotkey_actions for all registered actions)
  */
 export interface HotkeySettings {
+  /** Bindings of additional registered actions */
+  [actionId: string]: HotkeyBinding;
+
   /** Toggle overlay visibility (default: F3) */
   toggleVisibility: HotkeyBinding;
 
@@ -42,6 +49,26 @@ export interface HotkeySettings {
   chronometerReset: HotkeyBinding;
 }
 
+/**
+ * Hotkey action registered in the backend (list_hotkey_actions response)
+ */
+export interface HotkeyAction {
+  /** Action ID, also the key in HotkeySettings */
+  id: string;
+
+  /** Event emitted when the hotkey is pressed */
+  event: string;
+
+  /** Human-readable description */
+  description: string;
+
+  /** Binding used when none is configured */
+  defaultBinding: HotkeyBinding | null;
+
+  /** Minimum time between two triggers (milliseconds) */
+  debounceMs: number;
+}
+
 /**
  * Single hotkey binding configuration
  */
@@ -60,8 +87,25 @@ export interface HotkeyBinding {
 
   /** Alt modifier required */
   alt: boolean;
+
+  /** How the key triggers the action (default: press) */
+  trigger?: HotkeyTrigger;
+
+  /** Second key of a two-step sequence, e.g. "N" in "Ctrl+K, N" */
+  next?: HotkeyBinding;
+
+  /** Sequence, hold or double-tap window in milliseconds (default per trigger) */
+  timeoutMs?: number;
 }
 
+/**
+ * How a hotkey binding triggers its action
+ * - press: when the key goes down
+ * - hold: once the key has been held down for the timeout (default 500ms)
+ * - doubleTap: on the second press within the timeout (default 300ms)
+ */
+export type HotkeyTrigger = "press" | "hold" | "doubleTap";
+
 /**
  * Default user settings
  */
@@ -99,6 +143,7 @@ export const DEFAULT_USER_SETTINGS: UserSettings = {
   },
   autoStart: false,
   startMinimized: false,
+  activeProfile: "default",
   lastModified: "",
 };
 
@@ -109,6 +154,47 @@ export interface LoadUserSettingsResult {
   success: boolean;
   settings: UserSettings | null;
   error?: string;
+  /** Problems in the stored hotkeys (bindings with errors were reset to defaults) */
+  hotkeyConflicts?: HotkeyConflict[];
+}
+
+/**
+ * Kind of hotkey binding problem
+ */
+export type HotkeyConflictKind =
+  | "duplicate"
+  | "invalidKey"
+  | "keyMismatch"
+  | "reserved"
+  | "gameKey"
+  | "invalidTrigger";
+
+/**
+ * A single problem found in the hotkey bindings
+ */
+export interface HotkeyConflict {
+  kind: HotkeyConflictKind;
+
+  /** Errors block the update, warnings are informational */
+  severity: "error" | "warning";
+
+  /** Action IDs involved (more than one for duplicates) */
+  actions: string[];
+
+  /** Binding label, e.g. "Ctrl+T" */
+  binding: string;
+
+  message: string;
+}
+
+/**
+ * Result of update_hotkeys / validate_hotkey_bindings
+ */
+export interface HotkeyValidationReport {
+  /** False if any conflict is an error (the bindings were not applied) */
+  valid: boolean;
+
+  conflicts: HotkeyConflict[];
 }
 
 /**
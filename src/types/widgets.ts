@@ -14,7 +14,7 @@
 /**
  * Widget content types - extensible for future widgets.
  */
-export type WidgetType = 'clock' | 'timer' | 'chronometer';
+export type WidgetType = 'clock' | 'timer' | 'chronometer' | 'alarm';
 
 /**
  * Runtime widget instance with full state.
@@ -196,3 +196,21 @@ export const CHRONOMETER_WIDGET_DEFAULTS = {
   /** Minimum font size for chronometer display */
   MIN_FONT_SIZE: 12,
 } as const;
+
+/**
+ * Alarm widget specific defaults.
+ */
+export const ALARM_WIDGET_DEFAULTS = {
+  /** Default alarm widget width */
+  WIDTH: 260,
+  /** Default alarm widget height */
+  HEIGHT: 160,
+  /** Default alarm widget opacity */
+  OPACITY: 0.8,
+  /** Countdown update interval in ms */
+  UPDATE_INTERVAL_MS: 1000,
+  /** Upcoming alarms shown */
+  MAX_ALARMS: 5,
+  /** How long a fired alarm stays highlighted in ms */
+  FIRED_HIGHLIGHT_MS: 60_000,
+} as const;
@@ -56,9 +56,9 @@ export function validateHotkeyBinding(
 }
 
 /**
- * Format hotkey for display
+ * Format hotkey for display (same format as HotkeyBinding::label in the backend)
  * @param binding The hotkey binding
- * @returns Human-readable string like "Ctrl+Shift+O" or "F3"
+ * @returns Human-readable string like "Ctrl+Shift+O", "Ctrl+K, N" or "Hold F3"
  */
 export function formatHotkey(binding: HotkeyBinding): string {
   const parts: string[] = [];
@@ -66,7 +66,12 @@ export function formatHotkey(binding: HotkeyBinding): string {
   if (binding.shift) parts.push("Shift");
   if (binding.alt) parts.push("Alt");
   parts.push(binding.key);
-  return parts.join("+");
+
+  let label = parts.join("+");
+  if (binding.trigger === "hold") label = `Hold ${label}`;
+  if (binding.trigger === "doubleTap") label = `Double-tap ${label}`;
+  if (binding.next) label = `${label}, ${formatHotkey(binding.next)}`;
+  return label;
 }
 
 /**